@@ -37,6 +37,7 @@
             openssl
             pkg-config
             zenity # Required for Rust rfd lib
+            alsa-lib # Required for Rust midir lib
 
             # GUI libs
             libxkbcommon
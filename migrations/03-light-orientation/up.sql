@@ -0,0 +1,8 @@
+ALTER TABLE Lights ADD COLUMN mounting varchar(16) NOT NULL DEFAULT 'Hung';
+ALTER TABLE Lights ADD COLUMN mounting_rotation_x FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN mounting_rotation_y FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN mounting_rotation_z FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN pan_offset FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN tilt_offset FLOAT NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN pan_inverted BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE Lights ADD COLUMN tilt_inverted BOOLEAN NOT NULL DEFAULT 0;
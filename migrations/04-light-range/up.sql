@@ -0,0 +1,2 @@
+ALTER TABLE Lights ADD COLUMN pan_range FLOAT NOT NULL DEFAULT 540;
+ALTER TABLE Lights ADD COLUMN tilt_range FLOAT NOT NULL DEFAULT 270;
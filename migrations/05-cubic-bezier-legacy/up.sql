@@ -0,0 +1,3 @@
+-- Curves saved before this migration were evaluated as a blend of two quadratic Beziers, keep them that way.
+-- New curves are inserted with legacy_blend set to 0
+ALTER TABLE CubicBezierCurves ADD COLUMN legacy_blend BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,13 @@
+CREATE TABLE Splines (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name varchar(255)
+);
+
+CREATE TABLE SplineWaypoints (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  spline_id INTEGER NOT NULL REFERENCES Splines(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  x FLOAT,
+  y FLOAT,
+  z FLOAT
+);
@@ -0,0 +1,42 @@
+CREATE TABLE Circles (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  centre_x FLOAT,
+  centre_y FLOAT,
+  centre_z FLOAT,
+  radius FLOAT,
+  normal_x FLOAT,
+  normal_y FLOAT,
+  normal_z FLOAT,
+  start_angle FLOAT,
+  sweep FLOAT,
+  name varchar(255)
+);
+
+CREATE TABLE Arcs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  centre_x FLOAT,
+  centre_y FLOAT,
+  centre_z FLOAT,
+  radius FLOAT,
+  normal_x FLOAT,
+  normal_y FLOAT,
+  normal_z FLOAT,
+  start_angle FLOAT,
+  sweep FLOAT,
+  name varchar(255)
+);
+
+CREATE TABLE Ellipses (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  centre_x FLOAT,
+  centre_y FLOAT,
+  centre_z FLOAT,
+  radius_1 FLOAT,
+  radius_2 FLOAT,
+  normal_x FLOAT,
+  normal_y FLOAT,
+  normal_z FLOAT,
+  start_angle FLOAT,
+  sweep FLOAT,
+  name varchar(255)
+);
@@ -0,0 +1,13 @@
+CREATE TABLE CompositePaths (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name varchar(255)
+);
+
+-- segment_kind says which table segment_id is in, e.g. 'Line' for the Lines table
+CREATE TABLE CompositePathSegments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  composite_path_id INTEGER NOT NULL REFERENCES CompositePaths(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  segment_kind varchar(32) NOT NULL,
+  segment_id INTEGER NOT NULL
+);
@@ -0,0 +1,113 @@
+-- Every kind of path is stored in one table. The shape of the path is in geometry as JSON,
+-- which is the path serialised by serde without it's id, name, kind or segments
+CREATE TABLE Paths (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name varchar(255) NOT NULL,
+  kind varchar(32) NOT NULL,
+  geometry TEXT NOT NULL,
+  created DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  modified DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The paths a composite path is made of, in order
+CREATE TABLE PathSegments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  path_id INTEGER NOT NULL REFERENCES Paths(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  segment_id INTEGER NOT NULL REFERENCES Paths(id)
+);
+
+-- Every kind of path shares the same ids now, so remember which new id each old row gets
+CREATE TABLE LegacyPaths (
+  new_id INTEGER PRIMARY KEY AUTOINCREMENT,
+  kind varchar(32) NOT NULL,
+  old_id INTEGER NOT NULL,
+  name varchar(255) NOT NULL,
+  geometry TEXT NOT NULL
+);
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Line', id, IFNULL(name, ''), json_object(
+  'start', json_object('x', start_x, 'y', start_y, 'z', start_z),
+  'end', json_object('x', end_x, 'y', end_y, 'z', end_z)
+) FROM Lines ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Bezier', id, IFNULL(name, ''), json_object(
+  'start', json_object('x', start_x, 'y', start_y, 'z', start_z),
+  'midpoint', json_object('x', midpoint_x, 'y', midpoint_y, 'z', midpoint_z),
+  'end', json_object('x', end_x, 'y', end_y, 'z', end_z)
+) FROM BezierCurves ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'CubicBezier', id, IFNULL(name, ''), json_object(
+  'start', json_object('x', start_x, 'y', start_y, 'z', start_z),
+  'end', json_object('x', end_x, 'y', end_y, 'z', end_z),
+  'handle_1', json_object('x', handle_1_x, 'y', handle_1_y, 'z', handle_1_z),
+  'handle_2', json_object('x', handle_2_x, 'y', handle_2_y, 'z', handle_2_z),
+  'legacy_blend', json(CASE WHEN legacy_blend THEN 'true' ELSE 'false' END)
+) FROM CubicBezierCurves ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Spline', id, IFNULL(name, ''), json_object(
+  'waypoints', (
+    SELECT json_group_array(json_object('x', x, 'y', y, 'z', z) ORDER BY position)
+    FROM SplineWaypoints WHERE spline_id = Splines.id
+  )
+) FROM Splines ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Circle', id, IFNULL(name, ''), json_object(
+  'centre', json_object('x', centre_x, 'y', centre_y, 'z', centre_z),
+  'radius', radius,
+  'normal', json_object('x', normal_x, 'y', normal_y, 'z', normal_z),
+  'start_angle', start_angle,
+  'sweep', sweep
+) FROM Circles ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Arc', id, IFNULL(name, ''), json_object(
+  'centre', json_object('x', centre_x, 'y', centre_y, 'z', centre_z),
+  'radius', radius,
+  'normal', json_object('x', normal_x, 'y', normal_y, 'z', normal_z),
+  'start_angle', start_angle,
+  'sweep', sweep
+) FROM Arcs ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'Ellipse', id, IFNULL(name, ''), json_object(
+  'centre', json_object('x', centre_x, 'y', centre_y, 'z', centre_z),
+  'radius_1', radius_1,
+  'radius_2', radius_2,
+  'normal', json_object('x', normal_x, 'y', normal_y, 'z', normal_z),
+  'start_angle', start_angle,
+  'sweep', sweep
+) FROM Ellipses ORDER BY id;
+
+INSERT INTO LegacyPaths (kind, old_id, name, geometry)
+SELECT 'CompositePath', id, IFNULL(name, ''), json_object() FROM CompositePaths ORDER BY id;
+
+INSERT INTO Paths (id, name, kind, geometry)
+SELECT new_id, name, kind, geometry FROM LegacyPaths ORDER BY new_id;
+
+-- Segments that referred to paths that are gone were already being left out, so they're dropped here
+INSERT INTO PathSegments (path_id, position, segment_id)
+SELECT composite.new_id, CompositePathSegments.position, segment.new_id
+FROM CompositePathSegments
+JOIN LegacyPaths AS composite
+  ON composite.kind = 'CompositePath' AND composite.old_id = CompositePathSegments.composite_path_id
+JOIN LegacyPaths AS segment
+  ON segment.kind = CompositePathSegments.segment_kind AND segment.old_id = CompositePathSegments.segment_id
+ORDER BY CompositePathSegments.composite_path_id, CompositePathSegments.position;
+
+DROP TABLE LegacyPaths;
+DROP TABLE CompositePathSegments;
+DROP TABLE CompositePaths;
+DROP TABLE SplineWaypoints;
+DROP TABLE Splines;
+DROP TABLE Lines;
+DROP TABLE BezierCurves;
+DROP TABLE CubicBezierCurves;
+DROP TABLE Circles;
+DROP TABLE Arcs;
+DROP TABLE Ellipses;
@@ -1,16 +1,31 @@
-use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
 
 use egui_notify::Toasts;
 
 use crate::components::add_light_window::AddLightWindow;
+use crate::components::add_path::add_arc::AddArcWindow;
 use crate::components::add_path::add_bezier::AddBezierWindow;
+use crate::components::add_path::add_circle::AddCircleWindow;
+use crate::components::add_path::add_composite_path::AddCompositePathWindow;
 use crate::components::add_path::add_cubic_bezier::AddCubicBezierWindow;
+use crate::components::add_path::add_ellipse::AddEllipseWindow;
 use crate::components::add_path::add_line_window::AddLineWindow;
+use crate::components::add_path::add_spline::AddSplineWindow;
+use crate::components::csv_window::{export_csv, ImportCsvWindow};
 use crate::components::debug_point_at::DebugPointAt;
+use crate::components::import_model_window::ImportModelWindow;
+use crate::components::import_svg_window::ImportSvgWindow;
+use crate::components::manage_window::ManageWindow;
 use crate::components::output_section::OutputSection;
+use crate::components::plan_views::PlanViewsWindow;
 use crate::components::preferences::Preferences;
-use crate::config::ConfigFile;
+use crate::components::preview::Preview;
+use crate::components::project_menu::project_menu;
+use crate::config::{config_path, default_database_path, ConfigFile, CorruptConfig};
 use crate::db::Database;
+use crate::import::csv::CsvTable;
 
 pub struct GlobalState {
     pub database: Database,
@@ -20,35 +35,110 @@ pub struct GlobalState {
 
 impl Default for GlobalState {
     fn default() -> Self {
-        let mut data_path = dirs::data_dir().expect("Could not find OS data directory");
-        data_path.push("two_mover_3_pos");
+        let db_path = default_database_path().expect("Failed to create directories for database");
+        let config_path = config_path().expect("Failed to create directories for config file");
+
+        let mut toasts = Toasts::default();
 
-        fs::create_dir_all(&data_path).expect("Failed to create directories for database");
+        // A config file that can't be understood has been backed up, so it's safe to start again with defaults
+        let config_file = match ConfigFile::new(config_path.clone()) {
+            Ok(config_file) => config_file,
+            Err(e) if e.is::<CorruptConfig>() => {
+                toasts
+                    .error(format!("{e}\nDefault settings are being used instead"))
+                    .duration(None);
+                ConfigFile::reset(config_path).expect("Failed to write default config file")
+            }
+            Err(e) => panic!("Failed to create and/or read config file: {e}"),
+        };
 
-        let mut db_path = data_path.clone();
-        db_path.push("database");
-        db_path.set_extension("db");
+        // Carry on from the last project, falling back to the default database if it's gone
+        let last_project = config_file.projects().last_opened.clone();
+        let database = match last_project {
+            // Opening a database creates it, so a project that's been moved or deleted would come back empty
+            Some(project) if !project.exists() => {
+                toasts.error(format!(
+                    "The last project {} has been moved or deleted",
+                    project.display()
+                ));
+                Database::new(db_path)
+            }
+            Some(project) => match Database::open(project.clone()) {
+                Ok(database) => database,
+                Err(e) => {
+                    toasts.error(format!(
+                        "Failed to open the last project {}: {e}",
+                        project.display()
+                    ));
+                    Database::new(db_path)
+                }
+            },
+            None => Database::new(db_path),
+        };
 
-        let mut config_path = data_path.clone();
-        config_path.push("config");
-        config_path.set_extension("toml");
         Self {
-            database: Database::new(db_path),
-            config_file: ConfigFile::new(config_path)
-                .expect("Failed to create and/or read config file"),
-            toasts: Toasts::default(),
+            database,
+            config_file,
+            toasts,
+        }
+    }
+}
+
+impl GlobalState {
+    /// Switches to a different project file that already exists, and remembers it in the config
+    pub fn open_project(&mut self, project: PathBuf) -> Result<()> {
+        // Opening a database creates it, which isn't what someone picking an old project wants
+        if !project.exists() {
+            bail!("There is no project at {}", project.display());
+        }
+        self.create_project(project)
+    }
+
+    /// Switches to a different project file, creating it if it doesn't exist, and remembers it in the config
+    pub fn create_project(&mut self, project: PathBuf) -> Result<()> {
+        self.database = Database::open(project.clone())?;
+
+        // The project is open whether or not it's remembered, so this doesn't fail opening it
+        let mut projects = self.config_file.projects().clone();
+        projects.opened(project);
+        if let Err(e) = self.config_file.write_projects(projects) {
+            self.toasts.warning(format!(
+                "Failed to add the project to the recent projects: {e}"
+            ));
         }
+        Ok(())
+    }
+
+    /// The name of the open project, from it's file name
+    pub fn project_name(&self) -> String {
+        self.database
+            .path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
     }
 }
 
+/// The egui app, built on top of the rest of this crate
 pub struct App {
     add_light_window: AddLightWindow,
     add_line_window: AddLineWindow,
     add_bezier_window: AddBezierWindow,
     add_cubic_bezier_window: AddCubicBezierWindow,
+    add_spline_window: AddSplineWindow,
+    add_circle_window: AddCircleWindow,
+    add_arc_window: AddArcWindow,
+    add_ellipse_window: AddEllipseWindow,
+    add_composite_path_window: AddCompositePathWindow,
     debug_point_at: DebugPointAt,
+    import_svg_window: ImportSvgWindow,
+    import_model_window: ImportModelWindow,
+    import_csv_window: ImportCsvWindow,
+    manage_window: ManageWindow,
     output_section: OutputSection,
+    plan_views_window: PlanViewsWindow,
     preferences: Preferences,
+    preview: Preview,
     global_state: GlobalState,
 }
 impl Default for App {
@@ -61,9 +151,20 @@ impl Default for App {
             add_line_window: AddLineWindow::new(),
             add_bezier_window: AddBezierWindow::new(),
             add_cubic_bezier_window: AddCubicBezierWindow::new(),
+            add_spline_window: AddSplineWindow::new(),
+            add_circle_window: AddCircleWindow::new(),
+            add_arc_window: AddArcWindow::new(),
+            add_ellipse_window: AddEllipseWindow::new(),
+            add_composite_path_window: AddCompositePathWindow::new(),
             debug_point_at: DebugPointAt::new(),
+            import_svg_window: ImportSvgWindow::new(),
+            import_model_window: ImportModelWindow::new(),
+            import_csv_window: ImportCsvWindow::new(),
+            manage_window: ManageWindow::new(),
             output_section: OutputSection::new(),
+            plan_views_window: PlanViewsWindow::new(),
             preferences: Preferences::new(),
+            preview: Preview::new(),
             global_state,
         }
     }
@@ -71,13 +172,61 @@ impl Default for App {
 
 impl App {
     /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Default::default()
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let app: Self = Default::default();
+        app.update_title(&cc.egui_ctx);
+        app
+    }
+
+    /// Shows the open project's name in the title bar
+    fn update_title(&self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!(
+            "Two Mover 3 Pos - {}",
+            self.global_state.project_name()
+        )));
+    }
+
+    /// Refreshes anything that was read from the last project's database
+    fn project_changed(&mut self, ctx: &egui::Context) {
+        self.output_section.project_changed(&mut self.global_state);
+        self.manage_window.shown = false;
+        self.preview.project_changed();
+        self.plan_views_window.project_changed();
+        self.update_title(ctx);
     }
 
     fn menu_bar(&mut self, ui: &mut egui::Ui) {
         egui::MenuBar::new().ui(ui, |ui| {
             ui.menu_button("File", |ui| {
+                if project_menu(ui, &mut self.global_state) {
+                    self.project_changed(ui.ctx());
+                }
+                ui.separator();
+
+                ui.menu_button("Import", |ui| {
+                    if ui.button("SVG paths").clicked() {
+                        self.import_svg_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("3D model paths (OBJ, glTF)").clicked() {
+                        self.import_model_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("CSV").clicked() {
+                        self.import_csv_window.shown = true;
+                        ui.close();
+                    }
+                });
+
+                ui.menu_button("Export CSV", |ui| {
+                    for table in CsvTable::ALL {
+                        if ui.button(table.name()).clicked() {
+                            export_csv(table, &mut self.global_state);
+                            ui.close();
+                        }
+                    }
+                });
+
                 if ui.button("Quit").clicked() {
                     ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
                 }
@@ -91,6 +240,14 @@ impl App {
             });
 
             ui.menu_button("Edit", |ui| {
+                if ui.button("Lights and paths").clicked() {
+                    self.manage_window.show(&mut self.global_state);
+                    ui.close();
+                }
+                if ui.button("Plan and section views").clicked() {
+                    self.plan_views_window.shown = true;
+                    ui.close();
+                }
                 if ui.button("Preferences").clicked() {
                     self.preferences.show(&mut self.global_state);
                     ui.close();
@@ -120,6 +277,26 @@ impl App {
                         self.add_cubic_bezier_window.shown = true;
                         ui.close();
                     }
+                    if ui.button("Spline").clicked() {
+                        self.add_spline_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("Circle").clicked() {
+                        self.add_circle_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("Arc").clicked() {
+                        self.add_arc_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("Ellipse").clicked() {
+                        self.add_ellipse_window.shown = true;
+                        ui.close();
+                    }
+                    if ui.button("Composite path").clicked() {
+                        self.add_composite_path_window.show(&mut self.global_state);
+                        ui.close();
+                    }
                 });
             });
         });
@@ -138,7 +315,18 @@ impl eframe::App for App {
         self.add_bezier_window.add(ctx, &mut self.global_state);
         self.add_cubic_bezier_window
             .add(ctx, &mut self.global_state);
+        self.add_spline_window.add(ctx, &mut self.global_state);
+        self.add_circle_window.add(ctx, &mut self.global_state);
+        self.add_arc_window.add(ctx, &mut self.global_state);
+        self.add_ellipse_window.add(ctx, &mut self.global_state);
+        self.add_composite_path_window
+            .add(ctx, &mut self.global_state);
         self.debug_point_at.add(ctx, &mut self.global_state);
+        self.import_svg_window.add(ctx, &mut self.global_state);
+        self.import_model_window.add(ctx, &mut self.global_state);
+        self.import_csv_window.add(ctx, &mut self.global_state);
+        self.manage_window.add(ctx, &mut self.global_state);
+        self.plan_views_window.add(ctx, &mut self.global_state);
         self.preferences.add(ctx, &mut self.global_state);
 
         // Show toasts
@@ -154,11 +342,19 @@ impl eframe::App for App {
             self.output_section.add(ctx, ui, &mut self.global_state);
         });
 
-        // Boast about being written in egui
+        // Boast about being written in egui, under the 3D preview
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                 powered_by_egui_and_eframe(ui);
                 egui::warn_if_debug_build(ui);
+
+                ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                    self.preview.add(
+                        ui,
+                        &mut self.global_state,
+                        self.output_section.move_settings(),
+                    );
+                });
             });
         });
     }
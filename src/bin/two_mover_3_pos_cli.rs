@@ -0,0 +1,9 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+// Works out moves from a terminal without opening the window, run with `--help` to see how
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+
+    two_mover_3_pos::run_cli(std::env::args().skip(1).collect())
+}
@@ -0,0 +1,968 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use serde::Serialize;
+
+use crate::{
+    config::{config_path, default_database_path, ConfigFile},
+    db::{Database, DatabaseError},
+    light::Light,
+    move_calculator::{
+        calculate_move, frames_to_commands, output_commands, CueFlag, CueNumbering, CueRange,
+        CueSettings, Easing, Frame, Keyframe, MoveOptions, Spread,
+    },
+    path::{Path, PathEnum},
+};
+
+const USAGE: &str = "\
+Works out moves for moving lights without opening the window
+
+Usage: two_mover_3_pos_cli [--project <file>] [--json] <command>
+
+Commands:
+  lights    List the lights in the project
+  paths     List the paths in the project
+  move      Work out the commands for a move, then print them or send them to the desk
+  help      Show this message
+
+Options:
+  --project <file>  The project to use, instead of the last one opened in the app
+  --json            Print the results as JSON
+
+Move options:
+  --light <name>    A light to move, give it more than once to move more lights
+  --path <name>     The path for the lights to follow
+  --frames <n>      How many frames to split the move in to (default 10)
+  --time <seconds>  How long the move takes (default 1)
+  --cue <number>    The cue number to record the move in to (default 1)
+  --cue-step <step> Record each frame as a point cue this far after the last (default 0.01)
+  --past-next-cue   Let point cues carry on past the next whole cue
+  --whole-cues      Record each frame as the next whole cue
+  --cue-parts       Record each frame as a part of the cue, up to 99
+  --protect <first>-<last>
+                    Stop if the move would record over any of these cues
+  --cue-list <n>    The cue list to record the move in to (default 1)
+  --label <text>    Label each cue with this and it's frame, e.g. <text> 3/10
+  --mark            Mark the lights in to position before the move, on it's first cue
+  --block           Make the first cue of the move a block cue
+  --loop            Link the last cue back to the first, so the move goes round and round
+  --raw-speed       Space the frames using the path's own spacing, not evenly along it
+  --fan             Spread the lights evenly along the whole path
+  --phase <percent>
+                    Each light trails the one before it by this much of the path
+  --phase-distance <metres>
+                    Each light trails the one before it by this distance along the path
+  --easing <name>   How the lights speed up and slow down: linear (default), ease-in,
+                    ease-out, ease-in-out or s-curve
+  --keyframes <time:position,...>
+                    A custom easing going through these points, in % of the move's
+                    time and % of the way along the path, e.g. 25:50,75:50 to pause
+  --send            Send the commands to the desk set in the OSC preferences
+
+Exit codes:
+  0  Success
+  2  The arguments weren't right
+  3  A light, path or project couldn't be found
+  4  The project or config file couldn't be read
+  5  The commands couldn't be sent to the desk
+";
+
+/// Something that stopped the CLI. Each kind has it's own exit code so scripts can tell them apart
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    NotFound(String),
+    Storage(anyhow::Error),
+    Osc(anyhow::Error),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::NotFound(_) => 3,
+            CliError::Storage(_) => 4,
+            CliError::Osc(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::NotFound(message) => write!(f, "{message}"),
+            CliError::Storage(e) => write!(f, "{e:#}"),
+            CliError::Osc(e) => write!(f, "{e:#}"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for CliError {
+    fn from(e: anyhow::Error) -> Self {
+        CliError::Storage(e)
+    }
+}
+
+impl From<DatabaseError> for CliError {
+    fn from(e: DatabaseError) -> Self {
+        CliError::Storage(e.into())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Storage(e.into())
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(e: serde_json::Error) -> Self {
+        CliError::Storage(e.into())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+    project: Option<PathBuf>,
+    json: bool,
+    command: Command,
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    Lights,
+    Paths,
+    Move(MoveArgs),
+}
+
+/// The same settings as the output section of the app
+#[derive(Debug, PartialEq)]
+struct MoveArgs {
+    lights: Vec<String>,
+    path: String,
+    options: MoveOptions,
+    cues: CueSettings,
+    send: bool,
+}
+
+/// What the `move` command prints as JSON
+#[derive(Serialize)]
+struct MoveOutput<'a> {
+    cue_list: u32,
+    cue_number: u32,
+    frames: &'a [Frame],
+    commands: &'a [String],
+    sent: bool,
+}
+
+/// The value that comes after an option, e.g. the file in `--project <file>`
+fn option_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    option: &str,
+) -> Result<&'a String, CliError> {
+    args.next()
+        .ok_or_else(|| CliError::Usage(format!("{option} needs a value after it")))
+}
+
+fn parse_number<T: FromStr>(value: &str, option: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("{option} should be a number, not {value}")))
+}
+
+/// Only one of `--fan`, `--phase` and `--phase-distance` can be used
+fn set_spread(spread: &mut Option<Spread>, new_spread: Spread) -> Result<(), CliError> {
+    if spread.replace(new_spread).is_some() {
+        return Err(CliError::Usage(
+            "Only one of --fan, --phase and --phase-distance can be used".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Only one of `--easing` and `--keyframes` can be used
+fn set_easing(easing: &mut Option<Easing>, new_easing: Easing) -> Result<(), CliError> {
+    if easing.replace(new_easing).is_some() {
+        return Err(CliError::Usage(
+            "Only one of --easing and --keyframes can be used".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_easing(value: &str) -> Result<Easing, CliError> {
+    match value {
+        "linear" => Ok(Easing::Linear),
+        "ease-in" => Ok(Easing::EaseIn),
+        "ease-out" => Ok(Easing::EaseOut),
+        "ease-in-out" => Ok(Easing::EaseInOut),
+        "s-curve" => Ok(Easing::SCurve),
+        other => Err(CliError::Usage(format!(
+            "--easing can be linear, ease-in, ease-out, ease-in-out or s-curve, not {other}"
+        ))),
+    }
+}
+
+/// Reads keyframes like `25:50,75:50`, where each is a % of the move's time then a % along the path
+fn parse_keyframes(value: &str) -> Result<Easing, CliError> {
+    let keyframes = value
+        .split(',')
+        .map(|keyframe| {
+            let Some((time, position)) = keyframe.split_once(':') else {
+                return Err(CliError::Usage(format!(
+                    "--keyframes should look like 25:50,75:50, not {value}"
+                )));
+            };
+            let time: f64 = parse_number(time.trim(), "--keyframes")?;
+            let position: f64 = parse_number(position.trim(), "--keyframes")?;
+            if !(0.0..=100.0).contains(&time) || !(0.0..=100.0).contains(&position) {
+                return Err(CliError::Usage(format!(
+                    "--keyframes can be from 0 to 100, not {keyframe}"
+                )));
+            }
+            Ok(Keyframe {
+                time: time / 100.0,
+                position: position / 100.0,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Easing::Custom(keyframes))
+}
+
+/// Only one of `--cue-step`, `--whole-cues` and `--cue-parts` can be used
+fn set_numbering(
+    numbering: &mut Option<CueNumbering>,
+    new_numbering: CueNumbering,
+) -> Result<(), CliError> {
+    if numbering.replace(new_numbering).is_some() {
+        return Err(CliError::Usage(
+            "Only one of --cue-step, --whole-cues and --cue-parts can be used".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Reads a range of cues like `10-20`
+fn parse_cue_range(value: &str) -> Result<CueRange, CliError> {
+    let Some((first, last)) = value.split_once('-') else {
+        return Err(CliError::Usage(format!(
+            "--protect should look like 10-20, not {value}"
+        )));
+    };
+    let range = CueRange {
+        first: parse_number(first.trim(), "--protect")?,
+        last: parse_number(last.trim(), "--protect")?,
+    };
+    if range.first > range.last {
+        return Err(CliError::Usage(format!(
+            "--protect should have the lowest cue first, not {value}"
+        )));
+    }
+    Ok(range)
+}
+
+/// Reads the arguments that come after the program's name
+fn parse_args(args: &[String]) -> Result<CliArgs, CliError> {
+    let mut project: Option<PathBuf> = None;
+    let mut json = false;
+    let mut command: Option<&str> = None;
+
+    let mut lights: Vec<String> = vec![];
+    let mut path: Option<String> = None;
+    let mut frames: u16 = 10;
+    let mut move_time: f64 = 1.0;
+    let mut cue_list: u32 = 1;
+    let mut cue_number: u32 = 1;
+    let mut label: Option<String> = None;
+    let mut first_cue_flag: Option<CueFlag> = None;
+    let mut loop_back = false;
+    let mut past_next_cue = false;
+    let mut constant_speed = true;
+    let mut numbering: Option<CueNumbering> = None;
+    let mut protected: Option<CueRange> = None;
+    let mut spread: Option<Spread> = None;
+    let mut easing: Option<Easing> = None;
+    let mut send = false;
+    // The first move option that was used, so it can be rejected if the command isn't `move`
+    let mut move_option: Option<&str> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => project = Some(PathBuf::from(option_value(&mut args, arg)?)),
+            "--json" => json = true,
+            "-h" | "--help" => command = Some("help"),
+            "--light" => lights.push(option_value(&mut args, arg)?.clone()),
+            "--path" => path = Some(option_value(&mut args, arg)?.clone()),
+            "--frames" => frames = parse_number(option_value(&mut args, arg)?, arg)?,
+            "--time" => move_time = parse_number(option_value(&mut args, arg)?, arg)?,
+            "--cue" => cue_number = parse_number(option_value(&mut args, arg)?, arg)?,
+            "--cue-step" => {
+                let step = parse_number(option_value(&mut args, arg)?, arg)?;
+                set_numbering(&mut numbering, CueNumbering::Decimal { step })?
+            }
+            "--whole-cues" => set_numbering(&mut numbering, CueNumbering::Whole)?,
+            "--cue-parts" => set_numbering(&mut numbering, CueNumbering::Parts)?,
+            "--protect" => protected = Some(parse_cue_range(option_value(&mut args, arg)?)?),
+            "--cue-list" => cue_list = parse_number(option_value(&mut args, arg)?, arg)?,
+            "--label" => label = Some(option_value(&mut args, arg)?.clone()),
+            "--mark" | "--block" => {
+                let flag = if arg == "--mark" {
+                    CueFlag::Mark
+                } else {
+                    CueFlag::Block
+                };
+                if first_cue_flag.replace(flag).is_some_and(|old| old != flag) {
+                    return Err(CliError::Usage(
+                        "Only one of --mark and --block can be used".to_string(),
+                    ));
+                }
+            }
+            "--loop" => loop_back = true,
+            "--past-next-cue" => past_next_cue = true,
+            "--raw-speed" => constant_speed = false,
+            "--fan" => set_spread(&mut spread, Spread::Fan)?,
+            "--phase" => {
+                let percent: f64 = parse_number(option_value(&mut args, arg)?, arg)?;
+                if !(0.0..=100.0).contains(&percent) {
+                    return Err(CliError::Usage(format!(
+                        "--phase can be from 0 to 100, not {percent}"
+                    )));
+                }
+                set_spread(&mut spread, Spread::Phase(percent / 100.0))?
+            }
+            "--phase-distance" => {
+                let distance: f64 = parse_number(option_value(&mut args, arg)?, arg)?;
+                if !(distance >= 0.0 && distance.is_finite()) {
+                    return Err(CliError::Usage(format!(
+                        "--phase-distance can't be negative, it was {distance}"
+                    )));
+                }
+                set_spread(&mut spread, Spread::PhaseDistance(distance))?
+            }
+            "--easing" => set_easing(&mut easing, parse_easing(option_value(&mut args, arg)?)?)?,
+            "--keyframes" => {
+                set_easing(&mut easing, parse_keyframes(option_value(&mut args, arg)?)?)?
+            }
+            "--send" => send = true,
+            option if option.starts_with('-') => {
+                return Err(CliError::Usage(format!("Unknown option {option}")));
+            }
+            other => {
+                if let Some(command) = command {
+                    return Err(CliError::Usage(format!(
+                        "Unexpected {other} after {command}, names with spaces need quotes around them"
+                    )));
+                }
+                command = Some(other);
+                continue;
+            }
+        }
+
+        if matches!(
+            arg.as_str(),
+            "--light"
+                | "--path"
+                | "--frames"
+                | "--time"
+                | "--cue"
+                | "--cue-step"
+                | "--past-next-cue"
+                | "--whole-cues"
+                | "--cue-parts"
+                | "--protect"
+                | "--cue-list"
+                | "--label"
+                | "--mark"
+                | "--block"
+                | "--loop"
+                | "--raw-speed"
+                | "--fan"
+                | "--phase"
+                | "--phase-distance"
+                | "--easing"
+                | "--keyframes"
+                | "--send"
+        ) {
+            move_option.get_or_insert(arg.as_str());
+        }
+    }
+
+    let command = match command {
+        None | Some("help") => Command::Help,
+        Some("lights") => Command::Lights,
+        Some("paths") => Command::Paths,
+        Some("move") => {
+            if frames == 0 {
+                return Err(CliError::Usage("--frames can't be 0".to_string()));
+            }
+            // Time travel doesn't exist!
+            if !(move_time >= 0.0 && move_time.is_finite()) {
+                return Err(CliError::Usage(format!(
+                    "--time can't be negative, it was {move_time}"
+                )));
+            }
+            // There's no cue 0 in EOS
+            if cue_number == 0 {
+                return Err(CliError::Usage("--cue can't be 0".to_string()));
+            }
+            if lights.is_empty() {
+                return Err(CliError::Usage(
+                    "move needs at least one --light".to_string(),
+                ));
+            }
+            let Some(path) = path else {
+                return Err(CliError::Usage("move needs a --path".to_string()));
+            };
+
+            // Checked now rather than after reading the project, so mistakes are found straight away
+            let cues = CueSettings {
+                cue_list,
+                first_cue: cue_number,
+                numbering: numbering.unwrap_or_default(),
+                protected,
+                label,
+                first_cue_flag,
+                loop_back,
+                past_next_cue,
+            };
+            cues.cue_numbers(frames as usize)
+                .map_err(|e| CliError::Usage(e.to_string()))?;
+
+            Command::Move(MoveArgs {
+                lights,
+                path,
+                options: MoveOptions {
+                    frames,
+                    move_time,
+                    constant_speed,
+                    spread: spread.unwrap_or_default(),
+                    easing: easing.unwrap_or_default(),
+                },
+                cues,
+                send,
+            })
+        }
+        Some(other) => return Err(CliError::Usage(format!("Unknown command {other}"))),
+    };
+
+    if let (Some(option), false) = (move_option, matches!(command, Command::Move(_))) {
+        return Err(CliError::Usage(format!("{option} only works with move")));
+    }
+
+    Ok(CliArgs {
+        project,
+        json,
+        command,
+    })
+}
+
+/// Reads the config file the app uses. Unlike the app, a broken config file is an error rather than being replaced
+fn open_config() -> Result<ConfigFile, CliError> {
+    Ok(ConfigFile::new(config_path()?)?)
+}
+
+/// Opens the project that was asked for, or the one the app would open
+fn open_database(project: Option<PathBuf>) -> Result<Database, CliError> {
+    if let Some(project) = project {
+        // Opening a database creates it, which isn't what someone who made a typo wants
+        if !project.exists() {
+            return Err(CliError::NotFound(format!(
+                "There is no project at {}",
+                project.display()
+            )));
+        }
+        return Ok(Database::open(project)?);
+    }
+
+    let project = match open_config()?.projects().last_opened.clone() {
+        Some(project) => project,
+        None => default_database_path()?,
+    };
+    Ok(Database::open(project)?)
+}
+
+/// Finds the only item called `name`, `kind` is what it's called in error messages
+fn find_named<T: Clone>(
+    items: &[T],
+    name: &str,
+    kind: &str,
+    name_of: impl Fn(&T) -> String,
+) -> Result<T, CliError> {
+    let mut matching = items.iter().filter(|item| name_of(item) == name);
+
+    match (matching.next(), matching.next()) {
+        (Some(item), None) => Ok(item.clone()),
+        (None, _) => Err(CliError::NotFound(format!(
+            "There is no {kind} called {name}"
+        ))),
+        (Some(_), Some(_)) => Err(CliError::Usage(format!(
+            "There's more than one {kind} called {name}, rename one of them first"
+        ))),
+    }
+}
+
+fn run_move(
+    database: &Database,
+    move_args: MoveArgs,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    let all_lights = database.get_lights()?;
+    let lights = move_args
+        .lights
+        .iter()
+        .map(|name| {
+            find_named(&all_lights, name, "light", |light: &Light| {
+                light.name.clone()
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let path = find_named(
+        &database.get_paths()?,
+        &move_args.path,
+        "path",
+        PathEnum::name,
+    )?;
+
+    let frames = calculate_move(&path, &lights, &move_args.options)
+        .map_err(|e| CliError::Usage(e.to_string()))?;
+    let commands =
+        frames_to_commands(&frames, &move_args.cues).map_err(|e| CliError::Usage(e.to_string()))?;
+
+    if move_args.send {
+        let osc = open_config()?.read()?.osc;
+        output_commands(&commands, &osc.connection()).map_err(|e| CliError::Osc(e.into()))?;
+    }
+
+    if json {
+        let output = MoveOutput {
+            cue_list: move_args.cues.cue_list,
+            cue_number: move_args.cues.first_cue,
+            frames: &frames,
+            commands: &commands,
+            sent: move_args.send,
+        };
+        writeln!(out, "{}", serde_json::to_string_pretty(&output)?)?;
+    } else if move_args.send {
+        writeln!(
+            out,
+            "Sent {} commands for cue {}/{}",
+            commands.len(),
+            move_args.cues.cue_list,
+            move_args.cues.first_cue
+        )?;
+    } else {
+        for command in &commands {
+            writeln!(out, "{command}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the command in `args`, writing it's results to `out`
+fn run(args: &[String], out: &mut impl Write) -> Result<(), CliError> {
+    let args = parse_args(args)?;
+    if args.command == Command::Help {
+        write!(out, "{USAGE}")?;
+        return Ok(());
+    }
+
+    let database = open_database(args.project)?;
+
+    match args.command {
+        Command::Help => {}
+        Command::Lights => {
+            let lights = database.get_lights()?;
+            if args.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&lights)?)?;
+            } else {
+                for light in lights {
+                    writeln!(
+                        out,
+                        "{} (address {}) at {:.3}, {:.3}, {:.3}",
+                        light.name,
+                        light.address,
+                        light.coordinates.x,
+                        light.coordinates.y,
+                        light.coordinates.z
+                    )?;
+                }
+            }
+        }
+        Command::Paths => {
+            let paths = database.get_paths()?;
+            if args.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&paths)?)?;
+            } else {
+                for path in paths {
+                    writeln!(
+                        out,
+                        "{} ({}, {:.3}m long)",
+                        path.name(),
+                        path.kind(),
+                        path.length()
+                    )?;
+                }
+            }
+        }
+        Command::Move(move_args) => run_move(&database, move_args, args.json, out)?,
+    }
+
+    Ok(())
+}
+
+/// Runs the command line interface with the arguments that come after the program's name.
+/// Errors are written to stderr, as JSON if `--json` was used
+pub fn run_cli(args: Vec<String>) -> ExitCode {
+    let json = args.iter().any(|arg| arg == "--json");
+
+    match run(&args, &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if json {
+                eprintln!(
+                    "{}",
+                    serde_json::json!({ "error": e.to_string(), "exit_code": e.exit_code() })
+                );
+            } else {
+                eprintln!("Error: {e}");
+                if let CliError::Usage(_) = e {
+                    eprintln!("Run with --help to see how to use this");
+                }
+            }
+
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector3d::Vector3d;
+
+    use crate::path::line::Line;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Makes a project file with one light and one line in it
+    fn test_project(directory: &tempfile::TempDir) -> PathBuf {
+        let project = directory.path().join("show.db");
+        let database = Database::open(project.clone()).unwrap();
+        database
+            .add_light(&Light {
+                name: "Spot 1".to_string(),
+                address: 101,
+                coordinates: Vector3d::new(0.0, 0.0, 5.0),
+                ..Default::default()
+            })
+            .unwrap();
+        database
+            .add_path(&PathEnum::Line(Line {
+                name: "Apron".to_string(),
+                ..Line::new(Vector3d::new(-2.0, 1.0, 0.0), Vector3d::new(2.0, 1.0, 0.0))
+            }))
+            .unwrap();
+
+        project
+    }
+
+    #[test]
+    fn test_parse_move() {
+        let parsed = parse_args(&args(&[
+            "--json",
+            "move",
+            "--light",
+            "Spot 1",
+            "--light",
+            "Spot 2",
+            "--path",
+            "Apron",
+            "--frames",
+            "20",
+            "--time",
+            "2.5",
+            "--cue",
+            "7",
+            "--whole-cues",
+            "--protect",
+            "30-40",
+            "--cue-list",
+            "2",
+            "--label",
+            "Sweep",
+            "--mark",
+            "--loop",
+            "--raw-speed",
+            "--phase",
+            "25",
+            "--keyframes",
+            "25:50, 75:50",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            parsed,
+            CliArgs {
+                project: None,
+                json: true,
+                command: Command::Move(MoveArgs {
+                    lights: vec!["Spot 1".to_string(), "Spot 2".to_string()],
+                    path: "Apron".to_string(),
+                    options: MoveOptions {
+                        frames: 20,
+                        move_time: 2.5,
+                        constant_speed: false,
+                        spread: Spread::Phase(0.25),
+                        easing: Easing::Custom(vec![
+                            Keyframe {
+                                time: 0.25,
+                                position: 0.5,
+                            },
+                            Keyframe {
+                                time: 0.75,
+                                position: 0.5,
+                            },
+                        ]),
+                    },
+                    cues: CueSettings {
+                        cue_list: 2,
+                        first_cue: 7,
+                        numbering: CueNumbering::Whole,
+                        protected: Some(CueRange {
+                            first: 30.0,
+                            last: 40.0,
+                        }),
+                        label: Some("Sweep".to_string()),
+                        first_cue_flag: Some(CueFlag::Mark),
+                        loop_back: true,
+                        past_next_cue: false,
+                    },
+                    send: false,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let usage_error =
+            |arguments: &[&str]| matches!(parse_args(&args(arguments)), Err(CliError::Usage(_)));
+
+        assert!(usage_error(&["move", "--light", "Spot 1"]));
+        assert!(usage_error(&["move", "--path", "Apron"]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--frames", "0"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--frames",
+            "100",
+            "--cue-parts"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--cue-step",
+            "0.0001"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--whole-cues",
+            "--cue-parts"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--protect",
+            "1.05-2"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--protect",
+            "20-10"
+        ]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--frames", "500"
+        ]));
+        assert!(parse_args(&args(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--frames",
+            "500",
+            "--past-next-cue"
+        ]))
+        .is_ok());
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--cue", "0"
+        ]));
+        assert!(usage_error(&["move", "--light"]));
+        assert!(usage_error(&["lights", "--send"]));
+        assert!(usage_error(&["lights", "--frames", "ten"]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--fan", "--phase", "10"
+        ]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--phase", "150"
+        ]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--easing", "bouncy"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--keyframes",
+            "50"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--keyframes",
+            "50:120"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--easing",
+            "ease-in",
+            "--keyframes",
+            "50:50"
+        ]));
+        assert!(usage_error(&[
+            "move", "--light", "Spot 1", "--path", "Apron", "--mark", "--block"
+        ]));
+        assert!(usage_error(&[
+            "move",
+            "--light",
+            "Spot 1",
+            "--path",
+            "Apron",
+            "--cue-list",
+            "1000"
+        ]));
+        assert!(usage_error(&["dance"]));
+        assert!(usage_error(&["move", "Spot", "1"]));
+        assert_eq!(parse_args(&[]).unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn test_move_json() {
+        let directory = tempfile::tempdir().unwrap();
+        let project = test_project(&directory);
+
+        let mut out: Vec<u8> = vec![];
+        run(
+            &args(&[
+                "--project",
+                project.to_str().unwrap(),
+                "--json",
+                "move",
+                "--light",
+                "Spot 1",
+                "--path",
+                "Apron",
+                "--frames",
+                "2",
+                "--cue",
+                "3",
+            ]),
+            &mut out,
+        )
+        .unwrap();
+
+        let output: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(output["cue_number"], 3);
+        assert_eq!(output["sent"], false);
+        assert_eq!(output["frames"].as_array().unwrap().len(), 2);
+        // Pan and tilt for the light, then recording the cue and it's follow for each frame
+        assert_eq!(output["commands"].as_array().unwrap().len(), 8);
+        assert_eq!(output["commands"][2], "Record Cue 1/3.00 Time 0.50");
+    }
+
+    #[test]
+    fn test_list_paths() {
+        let directory = tempfile::tempdir().unwrap();
+        let project = test_project(&directory);
+
+        let mut out: Vec<u8> = vec![];
+        run(
+            &args(&["--project", project.to_str().unwrap(), "paths"]),
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Apron (Line, 4.000m long)\n"
+        );
+    }
+
+    #[test]
+    fn test_not_found_exit_code() {
+        let directory = tempfile::tempdir().unwrap();
+        let project = test_project(&directory);
+        let project = project.to_str().unwrap();
+
+        let missing_light = run(
+            &args(&[
+                "--project",
+                project,
+                "move",
+                "--light",
+                "Spot 9",
+                "--path",
+                "Apron",
+            ]),
+            &mut vec![],
+        );
+        assert_eq!(missing_light.unwrap_err().exit_code(), 3);
+
+        let missing_project = run(
+            &args(&["--project", "not/a/project.db", "lights"]),
+            &mut vec![],
+        );
+        assert_eq!(missing_project.unwrap_err().exit_code(), 3);
+    }
+}
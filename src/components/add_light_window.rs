@@ -1,6 +1,12 @@
 use egui::{DragValue, Layout};
 
-use crate::{app::GlobalState, components::select_vec, light::Light};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    light::{Light, Mounting},
+};
 
 pub struct AddLightWindow {
     current_light: Light,
@@ -44,19 +50,7 @@ impl AddLightWindow {
             .fade_out(true)
             .open(&mut self.shown)
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    select_vec(ui, "Position: ", &mut self.current_light.coordinates);
-
-                    ui.horizontal(|ui| {
-                        ui.label("Name:");
-                        ui.text_edit_singleline(&mut self.current_light.name);
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label("Address:");
-                        ui.add(DragValue::new(&mut self.current_light.address));
-                    });
-                });
+                ui.vertical(|ui| light_fields(ui, &mut self.current_light));
                 ui.add_space(16.0);
 
                 ui.horizontal(|ui| {
@@ -78,3 +72,76 @@ impl AddLightWindow {
         }
     }
 }
+
+/// Shows inputs for all of the values of a light, apart from it's id
+pub fn light_fields(ui: &mut egui::Ui, light: &mut Light) {
+    select_vec(ui, "Position: ", &mut light.coordinates);
+
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut light.name);
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Address:");
+        ui.add(DragValue::new(&mut light.address));
+    });
+
+    select_mounting(ui, &mut light.mounting);
+
+    ui.horizontal(|ui| {
+        ui.label("Pan offset:");
+        ui.add(DragValue::new(&mut light.pan_offset).speed(0.1));
+        ui.label("°");
+        ui.checkbox(&mut light.pan_inverted, "Invert pan");
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Tilt offset:");
+        ui.add(DragValue::new(&mut light.tilt_offset).speed(0.1));
+        ui.label("°");
+        ui.checkbox(&mut light.tilt_inverted, "Invert tilt");
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Pan range:");
+        ui.add(
+            DragValue::new(&mut light.pan_range)
+                .range(0.0..=720.0)
+                .suffix("°"),
+        );
+        ui.label("Tilt range:");
+        ui.add(
+            DragValue::new(&mut light.tilt_range)
+                .range(0.0..=360.0)
+                .suffix("°"),
+        );
+    });
+}
+
+/// Shows a UI element to pick how a light is mounted, with rotation inputs when it's not hung or standing
+fn select_mounting(ui: &mut egui::Ui, selecting_mounting: &mut Mounting) {
+    ui.horizontal(|ui| {
+        ui.label("Mounting:");
+        egui::ComboBox::from_id_salt("light_mounting")
+            .selected_text(selecting_mounting.kind())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(selecting_mounting, Mounting::Hung, "Hung");
+                ui.selectable_value(selecting_mounting, Mounting::Standing, "Standing");
+                if ui
+                    .selectable_label(
+                        matches!(selecting_mounting, Mounting::Rotated(_)),
+                        "Rotated",
+                    )
+                    .clicked()
+                    && !matches!(selecting_mounting, Mounting::Rotated(_))
+                {
+                    *selecting_mounting = Mounting::Rotated(Vector3d::default());
+                }
+            });
+    });
+
+    if let Mounting::Rotated(rotation) = selecting_mounting {
+        select_vec(ui, "Rotation (°): ", rotation);
+    }
+}
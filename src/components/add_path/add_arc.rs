@@ -0,0 +1,106 @@
+use egui::{DragValue, Layout};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{arc::Arc, PathEnum},
+};
+
+pub struct AddArcWindow {
+    current_arc: Arc,
+    pub shown: bool,
+}
+
+impl AddArcWindow {
+    pub fn new() -> Self {
+        Self {
+            // Lots of arcs lie on the floor, so start with the plane facing up
+            current_arc: Arc {
+                normal: Vector3d::new(0.0, 0.0, 1.0),
+                sweep: 90.0,
+                ..Arc::default()
+            },
+            shown: false,
+        }
+    }
+
+    /// Writes the arc to the specified Database
+    fn save(app_state: &mut GlobalState, adding_arc: &Arc) {
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Arc(adding_arc.clone()));
+
+        match db_result {
+            Ok(_) => {
+                app_state
+                    .toasts
+                    .success(format!("Successfully added a new arc: {}", adding_arc.name));
+            }
+
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write arc to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the arc entry window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+
+        egui::Window::new("Add arc")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut self.shown)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| arc_fields(ui, &mut self.current_arc));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Add").clicked() {
+                            open = false;
+                            Self::save(app_state, &self.current_arc);
+                        }
+                    });
+                })
+            });
+
+        // Support using the close button defined with `.open()` above
+        if self.shown {
+            self.shown = open;
+        }
+    }
+}
+
+/// Shows inputs for all of the values of an arc, apart from it's id
+pub fn arc_fields(ui: &mut egui::Ui, arc: &mut Arc) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut arc.name);
+    });
+    select_vec(ui, "Centre: ", &mut arc.centre);
+    ui.horizontal(|ui| {
+        ui.label("Radius:");
+        ui.add(
+            DragValue::new(&mut arc.radius)
+                .speed(0.1)
+                .range(0.0..=f64::MAX),
+        );
+    });
+    // The direction the plane faces, e.g. up (0, 0, 1) for the floor
+    select_vec(ui, "Plane normal: ", &mut arc.normal);
+    ui.horizontal(|ui| {
+        ui.label("Start angle:");
+        ui.add(DragValue::new(&mut arc.start_angle).speed(1.0).suffix("°"));
+        ui.label("Sweep:");
+        ui.add(DragValue::new(&mut arc.sweep).speed(1.0).suffix("°"));
+    });
+}
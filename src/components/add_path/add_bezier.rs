@@ -1,6 +1,10 @@
 use egui::Layout;
 
-use crate::{app::GlobalState, components::select_vec, path::bezier::Bezier};
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{bezier::Bezier, PathEnum},
+};
 
 pub struct AddBezierWindow {
     current_bezier: Bezier,
@@ -16,7 +20,9 @@ impl AddBezierWindow {
     }
     /// Writes the line to the specified Database
     fn save(app_state: &mut GlobalState, adding_bezier: &Bezier) {
-        let db_result = app_state.database.add_bezier(adding_bezier);
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Bezier(adding_bezier.clone()));
 
         match db_result {
             Ok(_) => {
@@ -45,15 +51,7 @@ impl AddBezierWindow {
             .fade_out(true)
             .open(&mut self.shown)
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.label("Name:");
-                        ui.text_edit_singleline(&mut self.current_bezier.name);
-                    });
-                    select_vec(ui, "Start position: ", &mut self.current_bezier.start);
-                    select_vec(ui, "Midpoint: ", &mut self.current_bezier.midpoint);
-                    select_vec(ui, "End position: ", &mut self.current_bezier.end);
-                });
+                ui.vertical(|ui| bezier_fields(ui, &mut self.current_bezier));
                 ui.add_space(16.0);
 
                 ui.horizontal(|ui| {
@@ -75,3 +73,14 @@ impl AddBezierWindow {
         }
     }
 }
+
+/// Shows inputs for all of the values of a bezier curve, apart from it's id
+pub fn bezier_fields(ui: &mut egui::Ui, bezier: &mut Bezier) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut bezier.name);
+    });
+    select_vec(ui, "Start position: ", &mut bezier.start);
+    select_vec(ui, "Midpoint: ", &mut bezier.midpoint);
+    select_vec(ui, "End position: ", &mut bezier.end);
+}
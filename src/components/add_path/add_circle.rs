@@ -0,0 +1,108 @@
+use egui::{DragValue, Layout};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{circle::Circle, PathEnum},
+};
+
+pub struct AddCircleWindow {
+    current_circle: Circle,
+    pub shown: bool,
+}
+
+impl AddCircleWindow {
+    pub fn new() -> Self {
+        Self {
+            // Most circles lie on the floor, so start with the plane facing up
+            current_circle: Circle {
+                normal: Vector3d::new(0.0, 0.0, 1.0),
+                ..Circle::default()
+            },
+            shown: false,
+        }
+    }
+
+    /// Writes the circle to the specified Database
+    fn save(app_state: &mut GlobalState, adding_circle: &Circle) {
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Circle(adding_circle.clone()));
+
+        match db_result {
+            Ok(_) => {
+                app_state.toasts.success(format!(
+                    "Successfully added a new circle: {}",
+                    adding_circle.name
+                ));
+            }
+
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write circle to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the circle entry window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+
+        egui::Window::new("Add circle")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut self.shown)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| circle_fields(ui, &mut self.current_circle));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Add").clicked() {
+                            open = false;
+                            Self::save(app_state, &self.current_circle);
+                        }
+                    });
+                })
+            });
+
+        // Support using the close button defined with `.open()` above
+        if self.shown {
+            self.shown = open;
+        }
+    }
+}
+
+/// Shows inputs for all of the values of a circle, apart from it's id
+pub fn circle_fields(ui: &mut egui::Ui, circle: &mut Circle) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut circle.name);
+    });
+    select_vec(ui, "Centre: ", &mut circle.centre);
+    ui.horizontal(|ui| {
+        ui.label("Radius:");
+        ui.add(
+            DragValue::new(&mut circle.radius)
+                .speed(0.1)
+                .range(0.0..=f64::MAX),
+        );
+    });
+    // The direction the plane faces, e.g. up (0, 0, 1) for the floor
+    select_vec(ui, "Plane normal: ", &mut circle.normal);
+    ui.horizontal(|ui| {
+        ui.label("Start angle:");
+        ui.add(
+            DragValue::new(&mut circle.start_angle)
+                .speed(1.0)
+                .suffix("°"),
+        );
+    });
+}
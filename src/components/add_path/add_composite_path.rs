@@ -0,0 +1,168 @@
+use egui::{Color32, Layout};
+
+use crate::{
+    app::GlobalState,
+    path::{composite::CompositePath, Path, PathEnum},
+};
+
+pub struct AddCompositePathWindow {
+    current_composite_path: CompositePath,
+    /// Paths that can be added as segments, read from the database when the window is shown
+    available_segments: Vec<PathEnum>,
+    pub shown: bool,
+}
+
+impl AddCompositePathWindow {
+    pub fn new() -> Self {
+        Self {
+            current_composite_path: CompositePath::default(),
+            available_segments: Vec::default(),
+            shown: false,
+        }
+    }
+
+    /// Shows this window, reading the paths that can be used as segments from the database
+    pub fn show(&mut self, app_state: &mut GlobalState) {
+        match app_state.database.get_segment_paths() {
+            Ok(paths) => self.available_segments = paths,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+
+        self.shown = true;
+    }
+
+    /// Writes the composite path to the specified Database
+    fn save(app_state: &mut GlobalState, adding_composite_path: &CompositePath) {
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::CompositePath(adding_composite_path.clone()));
+
+        match db_result {
+            Ok(_) => {
+                app_state.toasts.success(format!(
+                    "Successfully added a new composite path: {}",
+                    adding_composite_path.name
+                ));
+
+                if !adding_composite_path.gaps().is_empty() {
+                    app_state.toasts.warning(format!(
+                        "{} has gaps between some of it's segments",
+                        adding_composite_path.name
+                    ));
+                }
+            }
+
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write composite path to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the composite path entry window to the UI. Must be shown by calling `Self.show()`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+
+        egui::Window::new("Add composite path")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut self.shown)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    composite_path_fields(
+                        ui,
+                        &mut self.current_composite_path,
+                        &self.available_segments,
+                    )
+                });
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Add").clicked() {
+                            open = false;
+                            Self::save(app_state, &self.current_composite_path);
+                        }
+                    });
+                })
+            });
+
+        // Support using the close button defined with `.open()` above
+        if self.shown {
+            self.shown = open;
+        }
+    }
+}
+
+/// Shows inputs for all of the values of a composite path, apart from it's id
+pub fn composite_path_fields(
+    ui: &mut egui::Ui,
+    composite_path: &mut CompositePath,
+    available_segments: &[PathEnum],
+) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut composite_path.name);
+    });
+
+    ui.label("Segments:");
+    segment_list(ui, &mut composite_path.segments);
+
+    egui::ComboBox::from_label("Add segment")
+        .selected_text("Select a path")
+        .show_ui(ui, |ui| {
+            for path in available_segments {
+                if ui.selectable_label(false, path.name()).clicked() {
+                    composite_path.segments.push(path.clone());
+                }
+            }
+        });
+
+    for gap in composite_path.gaps() {
+        ui.colored_label(
+            Color32::ORANGE,
+            format!(
+                "⚠ Segment {} ends {:.3}m away from where segment {} starts",
+                gap.after_segment + 1,
+                gap.distance,
+                gap.after_segment + 2
+            ),
+        );
+    }
+}
+/// Shows the ordered list of segments, with buttons to reorder and remove them
+fn segment_list(ui: &mut egui::Ui, segments: &mut Vec<PathEnum>) {
+    // Can't change the list while iterating over it, so remember what to do until after
+    let mut moving_up: Option<usize> = None;
+    let mut removing: Option<usize> = None;
+    for (i, segment) in segments.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}. {}", i + 1, segment.name()));
+            if ui
+                .add_enabled(i > 0, egui::Button::new("⏶"))
+                .on_hover_text("Move up")
+                .clicked()
+            {
+                moving_up = Some(i);
+            }
+            if ui.button("🗑").on_hover_text("Remove segment").clicked() {
+                removing = Some(i);
+            }
+        });
+    }
+
+    if let Some(i) = moving_up {
+        segments.swap(i - 1, i);
+    }
+    if let Some(i) = removing {
+        segments.remove(i);
+    }
+}
@@ -1,6 +1,10 @@
 use egui::Layout;
 
-use crate::{app::GlobalState, components::select_vec, path::cubic_bezier::CubicBezier};
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{cubic_bezier::CubicBezier, PathEnum},
+};
 
 pub struct AddCubicBezierWindow {
     current_cubic_bezier: CubicBezier,
@@ -16,7 +20,9 @@ impl AddCubicBezierWindow {
     }
     /// Writes the line to the specified Database
     fn save(app_state: &mut GlobalState, adding_cubic_bezier: &CubicBezier) {
-        let db_result = app_state.database.add_cubic_bezier(adding_cubic_bezier);
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::CubicBezier(adding_cubic_bezier.clone()));
 
         match db_result {
             Ok(_) => {
@@ -45,16 +51,7 @@ impl AddCubicBezierWindow {
             .fade_out(true)
             .open(&mut self.shown)
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.label("Name:");
-                        ui.text_edit_singleline(&mut self.current_cubic_bezier.name);
-                    });
-                    select_vec(ui, "Start position: ", &mut self.current_cubic_bezier.start);
-                    select_vec(ui, "End position: ", &mut self.current_cubic_bezier.end);
-                    select_vec(ui, "Handle 1: ", &mut self.current_cubic_bezier.handle_1);
-                    select_vec(ui, "Handle 2: ", &mut self.current_cubic_bezier.handle_2);
-                });
+                ui.vertical(|ui| cubic_bezier_fields(ui, &mut self.current_cubic_bezier));
                 ui.add_space(16.0);
 
                 ui.horizontal(|ui| {
@@ -76,3 +73,15 @@ impl AddCubicBezierWindow {
         }
     }
 }
+
+/// Shows inputs for all of the values of a cubic bezier curve, apart from it's id
+pub fn cubic_bezier_fields(ui: &mut egui::Ui, cubic_bezier: &mut CubicBezier) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut cubic_bezier.name);
+    });
+    select_vec(ui, "Start position: ", &mut cubic_bezier.start);
+    select_vec(ui, "End position: ", &mut cubic_bezier.end);
+    select_vec(ui, "Handle 1: ", &mut cubic_bezier.handle_1);
+    select_vec(ui, "Handle 2: ", &mut cubic_bezier.handle_2);
+}
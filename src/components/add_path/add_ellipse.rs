@@ -0,0 +1,116 @@
+use egui::{DragValue, Layout};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{ellipse::Ellipse, PathEnum},
+};
+
+pub struct AddEllipseWindow {
+    current_ellipse: Ellipse,
+    pub shown: bool,
+}
+
+impl AddEllipseWindow {
+    pub fn new() -> Self {
+        Self {
+            // Most ellipses lie on the floor, so start with the plane facing up
+            current_ellipse: Ellipse {
+                normal: Vector3d::new(0.0, 0.0, 1.0),
+                sweep: 360.0,
+                ..Ellipse::default()
+            },
+            shown: false,
+        }
+    }
+    /// Writes the ellipse to the specified Database
+    fn save(app_state: &mut GlobalState, adding_ellipse: &Ellipse) {
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Ellipse(adding_ellipse.clone()));
+
+        match db_result {
+            Ok(_) => {
+                app_state.toasts.success(format!(
+                    "Successfully added a new ellipse: {}",
+                    adding_ellipse.name
+                ));
+            }
+
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write ellipse to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the ellipse entry window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+
+        egui::Window::new("Add ellipse")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut self.shown)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| ellipse_fields(ui, &mut self.current_ellipse));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Add").clicked() {
+                            open = false;
+                            Self::save(app_state, &self.current_ellipse);
+                        }
+                    });
+                })
+            });
+
+        // Support using the close button defined with `.open()` above
+        if self.shown {
+            self.shown = open;
+        }
+    }
+}
+
+/// Shows inputs for all of the values of an ellipse, apart from it's id
+pub fn ellipse_fields(ui: &mut egui::Ui, ellipse: &mut Ellipse) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut ellipse.name);
+    });
+    select_vec(ui, "Centre: ", &mut ellipse.centre);
+    ui.horizontal(|ui| {
+        ui.label("Radius 1:");
+        ui.add(
+            DragValue::new(&mut ellipse.radius_1)
+                .speed(0.1)
+                .range(0.0..=f64::MAX),
+        );
+        ui.label("Radius 2:");
+        ui.add(
+            DragValue::new(&mut ellipse.radius_2)
+                .speed(0.1)
+                .range(0.0..=f64::MAX),
+        );
+    });
+    // The direction the plane faces, e.g. up (0, 0, 1) for the floor
+    select_vec(ui, "Plane normal: ", &mut ellipse.normal);
+    ui.horizontal(|ui| {
+        ui.label("Start angle:");
+        ui.add(
+            DragValue::new(&mut ellipse.start_angle)
+                .speed(1.0)
+                .suffix("°"),
+        );
+        ui.label("Sweep:");
+        ui.add(DragValue::new(&mut ellipse.sweep).speed(1.0).suffix("°"));
+    });
+}
@@ -1,6 +1,10 @@
 use egui::Layout;
 
-use crate::{app::GlobalState, components::select_vec, path::line::Line};
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{line::Line, PathEnum},
+};
 
 pub struct AddLineWindow {
     current_line: Line,
@@ -16,7 +20,9 @@ impl AddLineWindow {
     }
     /// Writes the line to the specified Database
     fn save(app_state: &mut GlobalState, adding_line: &Line) {
-        let db_result = app_state.database.add_line(adding_line);
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Line(adding_line.clone()));
 
         match db_result {
             Ok(_) => {
@@ -45,14 +51,7 @@ impl AddLineWindow {
             .fade_out(true)
             .open(&mut self.shown)
             .show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.label("Name:");
-                        ui.text_edit_singleline(&mut self.current_line.name);
-                    });
-                    select_vec(ui, "Start position: ", &mut self.current_line.start);
-                    select_vec(ui, "End position: ", &mut self.current_line.end);
-                });
+                ui.vertical(|ui| line_fields(ui, &mut self.current_line));
                 ui.add_space(16.0);
 
                 ui.horizontal(|ui| {
@@ -74,3 +73,13 @@ impl AddLineWindow {
         }
     }
 }
+
+/// Shows inputs for all of the values of a line, apart from it's id
+pub fn line_fields(ui: &mut egui::Ui, line: &mut Line) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut line.name);
+    });
+    select_vec(ui, "Start position: ", &mut line.start);
+    select_vec(ui, "End position: ", &mut line.end);
+}
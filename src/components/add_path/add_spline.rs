@@ -0,0 +1,104 @@
+use egui::Layout;
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    path::{spline::Spline, PathEnum},
+};
+
+pub struct AddSplineWindow {
+    current_spline: Spline,
+    pub shown: bool,
+}
+
+impl AddSplineWindow {
+    pub fn new() -> Self {
+        Self {
+            current_spline: Spline::new(vec![Vector3d::default(); 2]),
+            shown: false,
+        }
+    }
+    /// Writes the spline to the specified Database
+    fn save(app_state: &mut GlobalState, adding_spline: &Spline) {
+        let db_result = app_state
+            .database
+            .add_path(&PathEnum::Spline(adding_spline.clone()));
+
+        match db_result {
+            Ok(_) => {
+                app_state.toasts.success(format!(
+                    "Successfully added a new spline: {}",
+                    adding_spline.name
+                ));
+            }
+
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write spline to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the spline entry window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+
+        egui::Window::new("Add spline")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut self.shown)
+            .show(ctx, |ui| {
+                ui.vertical(|ui| spline_fields(ui, &mut self.current_spline));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Add").clicked() {
+                            open = false;
+                            Self::save(app_state, &self.current_spline);
+                        }
+                    });
+                })
+            });
+
+        // Support using the close button defined with `.open()` above
+        if self.shown {
+            self.shown = open;
+        }
+    }
+}
+
+/// Shows inputs for all of the values of a spline, apart from it's id
+pub fn spline_fields(ui: &mut egui::Ui, spline: &mut Spline) {
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut spline.name);
+    });
+
+    // Can't remove while iterating, so remember which waypoint to remove until after
+    let mut removing: Option<usize> = None;
+    for (i, waypoint) in spline.waypoints.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            select_vec(ui, &format!("Waypoint {}: ", i + 1), waypoint);
+            if ui.button("🗑").on_hover_text("Remove waypoint").clicked() {
+                removing = Some(i);
+            }
+        });
+    }
+    if let Some(i) = removing {
+        spline.waypoints.remove(i);
+    }
+
+    if ui.button("Add waypoint").clicked() {
+        // Start new waypoints on top of the last one, it's usually close to where the next one will be
+        let last_waypoint = spline.waypoints.last().copied().unwrap_or_default();
+        spline.waypoints.push(last_waypoint);
+    }
+}
@@ -1,3 +1,29 @@
+pub mod add_arc;
 pub mod add_bezier;
+pub mod add_circle;
+pub mod add_composite_path;
 pub mod add_cubic_bezier;
+pub mod add_ellipse;
 pub mod add_line_window;
+pub mod add_spline;
+
+use crate::path::PathEnum;
+
+/// Shows inputs for all of the values of any kind of path, apart from it's id.
+/// `available_segments` are the paths that can be added to a composite path
+pub fn path_fields(ui: &mut egui::Ui, path: &mut PathEnum, available_segments: &[PathEnum]) {
+    match path {
+        PathEnum::Line(line) => add_line_window::line_fields(ui, line),
+        PathEnum::Bezier(bezier) => add_bezier::bezier_fields(ui, bezier),
+        PathEnum::CubicBezier(cubic_bezier) => {
+            add_cubic_bezier::cubic_bezier_fields(ui, cubic_bezier)
+        }
+        PathEnum::Spline(spline) => add_spline::spline_fields(ui, spline),
+        PathEnum::Circle(circle) => add_circle::circle_fields(ui, circle),
+        PathEnum::Arc(arc) => add_arc::arc_fields(ui, arc),
+        PathEnum::Ellipse(ellipse) => add_ellipse::ellipse_fields(ui, ellipse),
+        PathEnum::CompositePath(composite_path) => {
+            add_composite_path::composite_path_fields(ui, composite_path, available_segments)
+        }
+    }
+}
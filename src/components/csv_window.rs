@@ -0,0 +1,293 @@
+use std::{fs, path::PathBuf};
+
+use egui::{Color32, Layout};
+
+use crate::{
+    app::GlobalState,
+    import::csv::{
+        read_csv, save_csv_items, CsvFile, CsvItem, CsvTable, DuplicatePolicy, ParsedRow,
+    },
+    path::Path,
+};
+
+pub struct ImportCsvWindow {
+    table: CsvTable,
+    file: Option<PathBuf>,
+    contents: CsvFile,
+    /// Which column of the file each of the table's columns is read from
+    mapping: Vec<Option<usize>>,
+    duplicate_policy: DuplicatePolicy,
+    /// The rows that will be imported with the current mapping
+    preview: Vec<ParsedRow>,
+    pub shown: bool,
+}
+
+impl ImportCsvWindow {
+    pub fn new() -> Self {
+        Self {
+            table: CsvTable::Lights,
+            file: None,
+            contents: CsvFile::default(),
+            mapping: vec![None; CsvTable::Lights.columns().len()],
+            duplicate_policy: DuplicatePolicy::default(),
+            preview: Vec::default(),
+            shown: false,
+        }
+    }
+
+    /// Asks the user for a CSV file and reads it
+    fn choose_file(&mut self, app_state: &mut GlobalState) {
+        let Some(file) = rfd::FileDialog::new()
+            .add_filter("CSV spreadsheet", &["csv"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let contents = match fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to read {}: {e}", file.display()));
+                return;
+            }
+        };
+
+        match read_csv(&contents) {
+            Ok(contents) => {
+                self.contents = contents;
+                self.file = Some(file);
+                self.mapping = self.table.guess_mapping(&self.contents.headers);
+                self.update_preview();
+            }
+            Err(e) => {
+                app_state.toasts.error(format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Reads the rows again with the current table and mapping
+    fn update_preview(&mut self) {
+        self.preview = self.table.parse(&self.contents, &self.mapping);
+    }
+
+    /// Checks every required column has been given a column in the file
+    fn required_columns_mapped(&self) -> bool {
+        self.table
+            .columns()
+            .iter()
+            .zip(&self.mapping)
+            .all(|(column, mapped)| !column.required || mapped.is_some())
+    }
+
+    /// Writes the valid rows to the database
+    fn save(&self, app_state: &mut GlobalState) {
+        match save_csv_items(&app_state.database, &self.preview, self.duplicate_policy) {
+            Ok(summary) => {
+                app_state.toasts.success(format!(
+                    "Imported {} {} ({} skipped, {} overwritten, {} renamed)",
+                    summary.added,
+                    self.table.name().to_lowercase(),
+                    summary.skipped,
+                    summary.overwritten,
+                    summary.renamed
+                ));
+            }
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write CSV rows to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the CSV import window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+        let mut shown = self.shown;
+
+        egui::Window::new("Import CSV")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let old_table = self.table;
+                let old_mapping = self.mapping.clone();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Choose file…").clicked() {
+                        self.choose_file(app_state);
+                    }
+                    match &self.file {
+                        Some(file) => ui.label(file.display().to_string()),
+                        None => ui.label("No file chosen"),
+                    };
+                });
+
+                egui::ComboBox::from_label("Import in to")
+                    .selected_text(self.table.name())
+                    .show_ui(ui, |ui| {
+                        for table in CsvTable::ALL {
+                            ui.selectable_value(&mut self.table, table, table.name());
+                        }
+                    });
+
+                if self.table != old_table {
+                    self.mapping = self.table.guess_mapping(&self.contents.headers);
+                }
+
+                ui.separator();
+                ui.label("Columns (* is required):");
+                egui::ScrollArea::vertical()
+                    .id_salt("csv_mapping")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        mapping_grid(ui, self.table, &self.contents.headers, &mut self.mapping);
+                    });
+
+                egui::ComboBox::from_label("When the name already exists")
+                    .selected_text(format!("{:?}", self.duplicate_policy))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.duplicate_policy,
+                            DuplicatePolicy::Skip,
+                            "Skip",
+                        )
+                        .on_hover_text("Keep the existing one");
+                        ui.selectable_value(
+                            &mut self.duplicate_policy,
+                            DuplicatePolicy::Overwrite,
+                            "Overwrite",
+                        )
+                        .on_hover_text("Replace the existing one");
+                        ui.selectable_value(
+                            &mut self.duplicate_policy,
+                            DuplicatePolicy::Rename,
+                            "Rename",
+                        )
+                        .on_hover_text("Add a number to the new one's name");
+                    });
+
+                ui.separator();
+                let valid_rows = self.preview.iter().filter(|row| row.item.is_ok()).count();
+                ui.label(format!(
+                    "{valid_rows} valid, {} with errors",
+                    self.preview.len() - valid_rows
+                ));
+                egui::ScrollArea::vertical()
+                    .id_salt("csv_preview")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        preview_list(ui, &self.preview);
+                    });
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(
+                                valid_rows > 0 && self.required_columns_mapped(),
+                                egui::Button::new("Import"),
+                            )
+                            .clicked()
+                        {
+                            open = false;
+                            self.save(app_state);
+                        }
+                    });
+                });
+
+                if self.table != old_table || self.mapping != old_mapping {
+                    self.update_preview();
+                }
+            });
+
+        // Support using the close button defined with `.open()` above
+        self.shown = shown && open;
+    }
+}
+
+/// Lets the user pick which column of the file each of the table's columns is read from
+fn mapping_grid(
+    ui: &mut egui::Ui,
+    table: CsvTable,
+    headers: &[String],
+    mapping: &mut [Option<usize>],
+) {
+    egui::Grid::new("csv_mapping_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (column, mapped) in table.columns().iter().zip(mapping.iter_mut()) {
+                if column.required {
+                    ui.label(format!("{} *", column.name));
+                } else {
+                    ui.label(column.name);
+                }
+
+                let selected_text = match mapped {
+                    Some(i) => headers[*i].as_str(),
+                    None => "(not in file)",
+                };
+                egui::ComboBox::from_id_salt(column.name)
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(mapped, None, "(not in file)");
+                        for (i, header) in headers.iter().enumerate() {
+                            ui.selectable_value(mapped, Some(i), header);
+                        }
+                    });
+                ui.end_row();
+            }
+        });
+}
+
+/// Shows what each row will be imported as, or why it can't be
+fn preview_list(ui: &mut egui::Ui, preview: &[ParsedRow]) {
+    for row in preview {
+        match &row.item {
+            Ok(CsvItem::Light(light)) => {
+                ui.label(format!("Line {}: {}", row.line, light.name));
+            }
+            Ok(CsvItem::Path(path)) => {
+                ui.label(format!("Line {}: {}", row.line, path.name()));
+            }
+            Err(e) => {
+                ui.colored_label(Color32::ORANGE, format!("Line {}: {e}", row.line));
+            }
+        }
+    }
+}
+
+/// Asks the user where to save a table and writes it there as a CSV file
+pub fn export_csv(table: CsvTable, app_state: &mut GlobalState) {
+    let Some(file) = rfd::FileDialog::new()
+        .add_filter("CSV spreadsheet", &["csv"])
+        .set_file_name(format!("{}.csv", table.name().to_lowercase()))
+        .save_file()
+    else {
+        return;
+    };
+
+    let result = table
+        .export(&app_state.database)
+        .and_then(|contents| Ok(fs::write(&file, contents)?));
+
+    match result {
+        Ok(()) => {
+            app_state
+                .toasts
+                .success(format!("Exported {} to {}", table.name(), file.display()));
+        }
+        Err(e) => {
+            app_state
+                .toasts
+                .error(format!("Failed to export {}: {e:#}", table.name()));
+        }
+    }
+}
@@ -2,16 +2,16 @@ use vector3d::Vector3d;
 
 use crate::{
     app::GlobalState,
-    components::{
-        output_section::move_calculator, select_lights_modal::SelectLightsModal, select_vec,
-    },
+    components::{select_lights_modal::SelectLightsModal, select_vec},
+    move_calculator::OutputJob,
 };
 
-#[derive(Debug)]
 pub struct DebugPointAt {
     point: Vector3d<f64>,
     pub shown: bool,
     select_lights_modal: SelectLightsModal,
+    /// The commands being sent to the desk, if there are any. Sent in the background so the UI doesn't freeze while it's connecting
+    output_job: Option<OutputJob>,
 }
 
 impl DebugPointAt {
@@ -20,10 +20,12 @@ impl DebugPointAt {
             point: Vector3d::default(),
             shown: false,
             select_lights_modal: SelectLightsModal::new("Select lights for debug point at"),
+            output_job: None,
         }
     }
     pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
         self.select_lights_modal.add(ctx);
+        self.output_progress(ctx, app_state);
 
         egui::Window::new("Debug point at")
             .collapsible(false)
@@ -43,16 +45,23 @@ impl DebugPointAt {
 
                 select_vec(ui, "Position: ", &mut self.point);
 
-                if ui.button("Output OSC").clicked() {
+                if ui
+                    .add_enabled(self.output_job.is_none(), egui::Button::new("Output OSC"))
+                    .on_disabled_hover_text("Wait for the last commands to finish sending")
+                    .clicked()
+                {
                     let mut commands: Vec<String> = vec![];
                     for light in self.select_lights_modal.get_selected_lights() {
-                        commands.append(&mut light.point_at(self.point).to_commands());
+                        // Kept inside the light's pan and tilt range, like a move would be
+                        commands
+                            .append(&mut light.point_at_closest(self.point, None).to_commands());
                     }
 
-                    let osc_result = move_calculator::output_commands(commands, app_state);
-
-                    match osc_result {
-                        Ok(_) => (),
+                    match app_state.config_file.read() {
+                        Ok(config) => {
+                            self.output_job =
+                                Some(OutputJob::start(commands, config.osc.connection()));
+                        }
                         Err(err) => {
                             app_state
                                 .toasts
@@ -62,4 +71,26 @@ impl DebugPointAt {
                 }
             });
     }
+
+    /// Toasts any commands that failed, and forgets the job once it's finished
+    fn output_progress(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let Some(job) = &self.output_job else {
+            return;
+        };
+
+        // Checked before taking the failures, so none can arrive after they've been shown
+        let finished = job.is_finished();
+        for failure in job.failures() {
+            app_state
+                .toasts
+                .error(format!("Failed to send OSC command to desk: {failure}"));
+        }
+
+        if finished {
+            self.output_job = None;
+        } else {
+            // Nothing else repaints while the commands are being sent
+            ctx.request_repaint();
+        }
+    }
 }
@@ -0,0 +1,243 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use egui::{DragValue, Layout};
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    import::{
+        gltf::import_gltf,
+        model::{ModelImportSettings, PolylineShape, UpAxis},
+        obj::import_obj,
+        save_imported_paths, ImportedPath,
+    },
+};
+
+pub struct ImportModelWindow {
+    settings: ModelImportSettings,
+    file: Option<PathBuf>,
+    file_contents: Vec<u8>,
+    /// The paths that will be imported with the current settings
+    preview: Vec<ImportedPath>,
+    pub shown: bool,
+}
+
+impl ImportModelWindow {
+    pub fn new() -> Self {
+        Self {
+            settings: ModelImportSettings::default(),
+            file: None,
+            file_contents: Vec::default(),
+            preview: Vec::default(),
+            shown: false,
+        }
+    }
+
+    /// Asks the user for an OBJ or glTF file and reads it
+    fn choose_file(&mut self, app_state: &mut GlobalState) {
+        let Some(file) = rfd::FileDialog::new()
+            .add_filter("3D model", &["obj", "gltf", "glb"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match fs::read(&file) {
+            Ok(contents) => {
+                self.file_contents = contents;
+                self.file = Some(file);
+                self.update_preview(app_state);
+            }
+            Err(e) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to read {}: {e}", file.display()));
+            }
+        }
+    }
+
+    /// Reads the paths out of the chosen file, picking the importer from it's extension
+    fn read_file(&self, file: &std::path::Path) -> Result<Vec<ImportedPath>> {
+        let default_name = file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or("Model path".to_string());
+
+        let is_obj = file
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("obj"));
+
+        if is_obj {
+            import_obj(
+                &String::from_utf8_lossy(&self.file_contents),
+                &default_name,
+                &self.settings,
+            )
+        } else {
+            import_gltf(
+                &self.file_contents,
+                file.parent(),
+                &default_name,
+                &self.settings,
+            )
+        }
+    }
+
+    /// Reads the paths out of the file again with the current settings
+    fn update_preview(&mut self, app_state: &mut GlobalState) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        match self.read_file(file) {
+            Ok(paths) => {
+                if paths.is_empty() {
+                    app_state
+                        .toasts
+                        .warning("No lines were found in this file. Curves must be exported as edges or lines");
+                }
+                self.preview = paths;
+            }
+            Err(e) => {
+                self.preview = vec![];
+                app_state.toasts.error(format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Writes the previewed paths to the database
+    fn save(&self, app_state: &mut GlobalState) {
+        match save_imported_paths(&app_state.database, &self.preview) {
+            Ok(saved) => {
+                app_state
+                    .toasts
+                    .success(format!("Successfully imported {saved} paths"));
+            }
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write imported paths to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the 3D model import window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+        let mut shown = self.shown;
+
+        egui::Window::new("Import 3D model paths")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let old_settings = self.settings.clone();
+
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Choose file…").clicked() {
+                            self.choose_file(app_state);
+                        }
+                        match &self.file {
+                            Some(file) => ui.label(file.display().to_string()),
+                            None => ui.label("No file chosen"),
+                        };
+                    });
+
+                    egui::ComboBox::from_label("Up axis")
+                        .selected_text(format!("{:?}", self.settings.up_axis))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.settings.up_axis, UpAxis::Y, "Y")
+                                .on_hover_text("glTF files and Blender's default OBJ export");
+                            ui.selectable_value(&mut self.settings.up_axis, UpAxis::Z, "Z")
+                                .on_hover_text("The same as this program");
+                        });
+
+                    egui::ComboBox::from_label("Import as")
+                        .selected_text(format!("{:?}", self.settings.shape))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut self.settings.shape,
+                                PolylineShape::Lines,
+                                "Lines",
+                            )
+                            .on_hover_text("A straight line between each point");
+                            ui.selectable_value(
+                                &mut self.settings.shape,
+                                PolylineShape::Spline,
+                                "Spline",
+                            )
+                            .on_hover_text("A smooth curve through every point");
+                        });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Scale:");
+                        ui.add(
+                            DragValue::new(&mut self.settings.scale)
+                                .speed(0.001)
+                                .range(0.0..=f64::MAX)
+                                .max_decimals(6),
+                        );
+                        ui.label("m per unit");
+                        if ui.small_button("mm").clicked() {
+                            self.settings.scale = 0.001;
+                        }
+                        if ui.small_button("cm").clicked() {
+                            self.settings.scale = 0.01;
+                        }
+                        if ui.small_button("m").clicked() {
+                            self.settings.scale = 1.0;
+                        }
+                        if ui.small_button("in").clicked() {
+                            self.settings.scale = 0.0254;
+                        }
+                        if ui.small_button("ft").clicked() {
+                            self.settings.scale = 0.3048;
+                        }
+                    });
+
+                    select_vec(ui, "Origin: ", &mut self.settings.origin);
+
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for path in &self.preview {
+                                ui.label(format!(
+                                    "{} ({} segments, {:.2}m)",
+                                    path.name,
+                                    path.segments.len(),
+                                    path.length()
+                                ));
+                            }
+                        });
+                });
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(!self.preview.is_empty(), egui::Button::new("Import"))
+                            .clicked()
+                        {
+                            open = false;
+                            self.save(app_state);
+                        }
+                    });
+                });
+
+                if self.settings != old_settings {
+                    self.update_preview(app_state);
+                }
+            });
+
+        // Support using the close button defined with `.open()` above
+        self.shown = shown && open;
+    }
+}
@@ -0,0 +1,182 @@
+use std::{fs, path::PathBuf};
+
+use egui::{DragValue, Layout};
+
+use crate::{
+    app::GlobalState,
+    components::select_vec,
+    import::{
+        save_imported_paths,
+        svg::{import_svg, SvgImportSettings, SvgPlane},
+        ImportedPath,
+    },
+};
+
+pub struct ImportSvgWindow {
+    settings: SvgImportSettings,
+    file: Option<PathBuf>,
+    file_contents: String,
+    /// The paths that will be imported with the current settings
+    preview: Vec<ImportedPath>,
+    pub shown: bool,
+}
+
+impl ImportSvgWindow {
+    pub fn new() -> Self {
+        Self {
+            settings: SvgImportSettings::default(),
+            file: None,
+            file_contents: String::default(),
+            preview: Vec::default(),
+            shown: false,
+        }
+    }
+
+    /// Asks the user for an SVG file and reads it
+    fn choose_file(&mut self, app_state: &mut GlobalState) {
+        let Some(file) = rfd::FileDialog::new()
+            .add_filter("SVG drawing", &["svg"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match fs::read_to_string(&file) {
+            Ok(contents) => {
+                self.file_contents = contents;
+                self.file = Some(file);
+                self.update_preview(app_state);
+            }
+            Err(e) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to read {}: {e}", file.display()));
+            }
+        }
+    }
+
+    /// Reads the paths out of the file again with the current settings
+    fn update_preview(&mut self, app_state: &mut GlobalState) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let default_name = file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or("SVG path".to_string());
+
+        match import_svg(&self.file_contents, &default_name, &self.settings) {
+            Ok(paths) => self.preview = paths,
+            Err(e) => {
+                self.preview = vec![];
+                app_state.toasts.error(format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Writes the previewed paths to the database
+    fn save(&self, app_state: &mut GlobalState) {
+        match save_imported_paths(&app_state.database, &self.preview) {
+            Ok(saved) => {
+                app_state
+                    .toasts
+                    .success(format!("Successfully imported {saved} paths"));
+            }
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to write imported paths to database: {err}"));
+            }
+        }
+    }
+
+    /// Adds the SVG import window to the UI. Must be shown by setting `Self.shown = true`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut open = self.shown;
+        let mut shown = self.shown;
+
+        egui::Window::new("Import SVG paths")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let old_settings = self.settings.clone();
+
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Choose file…").clicked() {
+                            self.choose_file(app_state);
+                        }
+                        match &self.file {
+                            Some(file) => ui.label(file.display().to_string()),
+                            None => ui.label("No file chosen"),
+                        };
+                    });
+
+                    egui::ComboBox::from_label("Plane")
+                        .selected_text(format!("{:?}", self.settings.plane))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.settings.plane, SvgPlane::Floor, "Floor")
+                                .on_hover_text("The top of the drawing is upstage");
+                            ui.selectable_value(&mut self.settings.plane, SvgPlane::Wall, "Wall")
+                                .on_hover_text(
+                                    "A wall facing the audience, the top of the drawing is up",
+                                );
+                        });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Scale:");
+                        ui.add(
+                            DragValue::new(&mut self.settings.scale)
+                                .speed(0.0001)
+                                .range(0.0..=f64::MAX)
+                                .max_decimals(6),
+                        );
+                        ui.label("m per SVG unit");
+                    });
+
+                    select_vec(ui, "Origin: ", &mut self.settings.origin);
+
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for path in &self.preview {
+                                ui.label(format!(
+                                    "{} ({} segments, {:.2}m)",
+                                    path.name,
+                                    path.segments.len(),
+                                    path.length()
+                                ));
+                            }
+                        });
+                });
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add_enabled(!self.preview.is_empty(), egui::Button::new("Import"))
+                            .clicked()
+                        {
+                            open = false;
+                            self.save(app_state);
+                        }
+                    });
+                });
+
+                if self.settings != old_settings {
+                    self.update_preview(app_state);
+                }
+            });
+
+        // Support using the close button defined with `.open()` above
+        self.shown = shown && open;
+    }
+}
@@ -0,0 +1,333 @@
+use egui::{Color32, Layout};
+
+use crate::{
+    app::GlobalState,
+    components::{add_light_window::light_fields, add_path::path_fields},
+    light::Light,
+    path::{Path, PathEnum},
+};
+
+/// Something stored in the database that can be edited or deleted
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Light(Light),
+    Path(PathEnum),
+}
+
+impl Item {
+    fn name(&self) -> String {
+        match self {
+            Item::Light(light) => light.name.clone(),
+            Item::Path(path) => path.name(),
+        }
+    }
+
+    /// The kind and id of the row this item is stored in, so an edited item can still be matched to it's row
+    fn key(&self) -> (&'static str, i64) {
+        match self {
+            Item::Light(light) => ("Light", light.id),
+            Item::Path(path) => (path.kind(), path.id()),
+        }
+    }
+}
+
+/// What the user clicked on this frame, done after the list has been drawn
+enum Action {
+    Edit(Item),
+    Save,
+    CancelEdit,
+    Delete(Item),
+}
+
+pub struct ManageWindow {
+    lights: Vec<Light>,
+    paths: Vec<PathEnum>,
+    /// The item being edited in place, with the changes that haven't been saved yet
+    editing: Option<Item>,
+    /// The item waiting for the user to confirm it should be deleted
+    deleting: Option<Item>,
+    pub shown: bool,
+}
+
+impl ManageWindow {
+    pub fn new() -> Self {
+        Self {
+            lights: Vec::default(),
+            paths: Vec::default(),
+            editing: None,
+            deleting: None,
+            shown: false,
+        }
+    }
+
+    /// Shows this window, reading everything in the database
+    pub fn show(&mut self, app_state: &mut GlobalState) {
+        self.reload(app_state);
+        self.shown = true;
+    }
+
+    /// Reads the lights and paths from the database again
+    fn reload(&mut self, app_state: &mut GlobalState) {
+        match app_state.database.get_lights() {
+            Ok(lights) => self.lights = lights,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+
+        match app_state.database.get_paths() {
+            Ok(paths) => self.paths = paths,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+    }
+
+    /// Writes the edited item back to it's row in the database
+    fn save(app_state: &mut GlobalState, item: &Item) -> bool {
+        let db_result = match item {
+            Item::Light(light) => app_state.database.update_light(light),
+            Item::Path(path) => app_state.database.update_path(path),
+        };
+
+        match db_result {
+            Ok(()) => {
+                app_state
+                    .toasts
+                    .success(format!("Successfully updated {}", item.name()));
+                true
+            }
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to update database: {err}"));
+                false
+            }
+        }
+    }
+
+    /// Removes the item's row from the database
+    fn delete(app_state: &mut GlobalState, item: &Item) {
+        let db_result = match item {
+            Item::Light(light) => app_state.database.delete_light(light.id),
+            Item::Path(path) => app_state.database.delete_path(path),
+        };
+
+        match db_result {
+            Ok(()) => {
+                app_state
+                    .toasts
+                    .success(format!("Successfully deleted {}", item.name()));
+            }
+            Err(err) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to delete from database: {err}"));
+            }
+        }
+    }
+
+    /// The names of the composite paths that use `item` as a segment
+    fn used_by(&self, item: &Item) -> Vec<String> {
+        let Item::Path(segment) = item else {
+            return vec![];
+        };
+
+        self.paths
+            .iter()
+            .filter_map(|path| match path {
+                PathEnum::CompositePath(composite_path) => Some(composite_path),
+                _ => None,
+            })
+            .filter(|composite_path| {
+                composite_path
+                    .segments
+                    .iter()
+                    .any(|s| s.kind() == segment.kind() && s.id() == segment.id())
+            })
+            .map(|composite_path| composite_path.name.clone())
+            .collect()
+    }
+
+    /// Adds the management window to the UI. Must be shown with `Self::show`
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let mut shown = self.shown;
+        let mut action: Option<Action> = None;
+
+        egui::Window::new("Lights and paths")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let available_segments: Vec<PathEnum> = self
+                    .paths
+                    .iter()
+                    .filter(|path| !matches!(path, PathEnum::CompositePath(_)))
+                    .cloned()
+                    .collect();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::CollapsingHeader::new(format!("Lights ({})", self.lights.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for light in &self.lights {
+                                item_row(
+                                    ui,
+                                    &Item::Light(light.clone()),
+                                    &format!("Address {}", light.address),
+                                    &mut self.editing,
+                                    &available_segments,
+                                    &mut action,
+                                );
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(format!("Paths ({})", self.paths.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for path in &self.paths {
+                                item_row(
+                                    ui,
+                                    &Item::Path(path.clone()),
+                                    path.kind(),
+                                    &mut self.editing,
+                                    &available_segments,
+                                    &mut action,
+                                );
+                            }
+                        });
+                });
+            });
+
+        match action {
+            Some(Action::Edit(item)) => self.editing = Some(item),
+            Some(Action::CancelEdit) => self.editing = None,
+            Some(Action::Save) => {
+                if let Some(item) = &self.editing {
+                    if Self::save(app_state, item) {
+                        self.editing = None;
+                        self.reload(app_state);
+                    }
+                }
+            }
+            Some(Action::Delete(item)) => self.deleting = Some(item),
+            None => {}
+        }
+
+        if shown {
+            self.confirm_delete(ctx, app_state);
+        }
+
+        // Support using the close button defined with `.open()` above
+        self.shown = shown;
+        if !self.shown {
+            self.editing = None;
+        }
+    }
+
+    /// Asks the user to confirm the item in `self.deleting` should be deleted
+    fn confirm_delete(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        let Some(item) = self.deleting.clone() else {
+            return;
+        };
+        let used_by = self.used_by(&item);
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let modal = egui::Modal::new(egui::Id::new("confirm_delete")).show(ctx, |ui| {
+            ui.heading(format!("Delete {}?", item.name()));
+            ui.label("This can't be undone.");
+
+            if !used_by.is_empty() {
+                ui.colored_label(
+                    Color32::ORANGE,
+                    format!(
+                        "⚠ It will also be taken out of these composite paths: {}",
+                        used_by.join(", ")
+                    ),
+                );
+            }
+            ui.add_space(16.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Delete").clicked() {
+                        confirmed = true;
+                    }
+                });
+            });
+        });
+
+        if confirmed {
+            Self::delete(app_state, &item);
+            if self.editing.as_ref().map(Item::key) == Some(item.key()) {
+                self.editing = None;
+            }
+            self.reload(app_state);
+        }
+        if confirmed || cancelled || modal.should_close() {
+            self.deleting = None;
+        }
+    }
+}
+
+/// Shows one item with edit and delete buttons, and it's inputs underneath if it's being edited
+fn item_row(
+    ui: &mut egui::Ui,
+    item: &Item,
+    detail: &str,
+    editing: &mut Option<Item>,
+    available_segments: &[PathEnum],
+    action: &mut Option<Action>,
+) {
+    let is_editing = editing.as_ref().map(Item::key) == Some(item.key());
+
+    ui.horizontal(|ui| {
+        ui.label(item.name());
+        ui.weak(detail);
+        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("🗑").on_hover_text("Delete").clicked() {
+                *action = Some(Action::Delete(item.clone()));
+            }
+            if ui
+                .add_enabled(!is_editing, egui::Button::new("✏"))
+                .on_hover_text("Edit")
+                .clicked()
+            {
+                *action = Some(Action::Edit(item.clone()));
+            }
+        });
+    });
+
+    if !is_editing {
+        return;
+    }
+    let Some(editing_item) = editing else {
+        return;
+    };
+
+    ui.group(|ui| {
+        ui.push_id(item.key(), |ui| {
+            match editing_item {
+                Item::Light(light) => light_fields(ui, light),
+                Item::Path(path) => path_fields(ui, path, available_segments),
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("Cancel").clicked() {
+                    *action = Some(Action::CancelEdit);
+                }
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Save").clicked() {
+                        *action = Some(Action::Save);
+                    }
+                });
+            });
+        });
+    });
+}
@@ -3,9 +3,16 @@ use vector3d::Vector3d;
 
 pub mod add_light_window;
 pub mod add_path;
+pub mod csv_window;
 pub mod debug_point_at;
+pub mod import_model_window;
+pub mod import_svg_window;
+pub mod manage_window;
 pub mod output_section;
+pub mod plan_views;
 pub mod preferences;
+pub mod preview;
+pub mod project_menu;
 pub mod select_lights_modal;
 pub mod select_path_modal;
 pub mod toggleable_item;
@@ -0,0 +1,174 @@
+use std::fs;
+
+use egui::RichText;
+
+use crate::{app::GlobalState, move_calculator::FrameCommands};
+
+/// Shows the commands for a move before anything is sent, so they can be checked, copied or saved.
+/// Nothing goes to the desk until "Send to desk" has been pressed and confirmed
+pub struct CommandPreviewWindow {
+    frames: Vec<FrameCommands>,
+    /// "Send to desk" has been pressed once, and is waiting to be confirmed
+    confirming: bool,
+    pub shown: bool,
+}
+
+impl CommandPreviewWindow {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            confirming: false,
+            shown: false,
+        }
+    }
+
+    /// Shows the window with a new move in it
+    pub fn show(&mut self, frames: Vec<FrameCommands>) {
+        self.frames = frames;
+        self.confirming = false;
+        self.shown = true;
+    }
+
+    /// Shows the window with a new move in it, already asking to confirm sending it to the desk
+    pub fn show_to_send(&mut self, frames: Vec<FrameCommands>) {
+        self.show(frames);
+        self.confirming = true;
+    }
+
+    /// Every command, one per line
+    fn text(&self) -> String {
+        self.frames
+            .iter()
+            .flat_map(|frame| &frame.commands)
+            .map(|command| format!("{command}\n"))
+            .collect()
+    }
+
+    /// Asks the user where to save the commands as a text file, and saves them there
+    fn save(&self, app_state: &mut GlobalState) {
+        let Some(file) = rfd::FileDialog::new()
+            .add_filter("Text file", &["txt"])
+            .set_file_name("move.txt")
+            .save_file()
+        else {
+            return;
+        };
+
+        match fs::write(&file, self.text()) {
+            Ok(()) => {
+                app_state
+                    .toasts
+                    .success(format!("Saved the commands to {}", file.display()));
+            }
+            Err(e) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to save {}: {e}", file.display()));
+            }
+        }
+    }
+
+    /// Adds the preview window to the UI. Must be shown with `Self::show`.
+    /// Returns the commands once sending them has been confirmed, sending is left to the caller.
+    /// can_send is false while another move is still being sent
+    pub fn add(
+        &mut self,
+        ctx: &egui::Context,
+        app_state: &mut GlobalState,
+        can_send: bool,
+    ) -> Option<Vec<String>> {
+        let mut shown = self.shown;
+        let mut send = false;
+
+        egui::Window::new("Move commands")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let commands: usize = self.frames.iter().map(|frame| frame.commands.len()).sum();
+                if let (Some(first), Some(last)) = (self.frames.first(), self.frames.last()) {
+                    ui.label(format!(
+                        "{commands} commands recording cues {} to {}",
+                        first.cue_number, last.cue_number
+                    ));
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("📋 Copy").clicked() {
+                        ctx.copy_text(self.text());
+                        app_state.toasts.info("Copied the commands");
+                    }
+                    if ui.button("💾 Save…").clicked() {
+                        self.save(app_state);
+                    }
+                });
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("move_commands")
+                    .max_height(400.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (i, frame) in self.frames.iter().enumerate() {
+                            egui::CollapsingHeader::new(format!(
+                                "Frame {} - cue {}",
+                                i + 1,
+                                frame.cue_number
+                            ))
+                            .id_salt(i)
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for command in &frame.commands {
+                                    ui.label(RichText::new(command).monospace());
+                                }
+                            });
+                        }
+                    });
+
+                ui.separator();
+                // Two clicks, so cues on a live desk can't be recorded over by accident
+                if self.confirming {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "This records over any cues already in these places on the desk",
+                    );
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(can_send, egui::Button::new("Yes, send to desk"))
+                            .on_disabled_hover_text("Wait for the last move to finish sending")
+                            .clicked()
+                        {
+                            send = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirming = false;
+                        }
+                    });
+                } else if ui
+                    .add_enabled(can_send, egui::Button::new("Send to desk"))
+                    .on_disabled_hover_text("Wait for the last move to finish sending")
+                    .clicked()
+                {
+                    self.confirming = true;
+                }
+            });
+
+        // The commands have been sent, so there's nothing left to do with them here
+        if send {
+            shown = false;
+        }
+        self.shown = shown;
+        if !shown {
+            self.confirming = false;
+        }
+
+        send.then(|| {
+            self.frames
+                .iter()
+                .flat_map(|frame| frame.commands.clone())
+                .collect()
+        })
+    }
+}
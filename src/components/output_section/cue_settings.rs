@@ -0,0 +1,133 @@
+use egui::DragValue;
+
+use crate::move_calculator::{CueFlag, CueNumbering, CueRange, CueSettings};
+
+/// What the numbering is called in the UI
+fn numbering_name(numbering: CueNumbering) -> &'static str {
+    match numbering {
+        CueNumbering::Decimal { .. } => "Point cues",
+        CueNumbering::Whole => "Whole cues",
+        CueNumbering::Parts => "Cue parts",
+    }
+}
+
+/// What the flag is called in the UI
+fn flag_name(flag: Option<CueFlag>) -> &'static str {
+    match flag {
+        None => "No flag",
+        Some(CueFlag::Mark) => "Mark",
+        Some(CueFlag::Block) => "Block",
+    }
+}
+
+/// Shows which cues the move is recorded in to and how they're set up, with the cue numbers that will be used.
+/// `protected` and `label` keep the protected cues and label while they're turned off. An empty label is filled in with the path's name
+pub fn cue_settings(
+    ui: &mut egui::Ui,
+    cues: &mut CueSettings,
+    protected: &mut CueRange,
+    label: &mut String,
+    frames: u16,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Cue list");
+        ui.add(DragValue::new(&mut cues.cue_list).range(1..=999));
+        ui.label("Cue Number");
+        ui.add(DragValue::new(&mut cues.first_cue).range(1..=9999));
+    });
+
+    egui::ComboBox::from_label("Cue numbering")
+        .selected_text(numbering_name(cues.numbering))
+        .show_ui(ui, |ui| {
+            for option in [
+                CueNumbering::default(),
+                CueNumbering::Whole,
+                CueNumbering::Parts,
+            ] {
+                let selected = std::mem::discriminant(&cues.numbering)
+                    == std::mem::discriminant(&option);
+                if ui
+                    .selectable_label(selected, numbering_name(option))
+                    .clicked()
+                    && !selected
+                {
+                    cues.numbering = option;
+                }
+            }
+        })
+        .response
+        .on_hover_text("Point cues go after the cue number, whole cues carry on from it and parts all run from it's go");
+
+    if let CueNumbering::Decimal { step } = &mut cues.numbering {
+        ui.horizontal(|ui| {
+            ui.label("Step");
+            ui.add(
+                DragValue::new(step)
+                    .range(0.001..=100.0)
+                    .speed(0.001)
+                    .max_decimals(3),
+            );
+        });
+        ui.checkbox(&mut cues.past_next_cue, "Past next cue")
+            .on_hover_text("Let the point cues carry on past the next whole cue, recording over it if it's already on the desk");
+    }
+
+    ui.horizontal(|ui| {
+        let mut protect = cues.protected.is_some();
+        ui.checkbox(&mut protect, "Protect cues")
+            .on_hover_text("Cues already on the desk that the move mustn't record over");
+        ui.add_enabled_ui(protect, |ui| {
+            ui.add(
+                DragValue::new(&mut protected.first)
+                    .range(0.0..=9999.999)
+                    .max_decimals(3),
+            );
+            ui.label("to");
+            ui.add(
+                DragValue::new(&mut protected.last)
+                    .range(protected.first..=9999.999)
+                    .max_decimals(3),
+            );
+        });
+        cues.protected = protect.then_some(*protected);
+    });
+
+    ui.horizontal(|ui| {
+        let mut labelled = cues.label.is_some();
+        ui.checkbox(&mut labelled, "Label cues")
+            .on_hover_text("Label each cue with this and which frame it is, e.g. \"Apron 3/10\"");
+        ui.add_enabled(
+            labelled,
+            egui::TextEdit::singleline(label)
+                .hint_text("Path name")
+                .desired_width(100.0),
+        );
+        cues.label = labelled.then(|| label.clone());
+    });
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("First cue")
+            .selected_text(flag_name(cues.first_cue_flag))
+            .show_ui(ui, |ui| {
+                for flag in [None, Some(CueFlag::Mark), Some(CueFlag::Block)] {
+                    ui.selectable_value(&mut cues.first_cue_flag, flag, flag_name(flag));
+                }
+            })
+            .response
+            .on_hover_text("Mark the lights in to position before the move, or make it's first cue a block cue");
+        ui.checkbox(&mut cues.loop_back, "Loop")
+            .on_hover_text("Link the last cue back to the first, so the move goes round and round");
+    });
+
+    // Shown as the settings change so there are no surprises when the move is recorded
+    match cues.cue_numbers(frames as usize) {
+        Ok(cue_numbers) => {
+            if let (Some(first), Some(last)) = (cue_numbers.first(), cue_numbers.last()) {
+                ui.weak(format!("Records cues {first} to {last}"));
+            }
+        }
+        Err(e) => {
+            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use egui::{Color32, DragValue, Pos2, Rect, Sense, Stroke, Vec2};
+
+use crate::move_calculator::{Easing, Keyframe};
+
+/// How many straight lines the easing curve is drawn with
+const CURVE_SAMPLES: usize = 100;
+const GRAPH_SIZE: Vec2 = Vec2::new(200.0, 120.0);
+const CURVE_COLOUR: Color32 = Color32::from_rgb(80, 160, 255);
+const KEYFRAME_COLOUR: Color32 = Color32::from_rgb(255, 170, 0);
+
+/// What the easing is called in the UI
+fn easing_name(easing: &Easing) -> &'static str {
+    match easing {
+        Easing::Linear => "Linear",
+        Easing::EaseIn => "Ease in",
+        Easing::EaseOut => "Ease out",
+        Easing::EaseInOut => "Ease in and out",
+        Easing::SCurve => "S-curve",
+        Easing::Custom(_) => "Custom",
+    }
+}
+
+/// Shows a picker for how the lights speed up and slow down, a graph of it with a dot for each frame, and keyframes for custom easings
+pub fn easing_settings(ui: &mut egui::Ui, easing: &mut Easing, frames: u16) {
+    egui::ComboBox::from_label("Easing")
+        .selected_text(easing_name(easing))
+        .show_ui(ui, |ui| {
+            // Start custom easings off with a pause in the middle, so it's clear what the keyframes do
+            for option in [
+                Easing::Linear,
+                Easing::EaseIn,
+                Easing::EaseOut,
+                Easing::EaseInOut,
+                Easing::SCurve,
+                Easing::Custom(vec![
+                    Keyframe {
+                        time: 0.4,
+                        position: 0.5,
+                    },
+                    Keyframe {
+                        time: 0.6,
+                        position: 0.5,
+                    },
+                ]),
+            ] {
+                let selected = std::mem::discriminant(easing) == std::mem::discriminant(&option);
+                if ui
+                    .selectable_label(selected, easing_name(&option))
+                    .clicked()
+                    && !selected
+                {
+                    *easing = option;
+                }
+            }
+        })
+        .response
+        .on_hover_text("How the lights speed up and slow down along the path. Every frame takes the same time, so frames closer together are slower");
+
+    graph(ui, easing, frames);
+
+    if let Easing::Custom(keyframes) = easing {
+        keyframe_editor(ui, keyframes);
+    }
+}
+
+/// Draws how far along the path the lights are over the move, with the keyframes of custom easings draggable
+fn graph(ui: &mut egui::Ui, easing: &mut Easing, frames: u16) {
+    let (response, painter) = ui.allocate_painter(GRAPH_SIZE, Sense::hover());
+    let rect = response.rect.shrink(6.0);
+    painter.rect_filled(response.rect, 2.0, ui.visuals().extreme_bg_color);
+
+    // Time goes across and position along the path goes up
+    let to_screen = |time: f64, position: f64| {
+        Pos2::new(
+            rect.left() + rect.width() * time as f32,
+            rect.bottom() - rect.height() * position as f32,
+        )
+    };
+
+    let weak = ui.visuals().weak_text_color();
+    painter.rect_stroke(
+        Rect::from_min_max(to_screen(0.0, 1.0), to_screen(1.0, 0.0)),
+        0.0,
+        Stroke::new(1.0, weak.gamma_multiply(0.3)),
+        egui::StrokeKind::Middle,
+    );
+    painter.line_segment(
+        [to_screen(0.0, 0.0), to_screen(1.0, 1.0)],
+        Stroke::new(1.0, weak.gamma_multiply(0.3)),
+    );
+
+    let curve: Vec<Pos2> = (0..=CURVE_SAMPLES)
+        .map(|i| {
+            let time = i as f64 / CURVE_SAMPLES as f64;
+            to_screen(time, easing.position_at(time))
+        })
+        .collect();
+    painter.add(egui::Shape::line(curve, Stroke::new(2.0, CURVE_COLOUR)));
+
+    // The same times as `calculate_move` uses
+    for frame in 0..frames {
+        let time = frame as f64 / frames as f64;
+        painter.circle_filled(
+            to_screen(time, easing.position_at(time)),
+            2.5,
+            ui.visuals().text_color(),
+        );
+    }
+
+    let Easing::Custom(keyframes) = easing else {
+        return;
+    };
+    for (i, keyframe) in keyframes.iter_mut().enumerate() {
+        let centre = to_screen(keyframe.time, keyframe.position);
+        let handle = ui
+            .interact(
+                Rect::from_center_size(centre, Vec2::splat(12.0)),
+                response.id.with(i),
+                Sense::drag(),
+            )
+            .on_hover_cursor(egui::CursorIcon::Grab);
+        if handle.dragged() {
+            let delta = handle.drag_delta();
+            keyframe.time = (keyframe.time + (delta.x / rect.width()) as f64).clamp(0.0, 1.0);
+            keyframe.position =
+                (keyframe.position - (delta.y / rect.height()) as f64).clamp(0.0, 1.0);
+        }
+
+        let radius = if handle.hovered() || handle.dragged() {
+            6.0
+        } else {
+            4.5
+        };
+        painter.circle_filled(centre, radius, KEYFRAME_COLOUR);
+    }
+}
+
+/// Lists the keyframes of a custom easing to be typed in, added and removed
+fn keyframe_editor(ui: &mut egui::Ui, keyframes: &mut Vec<Keyframe>) {
+    let mut removed: Option<usize> = None;
+    egui::Grid::new("easing_keyframes").show(ui, |ui| {
+        ui.label("Time");
+        ui.label("Position");
+        ui.end_row();
+
+        for (i, keyframe) in keyframes.iter_mut().enumerate() {
+            percent_value(ui, &mut keyframe.time);
+            percent_value(ui, &mut keyframe.position);
+            if ui.button("🗑").on_hover_text("Remove keyframe").clicked() {
+                removed = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+
+    if let Some(i) = removed {
+        keyframes.remove(i);
+    }
+
+    if ui.button("➕ Add keyframe").clicked() {
+        // Halfway through the move, on the curve as it is now so nothing changes until it's moved
+        let position = Easing::Custom(keyframes.clone()).position_at(0.5);
+        keyframes.push(Keyframe {
+            time: 0.5,
+            position,
+        });
+    }
+}
+
+/// Edits a fraction (0 to 1) as a %
+fn percent_value(ui: &mut egui::Ui, fraction: &mut f64) {
+    let mut percent = *fraction * 100.0;
+    if ui
+        .add(DragValue::new(&mut percent).range(0.0..=100.0).suffix("%"))
+        .changed()
+    {
+        *fraction = percent / 100.0;
+    }
+}
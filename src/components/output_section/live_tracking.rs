@@ -0,0 +1,245 @@
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use egui::Key;
+
+use crate::{
+    app::GlobalState,
+    components::preferences::live_preferences::LivePreferences,
+    light::Light,
+    live::{LiveOutput, LiveTracker, MidiControl, OscControl, Throttle},
+    path::PathEnum,
+};
+
+/// Drives the selected lights along the selected path as the position changes, sending to the desk without recording cues
+pub struct LiveTracking {
+    /// How far along the path the lights are, in %
+    position: f64,
+    session: Option<LiveSession>,
+}
+
+/// Everything that's only needed while live
+struct LiveSession {
+    tracker: LiveTracker<PathEnum>,
+    output: LiveOutput,
+    throttle: Throttle,
+    preferences: LivePreferences,
+    /// Fractions along the path from the OSC and MIDI inputs
+    positions: mpsc::Receiver<f64>,
+    // Kept so the inputs carry on listening until the session ends
+    _osc: Option<OscControl>,
+    _midi: Option<MidiControl>,
+    /// The position has changed since it was last sent
+    pending: bool,
+}
+
+impl LiveTracking {
+    pub fn new() -> Self {
+        Self {
+            position: 0.0,
+            session: None,
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Stops sending to the desk and stops listening to the inputs
+    pub fn stop(&mut self) {
+        self.session = None;
+    }
+
+    /// Connects to the desk and starts listening to the inputs, toasting anything that goes wrong
+    fn start(
+        &mut self,
+        app_state: &mut GlobalState,
+        path: Option<PathEnum>,
+        lights: Vec<Light>,
+        constant_speed: bool,
+    ) {
+        let Some(path) = path else {
+            app_state
+                .toasts
+                .warning("No path is selected - try selecting a path");
+            return;
+        };
+
+        let tracker = match LiveTracker::new(path, lights, constant_speed) {
+            Ok(tracker) => tracker,
+            Err(e) => {
+                app_state
+                    .toasts
+                    .warning(format!("{e} - try selecting some lights"));
+                return;
+            }
+        };
+
+        let config = match app_state.config_file.read() {
+            Ok(config) => config,
+            Err(e) => {
+                app_state
+                    .toasts
+                    .error(format!("Failed to read config from file: {e}"));
+                return;
+            }
+        };
+
+        let output = match LiveOutput::connect(&config.osc.connection()) {
+            Ok(output) => output,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+                return;
+            }
+        };
+
+        // An input that fails to start is toasted, the others can still be used
+        let preferences = config.live;
+        let (sender, positions) = mpsc::channel();
+        let osc = if preferences.osc_enabled {
+            OscControl::listen(
+                preferences.osc_port,
+                preferences.osc_address.clone(),
+                sender.clone(),
+            )
+            .inspect_err(|e| {
+                app_state.toasts.error(format!(
+                    "Failed to listen for OSC on port {}: {e}",
+                    preferences.osc_port
+                ));
+            })
+            .ok()
+        } else {
+            None
+        };
+        let midi = if preferences.midi_enabled {
+            MidiControl::listen(
+                &preferences.midi_port,
+                preferences.midi_channel,
+                preferences.midi_controller,
+                sender,
+            )
+            .inspect_err(|e| {
+                app_state
+                    .toasts
+                    .error(format!("Failed to listen for MIDI: {e}"));
+            })
+            .ok()
+        } else {
+            None
+        };
+
+        self.session = Some(LiveSession {
+            tracker,
+            output,
+            throttle: Throttle::new(preferences.updates_per_second),
+            preferences,
+            positions,
+            _osc: osc,
+            _midi: midi,
+            // Send where the lights should be straight away
+            pending: true,
+        });
+    }
+
+    /// Adds the live controls. The path and lights are only read when going live
+    pub fn add(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        app_state: &mut GlobalState,
+        path: impl FnOnce() -> Option<PathEnum>,
+        lights: impl FnOnce() -> Vec<Light>,
+        constant_speed: bool,
+    ) {
+        ui.heading("Live tracking");
+
+        let mut live = self.is_live();
+        let label = if live { "⏹ Stop" } else { "▶ Go live" };
+        if ui
+            .toggle_value(&mut live, label)
+            .on_hover_text("Point the selected lights along the selected path as the position changes, without recording cues")
+            .changed()
+        {
+            if live {
+                self.start(app_state, path(), lights(), constant_speed);
+            } else {
+                self.stop();
+            }
+        }
+
+        let Some(session) = &mut self.session else {
+            ui.add_enabled(false, egui::Slider::new(&mut self.position, 0.0..=100.0));
+            return;
+        };
+
+        let mut position = self.position;
+        if ui
+            .add(egui::Slider::new(&mut position, 0.0..=100.0).suffix("%"))
+            .changed()
+        {
+            session.pending = true;
+        }
+
+        // The arrow keys are left to text boxes and sliders when they have focus
+        if ctx.memory(|memory| memory.focused().is_none()) {
+            let step = session.preferences.hotkey_step;
+            ctx.input(|input| {
+                if input.key_pressed(Key::ArrowRight) {
+                    position += step;
+                }
+                if input.key_pressed(Key::ArrowLeft) {
+                    position -= step;
+                }
+                if input.key_pressed(Key::Home) {
+                    position = 0.0;
+                }
+                if input.key_pressed(Key::End) {
+                    position = 100.0;
+                }
+            });
+        }
+
+        // Only the latest position from the inputs matters
+        if let Some(fraction) = session.positions.try_iter().last() {
+            position = fraction * 100.0;
+        }
+
+        position = position.clamp(0.0, 100.0);
+        if position != self.position {
+            self.position = position;
+            session.pending = true;
+        }
+
+        ui.weak(format!(
+            "←/→ move {}%, Home/End jump to the ends",
+            session.preferences.hotkey_step
+        ));
+
+        if session.pending {
+            let now = Instant::now();
+            let wait = session.throttle.remaining(now);
+            if wait.is_zero() {
+                let commands = session.tracker.move_to(self.position / 100.0);
+                session.throttle.updated(now);
+                session.pending = false;
+
+                // Stop rather than toast every update while the desk can't be reached
+                if let Err(e) = session.output.send(&commands) {
+                    app_state
+                        .toasts
+                        .error(format!("Stopped live tracking: {e}"));
+                    self.stop();
+                    return;
+                }
+            } else {
+                ctx.request_repaint_after(wait);
+            }
+        }
+
+        // Keep checking the inputs, they don't know about egui
+        ctx.request_repaint_after(Duration::from_millis(20));
+    }
+}
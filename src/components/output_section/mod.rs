@@ -1,18 +1,46 @@
+use std::time::Duration;
+
 use egui::DragValue;
 
 use crate::app::GlobalState;
 use crate::components::select_lights_modal::SelectLightsModal;
 use crate::components::select_path_modal::SelectPathModal;
+use crate::light::Light;
+use crate::move_calculator::{self, CueRange, CueSettings, MoveOptions, OutputJob, Spread};
+use crate::path::{Path, PathEnum};
+
+mod command_preview;
+mod cue_settings;
+mod easing_settings;
+mod live_tracking;
 
-pub mod move_calculator;
+use command_preview::CommandPreviewWindow;
+use cue_settings::cue_settings;
+use easing_settings::easing_settings;
+use live_tracking::LiveTracking;
 
 pub struct OutputSection {
     select_lights_modal: SelectLightsModal,
     select_path_modal: SelectPathModal,
     selected_output_type: OutputType,
-    move_time: f64,
-    frames: u16,
-    cue_number: u32,
+    options: MoveOptions,
+    cues: CueSettings,
+    /// The cues to protect, kept while protecting them is turned off
+    protected_cues: CueRange,
+    /// The cue label, kept while labelling is turned off
+    cue_label: String,
+    command_preview: CommandPreviewWindow,
+    /// The move being sent to the desk, if there is one
+    output_job: Option<OutputJob>,
+    live_tracking: LiveTracking,
+}
+
+/// What the move is made of, as it's set up in the output section
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveSettings {
+    pub path: PathEnum,
+    pub lights: Vec<Light>,
+    pub options: MoveOptions,
 }
 
 #[derive(Debug, PartialEq)]
@@ -27,16 +55,52 @@ impl OutputSection {
             select_lights_modal: SelectLightsModal::new("Select lights for move"),
             select_path_modal: SelectPathModal::new(),
             selected_output_type: OutputType::Instructions,
-            move_time: 1.0,
-            frames: 10,
-            cue_number: 1,
+            options: MoveOptions::default(),
+            cues: CueSettings::default(),
+            protected_cues: CueRange {
+                first: 1.0,
+                last: 1.0,
+            },
+            cue_label: String::new(),
+            command_preview: CommandPreviewWindow::new(),
+            output_job: None,
+            live_tracking: LiveTracking::new(),
+        }
+    }
+
+    /// Forgets selected lights and paths that aren't in the newly opened project
+    pub fn project_changed(&mut self, app_state: &mut GlobalState) {
+        self.live_tracking.stop();
+        // The commands being previewed are for the old project's lights
+        self.command_preview.shown = false;
+        self.select_lights_modal.update_lights(app_state);
+        self.select_path_modal.update_paths(app_state);
+    }
+
+    /// The move as it's set up now, None if there isn't a path and some lights selected
+    pub fn move_settings(&self) -> Option<MoveSettings> {
+        let lights = self.select_lights_modal.get_selected_lights();
+        if lights.is_empty() {
+            return None;
         }
+
+        Some(MoveSettings {
+            path: self.select_path_modal.get_selected_path()?,
+            lights,
+            options: self.options.clone(),
+        })
     }
 
     pub fn add(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, app_state: &mut GlobalState) {
         // Add modals
         self.select_lights_modal.add(ctx);
         self.select_path_modal.add(ctx);
+        if let Some(commands) = self
+            .command_preview
+            .add(ctx, app_state, self.output_job.is_none())
+        {
+            self.send_commands(commands, app_state);
+        }
 
         ui.vertical_centered(|ui| {
             ui.heading("Output settings");
@@ -60,52 +124,82 @@ impl OutputSection {
 
             ui.horizontal(|ui| {
                 ui.label("Move time");
-                ui.add(DragValue::new(&mut self.move_time).speed(0.1));
+                ui.add(DragValue::new(&mut self.options.move_time).speed(0.1));
                 ui.label("s");
             });
 
             ui.horizontal(|ui| {
                 ui.label("Frames");
-                ui.add(DragValue::new(&mut self.frames));
+                ui.add(DragValue::new(&mut self.options.frames));
             });
 
-            ui.horizontal(|ui| {
-                ui.label("Cue Number");
-                ui.add(DragValue::new(&mut self.cue_number));
-            });
+            ui.checkbox(&mut self.options.constant_speed, "Constant speed")
+                .on_hover_text("Space frames evenly along the path so the beam moves at the same speed all the way through");
+
+            spread_settings(ui, &mut self.options.spread);
+            easing_settings(ui, &mut self.options.easing, self.options.frames);
+
+            cue_settings(
+                ui,
+                &mut self.cues,
+                &mut self.protected_cues,
+                &mut self.cue_label,
+                self.options.frames,
+            );
 
             egui::ComboBox::from_label("Output Type")
                 .selected_text(format!("{:?}", self.selected_output_type))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut self.selected_output_type, OutputType::Osc, "OSC");
+                    ui.selectable_value(&mut self.selected_output_type, OutputType::Osc, "OSC")
+                        .on_hover_text("Send the move to the desk once it's been confirmed");
                     ui.selectable_value(
                         &mut self.selected_output_type,
                         OutputType::Instructions,
                         "Instructions",
-                    );
+                    )
+                    .on_hover_text("Show the commands to check, copy or save before sending them");
                 });
 
-            if ui.button("Execute move").clicked() {
+            let button = match self.selected_output_type {
+                OutputType::Osc => "Execute move",
+                OutputType::Instructions => "Preview move",
+            };
+            if ui
+                .add_enabled(self.output_job.is_none(), egui::Button::new(button))
+                .on_disabled_hover_text("Wait for the last move to finish sending")
+                .clicked()
+            {
                 self.execute_move(app_state);
             }
 
+            self.output_progress(ctx, ui, app_state);
+
+            ui.separator();
+            self.live_tracking.add(
+                ctx,
+                ui,
+                app_state,
+                || self.select_path_modal.get_selected_path(),
+                || self.select_lights_modal.get_selected_lights(),
+                self.options.constant_speed,
+            );
+
             // Ensure that move time is never negative. Time travel doesn't exist!
-            if self.move_time < 0.0 {
-                self.move_time = 0.0
+            if self.options.move_time < 0.0 {
+                self.options.move_time = 0.0
             }
 
-            // If there are more than 99 frames then there's no way to number then in EOS with the current system I'm using.
             // It makes no sense to have 0 frames
-            self.frames = self.frames.clamp(1, 99);
+            self.options.frames = self.options.frames.max(1);
 
             // There's no cue 0 in EOS
-            if self.cue_number == 0 {
-                self.cue_number = 1
+            if self.cues.first_cue == 0 {
+                self.cues.first_cue = 1
             }
         });
     }
 
-    fn execute_move(&self, app_state: &mut GlobalState) {
+    fn execute_move(&mut self, app_state: &mut GlobalState) {
         let some_paths = self.select_path_modal.get_selected_path();
         let lights = self.select_lights_modal.get_selected_lights();
 
@@ -126,10 +220,43 @@ impl OutputSection {
             return;
         }
 
-        let frames = move_calculator::calculate_move(path, lights, self.frames, self.move_time);
-        let commands = move_calculator::frames_to_commands(frames, self.cue_number);
-        match move_calculator::output_commands(commands, app_state) {
-            Ok(_) => {}
+        let frames = match move_calculator::calculate_move(&path, &lights, &self.options) {
+            Ok(frames) => frames,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+                return;
+            }
+        };
+        let mut cues = self.cues.clone();
+        if cues
+            .label
+            .as_ref()
+            .is_some_and(|label| label.trim().is_empty())
+        {
+            cues.label = Some(path.name());
+        }
+        let frame_commands = match move_calculator::frame_commands(&frames, &cues) {
+            Ok(frame_commands) => frame_commands,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+                return;
+            }
+        };
+
+        match self.selected_output_type {
+            // Still confirmed first, so cues on a live desk can't be recorded over by accident
+            OutputType::Osc => self.command_preview.show_to_send(frame_commands),
+            OutputType::Instructions => self.command_preview.show(frame_commands),
+        }
+    }
+
+    /// Starts sending commands to the desk in the background, see `output_progress`
+    fn send_commands(&mut self, commands: Vec<String>, app_state: &mut GlobalState) {
+        // Get the IP addresses from config
+        match app_state.config_file.read() {
+            Ok(config) => {
+                self.output_job = Some(OutputJob::start(commands, config.osc.connection()));
+            }
             Err(e) => {
                 app_state
                     .toasts
@@ -137,4 +264,119 @@ impl OutputSection {
             }
         }
     }
+
+    /// Shows how far through sending the move is, and toasts any commands that failed
+    fn output_progress(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        app_state: &mut GlobalState,
+    ) {
+        let Some(job) = &mut self.output_job else {
+            return;
+        };
+
+        // Checked before taking the failures, so none can arrive after they've been shown
+        let finished = job.is_finished();
+        for failure in job.failures() {
+            app_state
+                .toasts
+                .error(format!("Failed to output commands over OSC: {failure}"));
+        }
+
+        let (sent, total) = job.progress();
+        if !finished {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::ProgressBar::new(sent as f32 / total.max(1) as f32)
+                        .desired_width(160.0)
+                        .text(format!("Sent {sent} of {total} commands")),
+                );
+                if ui.button("Cancel").clicked() {
+                    job.cancel();
+                }
+            });
+            // The worker doesn't know about egui, so keep checking on it
+            ctx.request_repaint_after(Duration::from_millis(50));
+            return;
+        }
+
+        if job.is_cancelled() {
+            app_state.toasts.info(format!(
+                "Cancelled the move after sending {sent} of {total} commands"
+            ));
+        } else if sent == total {
+            app_state
+                .toasts
+                .success(format!("Sent all {total} commands"));
+        } else {
+            app_state
+                .toasts
+                .warning(format!("Only {sent} of {total} commands were sent"));
+        }
+        self.output_job = None;
+    }
+}
+
+/// What the spread is called in the UI
+fn spread_name(spread: Spread) -> &'static str {
+    match spread {
+        Spread::Together => "Together",
+        Spread::Phase(_) => "Phase (%)",
+        Spread::PhaseDistance(_) => "Phase (m)",
+        Spread::Fan => "Fan",
+    }
+}
+
+/// Shows a picker for how the lights are spread along the path, with the gap between them for phases
+fn spread_settings(ui: &mut egui::Ui, spread: &mut Spread) {
+    egui::ComboBox::from_label("Spread")
+        .selected_text(spread_name(*spread))
+        .show_ui(ui, |ui| {
+            // Start phases off with a gap that can be seen
+            for option in [
+                Spread::Together,
+                Spread::Phase(0.1),
+                Spread::PhaseDistance(1.0),
+                Spread::Fan,
+            ] {
+                let selected = std::mem::discriminant(spread) == std::mem::discriminant(&option);
+                if ui
+                    .selectable_label(selected, spread_name(option))
+                    .clicked()
+                    && !selected
+                {
+                    *spread = option;
+                }
+            }
+        })
+        .response
+        .on_hover_text("Where each light is along the path compared to the others, in the order they were selected");
+
+    match spread {
+        Spread::Phase(fraction) => {
+            let mut percent = *fraction * 100.0;
+            ui.horizontal(|ui| {
+                ui.label("Each light trails by");
+                if ui
+                    .add(DragValue::new(&mut percent).range(0.0..=100.0).suffix("%"))
+                    .changed()
+                {
+                    *fraction = percent / 100.0;
+                }
+            });
+        }
+        Spread::PhaseDistance(distance) => {
+            ui.horizontal(|ui| {
+                ui.label("Each light trails by");
+                ui.add(
+                    DragValue::new(distance)
+                        .range(0.0..=f64::MAX)
+                        .speed(0.1)
+                        .suffix(" m"),
+                );
+            });
+        }
+        Spread::Together | Spread::Fan => {}
+    }
 }
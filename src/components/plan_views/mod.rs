@@ -0,0 +1,391 @@
+use egui::{Align2, Color32, DragValue, FontId, Pos2, Sense, Stroke};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    light::Light,
+    path::{Path, PathEnum},
+};
+
+mod projection;
+
+use projection::{nice_length, Projection, View};
+
+/// How many straight lines each path is drawn with
+const PATH_SAMPLES: usize = 200;
+/// How close (in pixels) the mouse has to be to a point to pick it up
+const GRAB_DISTANCE: f32 = 8.0;
+
+const LIGHT_COLOUR: Color32 = Color32::from_rgb(255, 170, 0);
+const PATH_COLOUR: Color32 = Color32::from_rgb(80, 160, 255);
+
+/// Something in the rig that can be dragged around
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Handle {
+    /// The light at this index in `PlanViewsWindow::lights`
+    Light(usize),
+    /// A control point of the path at this index in `PlanViewsWindow::paths`, see `PathEnum::control_points`
+    PathPoint { path: usize, point: usize },
+}
+
+/// Top, front and side views of the rig, where lights and the control points of paths can be dragged around
+pub struct PlanViewsWindow {
+    pub shown: bool,
+    view: View,
+    /// The point in the rig at the middle of the view
+    centre: Vector3d<f64>,
+    /// Pixels per metre
+    scale: f64,
+    snap: bool,
+    /// In metres
+    grid_size: f64,
+    lights: Vec<Light>,
+    paths: Vec<PathEnum>,
+    /// `Database::changes` when the lights and paths were last read, None to read them again
+    read_at: Option<u64>,
+    /// What's being dragged, it's saved when it's let go
+    dragging: Option<Handle>,
+}
+
+impl PlanViewsWindow {
+    pub fn new() -> Self {
+        Self {
+            shown: false,
+            view: View::Plan,
+            centre: Vector3d::default(),
+            scale: 40.0,
+            snap: true,
+            grid_size: 0.5,
+            lights: vec![],
+            paths: vec![],
+            read_at: None,
+            dragging: None,
+        }
+    }
+
+    /// Reads everything from the newly opened project
+    pub fn project_changed(&mut self) {
+        self.read_at = None;
+        self.dragging = None;
+    }
+
+    /// Reads the lights and paths again if the database has changed since they were last read
+    fn reload(&mut self, app_state: &mut GlobalState) {
+        let changes = app_state.database.changes();
+        // Reading in the middle of a drag would put the point back where it started
+        if self.read_at == Some(changes) || self.dragging.is_some() {
+            return;
+        }
+        // Set first so a broken database isn't read, and toasted, every frame
+        self.read_at = Some(changes);
+
+        match app_state.database.get_lights() {
+            Ok(lights) => self.lights = lights,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+
+        match app_state.database.get_paths() {
+            Ok(paths) => self.paths = paths,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+    }
+
+    /// Where a handle is in the rig
+    fn handle_position(&self, handle: Handle) -> Option<Vector3d<f64>> {
+        match handle {
+            Handle::Light(light) => self.lights.get(light).map(|light| light.coordinates),
+            Handle::PathPoint { path, point } => self
+                .paths
+                .get(path)
+                .and_then(|path| path.control_points().get(point).copied()),
+        }
+    }
+
+    /// Every handle, lights first so they're picked up over a path point in the same place
+    fn handles(&self) -> Vec<Handle> {
+        let lights = (0..self.lights.len()).map(Handle::Light);
+        let path_points = self.paths.iter().enumerate().flat_map(|(path, path_enum)| {
+            (0..path_enum.control_points().len())
+                .map(move |point| Handle::PathPoint { path, point })
+        });
+        lights.chain(path_points).collect()
+    }
+
+    /// The handle closest to `position` on the screen, if there's one close enough to pick up
+    fn handle_at(&self, projection: &Projection, position: Pos2) -> Option<Handle> {
+        self.handles()
+            .into_iter()
+            .filter_map(|handle| {
+                let distance = projection
+                    .project(self.handle_position(handle)?)
+                    .distance(position);
+                (distance <= GRAB_DISTANCE).then_some((handle, distance))
+            })
+            // The first of any that are the same distance away wins, see `handles`
+            .min_by(|(_, distance_1), (_, distance_2)| distance_1.total_cmp(distance_2))
+            .map(|(handle, _)| handle)
+    }
+
+    /// Moves a handle to a new position in the rig
+    fn move_handle(&mut self, handle: Handle, position: Vector3d<f64>) {
+        match handle {
+            Handle::Light(light) => {
+                if let Some(light) = self.lights.get_mut(light) {
+                    light.coordinates = position;
+                }
+            }
+            Handle::PathPoint { path, point } => {
+                if let Some(control_point) = self
+                    .paths
+                    .get_mut(path)
+                    .and_then(|path| path.control_points_mut().into_iter().nth(point))
+                {
+                    *control_point = position;
+                }
+            }
+        }
+    }
+
+    /// Writes the light or path a handle belongs to back to the database
+    fn save_handle(&mut self, app_state: &mut GlobalState, handle: Handle) {
+        let result = match handle {
+            Handle::Light(light) => self
+                .lights
+                .get(light)
+                .map(|light| (light.name.clone(), app_state.database.update_light(light))),
+            Handle::PathPoint { path, .. } => self
+                .paths
+                .get(path)
+                .map(|path| (path.name(), app_state.database.update_path(path))),
+        };
+
+        if let Some((name, Err(e))) = result {
+            app_state
+                .toasts
+                .error(format!("Failed to move {name}: {e}"));
+        }
+        // Read everything again, which puts it back if it couldn't be saved and updates composite paths
+        self.read_at = None;
+    }
+
+    pub fn add(&mut self, ctx: &egui::Context, app_state: &mut GlobalState) {
+        if !self.shown {
+            return;
+        }
+        self.reload(app_state);
+
+        let mut shown = self.shown;
+        egui::Window::new("Plan and section views")
+            .collapsible(false)
+            .resizable(true)
+            .fade_in(true)
+            .fade_out(true)
+            .default_size([600.0, 450.0])
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for view in View::ALL {
+                        ui.selectable_value(&mut self.view, view, view.name());
+                    }
+                    ui.separator();
+                    ui.checkbox(&mut self.snap, "Snap to grid");
+                    ui.add(
+                        DragValue::new(&mut self.grid_size)
+                            .range(0.01..=10.0)
+                            .speed(0.01)
+                            .suffix(" m"),
+                    );
+                });
+                ui.weak("Drag lights and path points to move them, drag anywhere else to pan and scroll to zoom");
+
+                self.canvas(ui, app_state);
+            });
+        self.shown = shown;
+    }
+
+    /// Draws the current view and handles the mouse
+    fn canvas(&mut self, ui: &mut egui::Ui, app_state: &mut GlobalState) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let projection = Projection {
+            view: self.view,
+            centre: self.centre,
+            scale: self.scale,
+            rect: response.rect,
+        };
+
+        if response.drag_started() {
+            // Where the button went down, the pointer has already moved a bit by the time it counts as a drag
+            self.dragging = ui
+                .input(|input| input.pointer.press_origin())
+                .and_then(|position| self.handle_at(&projection, position));
+        }
+        if response.dragged() {
+            match (self.dragging, response.interact_pointer_pos()) {
+                (Some(handle), Some(pointer)) => {
+                    if let Some(old_position) = self.handle_position(handle) {
+                        let mut position = projection.unproject(pointer, old_position);
+                        if self.snap {
+                            position = projection.snap(position, self.grid_size);
+                        }
+                        self.move_handle(handle, position);
+                    }
+                }
+                _ => self.centre = projection.pan(response.drag_delta()),
+            }
+        }
+        if response.drag_stopped() {
+            if let Some(handle) = self.dragging.take() {
+                self.save_handle(app_state, handle);
+            }
+        }
+        if response.hovered() {
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                self.scale = (self.scale * (scroll as f64 * 0.002).exp()).clamp(2.0, 2000.0);
+            }
+        }
+
+        painter.rect_filled(response.rect, 0.0, ui.visuals().extreme_bg_color);
+        let text_colour = ui.visuals().text_color();
+        self.draw_grid(&painter, &projection, ui.visuals().weak_text_color());
+
+        for path in &self.paths {
+            let points: Vec<Pos2> = path
+                .points(PATH_SAMPLES)
+                .into_iter()
+                .map(|point| projection.project(point))
+                .collect();
+            if let Some(start) = points.first() {
+                painter.text(
+                    *start,
+                    Align2::LEFT_BOTTOM,
+                    path.name(),
+                    FontId::proportional(12.0),
+                    PATH_COLOUR,
+                );
+            }
+            painter.add(egui::Shape::line(points, Stroke::new(2.0, PATH_COLOUR)));
+
+            for point in path.control_points() {
+                painter.rect_stroke(
+                    egui::Rect::from_center_size(projection.project(point), egui::vec2(7.0, 7.0)),
+                    0.0,
+                    Stroke::new(1.5, text_colour),
+                    egui::StrokeKind::Middle,
+                );
+            }
+        }
+
+        for light in &self.lights {
+            let position = projection.project(light.coordinates);
+            painter.circle_filled(position, 5.0, LIGHT_COLOUR);
+            painter.text(
+                position - egui::vec2(0.0, 8.0),
+                Align2::CENTER_BOTTOM,
+                &light.name,
+                FontId::proportional(12.0),
+                text_colour,
+            );
+        }
+
+        // Show where the handle under the mouse, or being dragged, is
+        let highlighted = self.dragging.or_else(|| {
+            response
+                .hover_pos()
+                .and_then(|position| self.handle_at(&projection, position))
+        });
+        if let Some(position) = highlighted.and_then(|handle| self.handle_position(handle)) {
+            let on_screen = projection.project(position);
+            painter.circle_stroke(on_screen, GRAB_DISTANCE, Stroke::new(2.0, text_colour));
+            painter.text(
+                on_screen + egui::vec2(GRAB_DISTANCE, GRAB_DISTANCE),
+                Align2::LEFT_TOP,
+                format!("({:.2}, {:.2}, {:.2})", position.x, position.y, position.z),
+                FontId::monospace(11.0),
+                text_colour,
+            );
+        }
+    }
+
+    /// Draws the grid lines, the axes through the origin and a scale bar
+    fn draw_grid(&self, painter: &egui::Painter, projection: &Projection, colour: Color32) {
+        let rect = projection.rect;
+        let (across_name, up_name) = self.view.axis_names();
+
+        // Leave out lines that would be too close together to see
+        let spacing = if self.grid_size * self.scale >= 8.0 {
+            self.grid_size
+        } else {
+            nice_length(self.scale, 8.0)
+        };
+        let top_left = projection.unproject(rect.left_top(), self.centre);
+        let bottom_right = projection.unproject(rect.right_bottom(), self.centre);
+        let (across, up) = self.view.axes();
+
+        let grid_stroke = Stroke::new(1.0, colour.gamma_multiply(0.25));
+        let axis_stroke = Stroke::new(1.5, colour);
+
+        let mut value = (top_left[across] / spacing).floor() * spacing;
+        while value <= bottom_right[across] {
+            let x = projection.project(with_axis(across, value)).x;
+            let stroke = if value.abs() < spacing / 2.0 {
+                axis_stroke
+            } else {
+                grid_stroke
+            };
+            painter.vline(x, rect.y_range(), stroke);
+            value += spacing;
+        }
+        let mut value = (bottom_right[up] / spacing).floor() * spacing;
+        while value <= top_left[up] {
+            let y = projection.project(with_axis(up, value)).y;
+            let stroke = if value.abs() < spacing / 2.0 {
+                axis_stroke
+            } else {
+                grid_stroke
+            };
+            painter.hline(rect.x_range(), y, stroke);
+            value += spacing;
+        }
+
+        let origin = projection.project(Vector3d::default());
+        painter.text(
+            Pos2::new(rect.right() - 4.0, origin.y),
+            Align2::RIGHT_BOTTOM,
+            format!("{across_name} →"),
+            FontId::proportional(12.0),
+            colour,
+        );
+        painter.text(
+            Pos2::new(origin.x + 4.0, rect.top() + 4.0),
+            Align2::LEFT_TOP,
+            format!("{up_name} ↑"),
+            FontId::proportional(12.0),
+            colour,
+        );
+
+        // A bar in the bottom left corner showing how long a round number of metres is
+        let length = nice_length(self.scale, 60.0);
+        let start = rect.left_bottom() + egui::vec2(12.0, -12.0);
+        let end = start + egui::vec2((length * self.scale) as f32, 0.0);
+        painter.line_segment([start, end], Stroke::new(2.0, colour));
+        painter.text(
+            start - egui::vec2(0.0, 4.0),
+            Align2::LEFT_BOTTOM,
+            format!("{length} m"),
+            FontId::proportional(12.0),
+            colour,
+        );
+    }
+}
+
+/// A point that's `value` along one axis and 0 along the others
+fn with_axis(axis: usize, value: f64) -> Vector3d<f64> {
+    let mut point = Vector3d::default();
+    point[axis] = value;
+    point
+}
@@ -0,0 +1,179 @@
+use egui::{Pos2, Rect, Vec2};
+use vector3d::Vector3d;
+
+/// Which way the rig is looked at. Each view flattens it on to two of the axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    /// Looking down from above, x right and y up the screen
+    Plan,
+    /// Looking at the stage from the front, x right and z up the screen
+    Front,
+    /// Looking from the side, y right and z up the screen
+    Side,
+}
+
+impl View {
+    pub const ALL: [View; 3] = [View::Plan, View::Front, View::Side];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            View::Plan => "Plan",
+            View::Front => "Front",
+            View::Side => "Side",
+        }
+    }
+
+    /// The indexes of the axes across and up the screen, for indexing a `Vector3d`
+    pub fn axes(self) -> (usize, usize) {
+        match self {
+            View::Plan => (0, 1),
+            View::Front => (0, 2),
+            View::Side => (1, 2),
+        }
+    }
+
+    /// The names of the axes across and up the screen
+    pub fn axis_names(self) -> (&'static str, &'static str) {
+        const NAMES: [&str; 3] = ["x", "y", "z"];
+        let (across, up) = self.axes();
+        (NAMES[across], NAMES[up])
+    }
+}
+
+/// Turns points in the rig in to points on the screen for a view, and back
+#[derive(Debug, Clone, Copy)]
+pub struct Projection {
+    pub view: View,
+    /// The point in the rig at the middle of `rect`. The axis the view looks along is ignored
+    pub centre: Vector3d<f64>,
+    /// Pixels per metre
+    pub scale: f64,
+    pub rect: Rect,
+}
+
+impl Projection {
+    /// Where a point in the rig is drawn
+    pub fn project(&self, point: Vector3d<f64>) -> Pos2 {
+        let (across, up) = self.view.axes();
+        self.rect.center()
+            + Vec2::new(
+                ((point[across] - self.centre[across]) * self.scale) as f32,
+                (-(point[up] - self.centre[up]) * self.scale) as f32,
+            )
+    }
+
+    /// Moves `point` to under `position` on the screen. The axis the view looks along is left as it was
+    pub fn unproject(&self, position: Pos2, mut point: Vector3d<f64>) -> Vector3d<f64> {
+        let (across, up) = self.view.axes();
+        let offset = position - self.rect.center();
+        point[across] = self.centre[across] + offset.x as f64 / self.scale;
+        point[up] = self.centre[up] - offset.y as f64 / self.scale;
+        point
+    }
+
+    /// Rounds the two axes this view shows to the nearest multiple of `grid` (in metres)
+    pub fn snap(&self, mut point: Vector3d<f64>, grid: f64) -> Vector3d<f64> {
+        let (across, up) = self.view.axes();
+        point[across] = snap(point[across], grid);
+        point[up] = snap(point[up], grid);
+        point
+    }
+
+    /// Moves the view by a mouse drag in pixels, so whatever's under the mouse stays under it
+    pub fn pan(&self, drag: Vec2) -> Vector3d<f64> {
+        let (across, up) = self.view.axes();
+        let mut centre = self.centre;
+        centre[across] -= drag.x as f64 / self.scale;
+        centre[up] += drag.y as f64 / self.scale;
+        centre
+    }
+}
+
+/// Rounds `value` to the nearest multiple of `grid`
+pub fn snap(value: f64, grid: f64) -> f64 {
+    if grid <= 0.0 {
+        return value;
+    }
+    (value / grid).round() * grid
+}
+
+/// A round length in metres (1, 2 or 5 times a power of 10) that's at least `min_pixels` long on the screen
+pub fn nice_length(scale: f64, min_pixels: f64) -> f64 {
+    let min_length = min_pixels / scale;
+    let power = 10_f64.powf(min_length.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|step| step * power)
+        .find(|length| *length >= min_length)
+        .unwrap_or(10.0 * power)
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn projection(view: View) -> Projection {
+        Projection {
+            view,
+            centre: Vector3d::new(1.0, 2.0, 3.0),
+            scale: 50.0,
+            rect: Rect::from_min_size(Pos2::ZERO, Vec2::new(400.0, 300.0)),
+        }
+    }
+
+    #[test]
+    fn test_to_screen() {
+        let plan = projection(View::Plan);
+        assert_eq!(
+            plan.project(Vector3d::new(1.0, 2.0, 100.0)),
+            Pos2::new(200.0, 150.0)
+        );
+        assert_eq!(
+            plan.project(Vector3d::new(2.0, 3.0, 0.0)),
+            Pos2::new(250.0, 100.0)
+        );
+
+        let side = projection(View::Side);
+        assert_eq!(
+            side.project(Vector3d::new(100.0, 3.0, 2.0)),
+            Pos2::new(250.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn test_from_screen_keeps_depth() {
+        for view in View::ALL {
+            let projection = projection(view);
+            let point = Vector3d::new(-1.5, 4.0, 2.25);
+            let moved = projection.unproject(projection.project(point), Vector3d::default());
+
+            let (across, up) = view.axes();
+            assert_eq!(moved[across], point[across]);
+            assert_eq!(moved[up], point[up]);
+            // The axis that can't be seen isn't changed
+            assert_eq!(moved[3 - across - up], 0.0);
+        }
+    }
+
+    #[test]
+    fn test_snap() {
+        assert_eq!(snap(1.26, 0.25), 1.25);
+        assert_eq!(snap(-0.6, 0.5), -0.5);
+        assert_eq!(snap(1.26, 0.0), 1.26);
+
+        let front = projection(View::Front);
+        assert_eq!(
+            front.snap(Vector3d::new(0.9, 0.33, 2.1), 1.0),
+            Vector3d::new(1.0, 0.33, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_nice_length() {
+        assert_eq!(nice_length(50.0, 60.0), 2.0);
+        assert_eq!(nice_length(50.0, 100.0), 2.0);
+        assert_eq!(nice_length(50.0, 101.0), 5.0);
+        assert_eq!(nice_length(1000.0, 60.0), 0.1);
+    }
+}
@@ -0,0 +1,144 @@
+use egui::DragValue;
+use serde::{Deserialize, Serialize};
+
+use crate::{components::preferences::PreferenceItem, live::midi_ports};
+
+/// Settings for tracking lights along a path live
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivePreferences {
+    /// The most times a second new pans and tilts are sent to the desk
+    pub updates_per_second: f64,
+    /// How far (in %) along the path the arrow keys move the lights
+    pub hotkey_step: f64,
+    pub osc_enabled: bool,
+    /// The port to listen for positions on
+    pub osc_port: u16,
+    pub osc_address: String,
+    pub midi_enabled: bool,
+    /// The name of the MIDI input to listen to, as the system lists it. Empty uses the first one there is
+    pub midi_port: String,
+    /// 1 to 16
+    pub midi_channel: u8,
+    pub midi_controller: u8,
+}
+
+impl Default for LivePreferences {
+    fn default() -> Self {
+        Self {
+            updates_per_second: 30.0,
+            hotkey_step: 1.0,
+            osc_enabled: false,
+            // One up from the port EOS listens on
+            osc_port: 8001,
+            osc_address: "/two_mover/position".to_string(),
+            midi_enabled: false,
+            midi_port: String::new(),
+            midi_channel: 1,
+            midi_controller: 1,
+        }
+    }
+}
+
+impl PreferenceItem for LivePreferences {
+    fn show(&mut self, ui: &mut egui::Ui, global_state: &mut crate::app::GlobalState) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Update rate");
+                ui.add(
+                    DragValue::new(&mut self.updates_per_second)
+                        .range(1.0..=100.0)
+                        .suffix(" updates/s"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Arrow key step");
+                ui.add(
+                    DragValue::new(&mut self.hotkey_step)
+                        .range(0.1..=25.0)
+                        .speed(0.1)
+                        .suffix("%"),
+                );
+            });
+
+            ui.separator();
+            ui.checkbox(&mut self.osc_enabled, "OSC input")
+                .on_hover_text(
+                    "Send a float from 0 to 1, or an int from 0 to 100 (%), to this address",
+                );
+            ui.add_enabled_ui(self.osc_enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Port");
+                    ui.add(DragValue::new(&mut self.osc_port).speed(1));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Address");
+                    ui.text_edit_singleline(&mut self.osc_address);
+                });
+            });
+
+            ui.separator();
+            ui.add_enabled(
+                cfg!(feature = "midi"),
+                egui::Checkbox::new(&mut self.midi_enabled, "MIDI input"),
+            )
+            .on_hover_text("A fader or encoder sending control changes")
+            .on_disabled_hover_text("This copy of the app was built without MIDI");
+            ui.add_enabled_ui(self.midi_enabled, |ui| {
+                let selected = if self.midi_port.is_empty() {
+                    "First input"
+                } else {
+                    &self.midi_port
+                };
+                egui::ComboBox::from_label("Input")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.midi_port, String::new(), "First input");
+                        // Only looked up while the list is open, as it asks the system every time
+                        for port in midi_ports() {
+                            ui.selectable_value(&mut self.midi_port, port.clone(), port);
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("Channel");
+                    ui.add(DragValue::new(&mut self.midi_channel).range(1..=16));
+                    ui.label("Controller");
+                    ui.add(DragValue::new(&mut self.midi_controller).range(0..=127));
+                });
+            });
+        });
+
+        // Save config on click, show error message if there are issues
+        if ui.button("Save").clicked() {
+            match global_state.config_file.write_live(self.clone()) {
+                Ok(_) => {
+                    global_state
+                        .toasts
+                        .success("Successfully changed live control settings!");
+                }
+                Err(e) => {
+                    global_state
+                        .toasts
+                        .error(format!("Failed to save live control config to file: {e}"));
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "🎚 Live control"
+    }
+
+    /// To be called before opening this UI element to update it's data with the config file.
+    /// Causes file IO so do **not** call on every frame
+    fn update(&mut self, global_state: &mut crate::app::GlobalState) {
+        match global_state.config_file.read() {
+            Ok(config) => *self = config.live,
+            Err(e) => {
+                global_state
+                    .toasts
+                    .error(format!("Failed to read config from file: {e}"));
+            }
+        }
+    }
+}
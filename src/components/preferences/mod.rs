@@ -4,19 +4,26 @@ use enum_dispatch::enum_dispatch;
 use crate::{
     app::GlobalState,
     components::{
-        preferences::{osc_preferences::OscPreferences, theme_preferences::ThemePreferences},
+        preferences::{
+            live_preferences::LivePreferences, osc_preferences::OscPreferences,
+            theme_preferences::ThemePreferences,
+        },
         toggleable_item::ToggleableItem,
     },
     only_one_toggleable_item::only_one_toggleable_item,
 };
 
+pub mod live_preferences;
 pub mod osc_preferences;
 pub mod theme_preferences;
 
 #[enum_dispatch]
 #[derive(Debug, Clone, PartialEq)]
+// Named after the preference structs, which are named after what they're settings for
+#[allow(clippy::enum_variant_names)]
 pub enum PreferenceItemEnum {
     OscPreferences,
+    LivePreferences,
     ThemePreferences,
 }
 
@@ -40,6 +47,9 @@ impl Preferences {
             shown: false,
             preference_items: vec![
                 ToggleableItem::from(PreferenceItemEnum::OscPreferences(OscPreferences::default())),
+                ToggleableItem::from(PreferenceItemEnum::LivePreferences(
+                    LivePreferences::default(),
+                )),
                 ToggleableItem::from(PreferenceItemEnum::ThemePreferences(ThemePreferences::new())),
             ],
         }
@@ -1,14 +1,17 @@
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, SocketAddr};
 
 use egui::DragValue;
 use serde::{Deserialize, Serialize};
 
-use crate::components::preferences::PreferenceItem;
+use crate::{components::preferences::PreferenceItem, move_calculator::DeskConnection};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OscPreferences {
     pub host: (Ipv4Addr, u16),
     pub desk: (Ipv4Addr, u16),
+    /// How fast commands are sent to the desk
+    pub commands_per_second: f64,
 }
 
 impl Default for OscPreferences {
@@ -16,6 +19,19 @@ impl Default for OscPreferences {
         Self {
             host: (Ipv4Addr::new(0, 0, 0, 0), 0),
             desk: (Ipv4Addr::new(192, 168, 0, 0), 8000),
+            // The desk kept up with this when commands were sent with a 10ms sleep between them
+            commands_per_second: 100.0,
+        }
+    }
+}
+
+impl OscPreferences {
+    /// Where and how fast to send commands to the desk
+    pub fn connection(&self) -> DeskConnection {
+        DeskConnection {
+            host: SocketAddr::from(self.host),
+            desk: SocketAddr::from(self.desk),
+            commands_per_second: self.commands_per_second,
         }
     }
 }
@@ -25,6 +41,14 @@ impl PreferenceItem for OscPreferences {
         ui.vertical(|ui| {
             select_ip_port(ui, "Host", &mut self.host);
             select_ip_port(ui, "Desk", &mut self.desk);
+            ui.horizontal(|ui| {
+                ui.label("Send rate");
+                ui.add(
+                    DragValue::new(&mut self.commands_per_second)
+                        .range(1.0..=1000.0)
+                        .suffix(" commands/s"),
+                );
+            });
         });
 
         // Save config on click, show error message if there are issues
@@ -54,10 +78,7 @@ impl PreferenceItem for OscPreferences {
         let config = global_state.config_file.read();
 
         match config {
-            Ok(config) => {
-                self.desk = config.osc.desk;
-                self.host = config.osc.host;
-            }
+            Ok(config) => *self = config.osc,
             Err(e) => {
                 global_state
                     .toasts
@@ -0,0 +1,239 @@
+use egui::{Pos2, Rect, Vec2};
+use trig::Trig;
+use vector3d::Vector3d;
+
+/// Anything closer to the camera than this (in metres) isn't drawn
+const NEAR: f64 = 0.05;
+
+/// A camera that orbits around a point, turning 3D points in to points on the screen so the preview doesn't need a GPU
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    /// The point the camera looks at and orbits around
+    pub target: Vector3d<f64>,
+    /// Around the z axis in degrees, 0 looks along +y
+    pub yaw: f64,
+    /// Up from the floor in degrees
+    pub pitch: f64,
+    /// How far the camera is from `target`, in metres
+    pub distance: f64,
+    /// Vertical field of view in degrees
+    pub fov: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            target: Vector3d::new(0.0, 0.0, 0.0),
+            yaw: -30.0,
+            pitch: 30.0,
+            distance: 15.0,
+            fov: 50.0,
+        }
+    }
+}
+
+fn normalise(vector: Vector3d<f64>) -> Vector3d<f64> {
+    vector / vector.norm2().sqrt()
+}
+
+impl Camera {
+    /// Where the camera is
+    fn eye(&self) -> Vector3d<f64> {
+        let offset = Vector3d::new(
+            self.pitch.cosd() * self.yaw.sind(),
+            -self.pitch.cosd() * self.yaw.cosd(),
+            self.pitch.sind(),
+        );
+        self.target + offset * self.distance
+    }
+
+    /// The camera's right, up and forwards directions
+    fn axes(&self) -> (Vector3d<f64>, Vector3d<f64>, Vector3d<f64>) {
+        let forwards = normalise(self.target - self.eye());
+        let right = normalise(forwards.cross(Vector3d::new(0.0, 0.0, 1.0)));
+        let up = right.cross(forwards);
+        (right, up, forwards)
+    }
+
+    /// A point relative to the camera, as how far right, up and in front of it it is
+    fn to_view(&self, point: Vector3d<f64>) -> Vector3d<f64> {
+        let (right, up, forwards) = self.axes();
+        let relative = point - self.eye();
+        Vector3d::new(
+            relative.dot(right),
+            relative.dot(up),
+            relative.dot(forwards),
+        )
+    }
+
+    /// How many pixels one metre is, one metre in front of the camera
+    fn focal_length(&self, rect: Rect) -> f64 {
+        rect.height() as f64 / 2.0 / (self.fov / 2.0).tand()
+    }
+
+    /// Puts a point that's already relative to the camera on the screen
+    fn view_to_screen(&self, view: Vector3d<f64>, rect: Rect) -> Pos2 {
+        let focal_length = self.focal_length(rect);
+        rect.center()
+            + Vec2::new(
+                (view.x / view.z * focal_length) as f32,
+                (-view.y / view.z * focal_length) as f32,
+            )
+    }
+
+    /// Where a point is drawn in `rect`, None if it's behind the camera
+    pub fn project(&self, point: Vector3d<f64>, rect: Rect) -> Option<Pos2> {
+        let view = self.to_view(point);
+        (view.z >= NEAR).then(|| self.view_to_screen(view, rect))
+    }
+
+    /// Where a line is drawn in `rect`, cut short if part of it is behind the camera. None if it's all behind the camera
+    pub fn project_line(
+        &self,
+        start: Vector3d<f64>,
+        end: Vector3d<f64>,
+        rect: Rect,
+    ) -> Option<[Pos2; 2]> {
+        let mut start = self.to_view(start);
+        let mut end = self.to_view(end);
+        if start.z < NEAR && end.z < NEAR {
+            return None;
+        }
+
+        // Move the end that's behind the camera to where the line crosses in to view
+        let crossing = |behind: Vector3d<f64>, in_front: Vector3d<f64>| {
+            let t = (NEAR - behind.z) / (in_front.z - behind.z);
+            behind + (in_front - behind) * t
+        };
+        if start.z < NEAR {
+            start = crossing(start, end);
+        } else if end.z < NEAR {
+            end = crossing(end, start);
+        }
+
+        Some([
+            self.view_to_screen(start, rect),
+            self.view_to_screen(end, rect),
+        ])
+    }
+
+    /// Turns the camera around the target by a mouse drag in pixels
+    pub fn orbit(&mut self, drag: Vec2) {
+        self.yaw -= drag.x as f64 * 0.3;
+        // Looking straight up or down would leave no way to tell which way is right
+        self.pitch = (self.pitch + drag.y as f64 * 0.3).clamp(-89.0, 89.0);
+    }
+
+    /// Moves the camera and target together by a mouse drag in pixels, so whatever's under the mouse stays under it
+    pub fn pan(&mut self, drag: Vec2, rect: Rect) {
+        let (right, up, _) = self.axes();
+        let metres_per_pixel = self.distance / self.focal_length(rect);
+        self.target = self.target - right * (drag.x as f64 * metres_per_pixel)
+            + up * (drag.y as f64 * metres_per_pixel);
+    }
+
+    /// Moves the camera closer for positive scrolls and further away for negative ones
+    pub fn zoom(&mut self, scroll: f32) {
+        self.distance = (self.distance * (-scroll as f64 * 0.002).exp()).clamp(0.5, 500.0);
+    }
+
+    /// Points the camera at the middle of `points`, far enough away to see all of them
+    pub fn fit(&mut self, points: &[Vector3d<f64>]) {
+        let Some(first) = points.first() else {
+            *self = Self::default();
+            return;
+        };
+
+        let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+            (
+                Vector3d::new(min.x.min(point.x), min.y.min(point.y), min.z.min(point.z)),
+                Vector3d::new(max.x.max(point.x), max.y.max(point.y), max.z.max(point.z)),
+            )
+        });
+        let radius = (max - min).norm2().sqrt() / 2.0;
+
+        self.target = (min + max) / 2.0;
+        // Leave a bit of room round the edges
+        self.distance = (radius / (self.fov / 2.0).sind() * 1.2).clamp(2.0, 500.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn screen() -> Rect {
+        Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0))
+    }
+
+    #[test]
+    fn test_project() {
+        let camera = Camera {
+            yaw: 0.0,
+            pitch: 0.0,
+            ..Default::default()
+        };
+
+        // The camera is at -y looking along +y, so +x is right and +z is up
+        let centre = camera
+            .project(Vector3d::new(0.0, 0.0, 0.0), screen())
+            .unwrap();
+        assert!((centre - screen().center()).length() < 0.001);
+
+        let right = camera
+            .project(Vector3d::new(1.0, 0.0, 0.0), screen())
+            .unwrap();
+        assert!(right.x > centre.x);
+        let up = camera
+            .project(Vector3d::new(0.0, 0.0, 1.0), screen())
+            .unwrap();
+        assert!(up.y < centre.y);
+
+        assert_eq!(
+            camera.project(Vector3d::new(0.0, -20.0, 0.0), screen()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_project_line_behind_camera() {
+        let camera = Camera {
+            yaw: 0.0,
+            pitch: 0.0,
+            ..Default::default()
+        };
+
+        let behind = Vector3d::new(1.0, -20.0, 0.0);
+        let in_front = Vector3d::new(1.0, 0.0, 0.0);
+        assert_eq!(camera.project_line(behind, behind * 2.0, screen()), None);
+
+        let [start, end] = camera.project_line(behind, in_front, screen()).unwrap();
+        assert_eq!(end, camera.project(in_front, screen()).unwrap());
+        // The cut off end is still on the same side of the screen
+        assert!(start.x > screen().center().x);
+    }
+
+    #[test]
+    fn test_fit() {
+        let mut camera = Camera::default();
+        let points = [Vector3d::new(-4.0, 0.0, 0.0), Vector3d::new(4.0, 2.0, 6.0)];
+        camera.fit(&points);
+
+        assert_eq!(camera.target, Vector3d::new(0.0, 1.0, 3.0));
+        for point in points {
+            let on_screen = camera.project(point, screen()).unwrap();
+            assert!(screen().contains(on_screen));
+        }
+    }
+
+    #[test]
+    fn test_orbit_and_zoom_limits() {
+        let mut camera = Camera::default();
+        camera.orbit(Vec2::new(0.0, 10_000.0));
+        assert_eq!(camera.pitch, 89.0);
+
+        camera.zoom(100_000.0);
+        assert_eq!(camera.distance, 0.5);
+    }
+}
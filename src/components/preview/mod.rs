@@ -0,0 +1,297 @@
+use egui::{Align2, Color32, FontId, Sense, Stroke};
+use vector3d::Vector3d;
+
+use crate::{
+    app::GlobalState,
+    components::output_section::MoveSettings,
+    light::Light,
+    move_calculator::{calculate_move, frame_points, Frame},
+    path::Path,
+};
+
+mod camera;
+
+use camera::Camera;
+
+/// How many straight lines each path is drawn with
+const PATH_SAMPLES: usize = 200;
+/// How far the floor grid goes out from the middle, in metres
+const GRID_SIZE: i32 = 10;
+
+const LIGHT_COLOUR: Color32 = Color32::from_rgb(255, 170, 0);
+const PATH_COLOUR: Color32 = Color32::from_rgb(80, 160, 255);
+const BEAM_COLOUR: Color32 = Color32::from_rgba_premultiplied(200, 200, 120, 160);
+
+/// A move worked out for the preview, kept so it isn't worked out again every frame
+struct PreviewMove {
+    settings: MoveSettings,
+    frames: Vec<Frame>,
+    /// Where on the path each light points in each frame
+    points: Vec<Vec<Vector3d<f64>>>,
+}
+
+/// An orbitable 3D view of the rig, the stored paths and the beams of the selected move
+pub struct Preview {
+    camera: Camera,
+    lights: Vec<Light>,
+    /// Each stored path as points along it, with it's name
+    paths: Vec<(String, Vec<Vector3d<f64>>)>,
+    /// `Database::changes` when the lights and paths were last read, None to read them again
+    read_at: Option<u64>,
+    /// The camera is fitted to the rig the first time it's read
+    fitted: bool,
+    /// The frame of the selected move being shown, from 0
+    frame: usize,
+    preview_move: Option<PreviewMove>,
+}
+
+impl Preview {
+    pub fn new() -> Self {
+        Self {
+            camera: Camera::default(),
+            lights: vec![],
+            paths: vec![],
+            read_at: None,
+            fitted: false,
+            frame: 0,
+            preview_move: None,
+        }
+    }
+
+    /// Reads everything from the newly opened project and fits the view to it
+    pub fn project_changed(&mut self) {
+        self.read_at = None;
+        self.fitted = false;
+    }
+
+    /// Reads the lights and paths again if the database has changed since they were last read
+    fn reload(&mut self, app_state: &mut GlobalState) {
+        let changes = app_state.database.changes();
+        if self.read_at == Some(changes) {
+            return;
+        }
+        // Set first so a broken database isn't read, and toasted, every frame
+        self.read_at = Some(changes);
+
+        match app_state.database.get_lights() {
+            Ok(lights) => self.lights = lights,
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+
+        match app_state.database.get_paths() {
+            Ok(paths) => {
+                self.paths = paths
+                    .iter()
+                    .map(|path| (path.name(), path.points(PATH_SAMPLES)))
+                    .collect()
+            }
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+            }
+        }
+
+        if !self.fitted {
+            self.fit();
+            self.fitted = true;
+        }
+    }
+
+    /// Points the camera so the whole rig and every path can be seen
+    fn fit(&mut self) {
+        let points: Vec<Vector3d<f64>> = self
+            .lights
+            .iter()
+            .map(|light| light.coordinates)
+            .chain(self.paths.iter().flat_map(|(_, points)| points.clone()))
+            .collect();
+        self.camera.fit(&points);
+    }
+
+    /// Works out the move again if it's been changed in the output section
+    fn update_move(&mut self, app_state: &mut GlobalState, settings: Option<MoveSettings>) {
+        let Some(settings) = settings else {
+            self.preview_move = None;
+            return;
+        };
+        if self
+            .preview_move
+            .as_ref()
+            .is_some_and(|preview_move| preview_move.settings == settings)
+        {
+            return;
+        }
+
+        match calculate_move(&settings.path, &settings.lights, &settings.options) {
+            Ok(frames) => {
+                self.frame = self.frame.min(frames.len().saturating_sub(1));
+                self.preview_move = Some(PreviewMove {
+                    points: frame_points(&settings.path, settings.lights.len(), &settings.options),
+                    settings,
+                    frames,
+                });
+            }
+            Err(e) => {
+                app_state.toasts.error(e.to_string());
+                self.preview_move = None;
+            }
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        ui: &mut egui::Ui,
+        app_state: &mut GlobalState,
+        move_settings: Option<MoveSettings>,
+    ) {
+        self.reload(app_state);
+        self.update_move(app_state, move_settings);
+
+        ui.horizontal(|ui| {
+            match &self.preview_move {
+                Some(preview_move) => {
+                    let last_frame = preview_move.frames.len().saturating_sub(1);
+                    ui.label("Frame");
+                    ui.add(
+                        egui::Slider::new(&mut self.frame, 0..=last_frame)
+                            .custom_formatter(|frame, _| format!("{}", frame as usize + 1))
+                            .custom_parser(|text| {
+                                text.parse::<f64>().ok().map(|frame| frame - 1.0)
+                            }),
+                    );
+                }
+                None => {
+                    ui.weak("Select some lights and a path to preview the move");
+                }
+            }
+
+            if ui
+                .button("Fit view")
+                .on_hover_text("Drag to orbit, right drag to pan and scroll to zoom")
+                .clicked()
+            {
+                self.fit();
+            }
+        });
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+
+        if response.dragged_by(egui::PointerButton::Primary) {
+            self.camera.orbit(response.drag_delta());
+        }
+        if response.dragged_by(egui::PointerButton::Secondary)
+            || response.dragged_by(egui::PointerButton::Middle)
+        {
+            self.camera.pan(response.drag_delta(), rect);
+        }
+        if response.hovered() {
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                self.camera.zoom(scroll);
+            }
+        }
+        if response.double_clicked() {
+            self.fit();
+        }
+
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let text_colour = ui.visuals().text_color();
+        let grid_colour = ui.visuals().weak_text_color().gamma_multiply(0.3);
+
+        let line = |start: Vector3d<f64>, end: Vector3d<f64>, stroke: Stroke| {
+            if let Some(points) = self.camera.project_line(start, end, rect) {
+                painter.line_segment(points, stroke);
+            }
+        };
+
+        // The floor, with the axes in red, green and blue
+        for i in -GRID_SIZE..=GRID_SIZE {
+            let i = i as f64;
+            let size = GRID_SIZE as f64;
+            let stroke = Stroke::new(1.0, grid_colour);
+            line(
+                Vector3d::new(i, -size, 0.0),
+                Vector3d::new(i, size, 0.0),
+                stroke,
+            );
+            line(
+                Vector3d::new(-size, i, 0.0),
+                Vector3d::new(size, i, 0.0),
+                stroke,
+            );
+        }
+        let origin = Vector3d::new(0.0, 0.0, 0.0);
+        line(
+            origin,
+            Vector3d::new(1.0, 0.0, 0.0),
+            Stroke::new(2.0, Color32::RED),
+        );
+        line(
+            origin,
+            Vector3d::new(0.0, 1.0, 0.0),
+            Stroke::new(2.0, Color32::GREEN),
+        );
+        line(
+            origin,
+            Vector3d::new(0.0, 0.0, 1.0),
+            Stroke::new(2.0, Color32::BLUE),
+        );
+
+        for (name, points) in &self.paths {
+            for pair in points.windows(2) {
+                line(pair[0], pair[1], Stroke::new(2.0, PATH_COLOUR));
+            }
+            if let Some(start) = points
+                .first()
+                .and_then(|start| self.camera.project(*start, rect))
+            {
+                painter.text(
+                    start,
+                    Align2::LEFT_BOTTOM,
+                    name,
+                    FontId::proportional(12.0),
+                    PATH_COLOUR,
+                );
+            }
+        }
+
+        // Each beam is drawn as far as the point it should be on, so a light that's pointing the wrong way misses it
+        if let Some(preview_move) = &self.preview_move {
+            if let (Some(frame), Some(targets)) = (
+                preview_move.frames.get(self.frame),
+                preview_move.points.get(self.frame),
+            ) {
+                for ((light, state), target) in preview_move
+                    .settings
+                    .lights
+                    .iter()
+                    .zip(&frame.light_states)
+                    .zip(targets)
+                {
+                    let length = (*target - light.coordinates).norm2().sqrt();
+                    let end = light.coordinates + light.beam_direction(state) * length;
+                    line(light.coordinates, end, Stroke::new(3.0, BEAM_COLOUR));
+
+                    if let Some(target) = self.camera.project(*target, rect) {
+                        painter.circle_stroke(target, 6.0, Stroke::new(2.0, text_colour));
+                    }
+                }
+            }
+        }
+
+        for light in &self.lights {
+            if let Some(position) = self.camera.project(light.coordinates, rect) {
+                painter.circle_filled(position, 5.0, LIGHT_COLOUR);
+                painter.text(
+                    position - egui::vec2(0.0, 8.0),
+                    Align2::CENTER_BOTTOM,
+                    &light.name,
+                    FontId::proportional(12.0),
+                    text_colour,
+                );
+            }
+        }
+    }
+}
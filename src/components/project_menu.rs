@@ -0,0 +1,122 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::app::GlobalState;
+
+/// Asks the user where a project file is or should go
+fn project_dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new().add_filter("Two Mover 3 Pos project", &["db"])
+}
+
+/// Opens a project that already exists, showing a toast if it fails. Returns true if it was opened
+fn open(app_state: &mut GlobalState, project: PathBuf) -> bool {
+    let opened = app_state.open_project(project.clone());
+    toast_opened(app_state, &project, opened)
+}
+
+/// Shows a toast for whether a project was opened. Returns true if it was
+fn toast_opened(app_state: &mut GlobalState, project: &Path, opened: anyhow::Result<()>) -> bool {
+    match opened {
+        Ok(()) => {
+            app_state
+                .toasts
+                .success(format!("Opened {}", app_state.project_name()));
+            true
+        }
+        Err(e) => {
+            app_state
+                .toasts
+                .error(format!("Failed to open {}: {e}", project.display()));
+            false
+        }
+    }
+}
+
+/// Starts an empty project in a new file
+fn new_project(app_state: &mut GlobalState) -> bool {
+    let Some(project) = project_dialog().set_file_name("Untitled.db").save_file() else {
+        return false;
+    };
+
+    if project == app_state.database.path() {
+        app_state.toasts.error("That project is already open");
+        return false;
+    }
+
+    // The dialog has already asked if it's ok to replace the file
+    if project.exists() {
+        if let Err(e) = fs::remove_file(&project) {
+            app_state
+                .toasts
+                .error(format!("Failed to replace {}: {e}", project.display()));
+            return false;
+        }
+    }
+
+    let opened = app_state.create_project(project.clone());
+    toast_opened(app_state, &project, opened)
+}
+
+/// Copies the open project to a new file and switches to it
+fn save_project_as(app_state: &mut GlobalState) -> bool {
+    let Some(project) = project_dialog()
+        .set_file_name(format!("{}.db", app_state.project_name()))
+        .save_file()
+    else {
+        return false;
+    };
+
+    if let Err(e) = app_state.database.save_copy(&project) {
+        app_state
+            .toasts
+            .error(format!("Failed to save {}: {e}", project.display()));
+        return false;
+    }
+
+    open(app_state, project)
+}
+
+/// Adds the New, Open, Open recent and Save as items to a menu. Returns true if a different project was opened
+pub fn project_menu(ui: &mut egui::Ui, app_state: &mut GlobalState) -> bool {
+    let mut switched = false;
+
+    if ui.button("New project…").clicked() {
+        ui.close();
+        switched = new_project(app_state);
+    }
+
+    if ui.button("Open project…").clicked() {
+        ui.close();
+        if let Some(project) = project_dialog().pick_file() {
+            switched = open(app_state, project);
+        }
+    }
+
+    let recent = app_state.config_file.projects().recent.clone();
+    ui.add_enabled_ui(!recent.is_empty(), |ui| {
+        ui.menu_button("Open recent", |ui| {
+            for project in recent {
+                if ui
+                    .add_enabled(
+                        project.exists(),
+                        egui::Button::new(project.display().to_string()),
+                    )
+                    .on_disabled_hover_text("This project has been moved or deleted")
+                    .clicked()
+                {
+                    ui.close();
+                    switched = open(app_state, project);
+                }
+            }
+        });
+    });
+
+    if ui.button("Save project as…").clicked() {
+        ui.close();
+        switched = save_project_as(app_state);
+    }
+
+    switched
+}
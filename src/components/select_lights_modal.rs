@@ -30,11 +30,9 @@ impl SelectLightsModal {
             lights.into_iter().map(ToggleableItem::from).collect();
 
         // Delete lights that have been removed from the Vec
-        for (i, light) in self.toggleable_lights.clone().into_iter().enumerate() {
-            if !new_toggleable_lights.contains(&light) {
-                self.toggleable_lights.remove(i);
-            }
-        }
+        // Edited lights don't match their old values any more, so they're removed here and added again below
+        self.toggleable_lights
+            .retain(|light| new_toggleable_lights.contains(light));
 
         // Add new lights, if the len is less or equal then we have all the lights already
         if new_toggleable_lights.len() <= self.toggleable_lights.len() {
@@ -33,11 +33,9 @@ impl SelectPathModal {
             paths.into_iter().map(ToggleableItem::from).collect();
 
         // Delete paths that have been removed from the Vec
-        for (i, path) in self.toggleable_paths.clone().into_iter().enumerate() {
-            if !new_toggleable_paths.contains(&path) {
-                self.toggleable_paths.remove(i);
-            }
-        }
+        // Edited paths don't match their old values any more, so they're removed here and added again below
+        self.toggleable_paths
+            .retain(|path| new_toggleable_paths.contains(path));
 
         // Add new lights, if the len is less or equal then we have all the lights already
         if new_toggleable_paths.len() <= self.toggleable_paths.len() {
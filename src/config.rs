@@ -1,63 +1,345 @@
-use std::{
-    fs::{self, OpenOptions},
-    io::{Read, Seek, SeekFrom, Write},
-    path::PathBuf,
-};
+use std::{fmt, fs, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::components::preferences::osc_preferences::OscPreferences;
+use crate::components::preferences::{
+    live_preferences::LivePreferences, osc_preferences::OscPreferences,
+};
+
+/// The layout of config file this version of the app writes.
+/// When changing the layout bump this and add a step to `migrate`
+const CONFIG_VERSION: i64 = 3;
 
 pub struct ConfigFile {
     path: PathBuf,
     config: Config,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// Anything missing from the file is filled in with it's default value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    /// Which layout the file was written with, see `CONFIG_VERSION`
+    pub version: i64,
     pub osc: OscPreferences,
+    pub live: LivePreferences,
+    pub projects: ProjectHistory,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            osc: OscPreferences::default(),
+            live: LivePreferences::default(),
+            projects: ProjectHistory::default(),
+        }
+    }
+}
+
+/// Brings a config written by an older version of the app up to the current layout.
+/// Returns true if anything was changed, so it can be written back
+fn migrate(table: &mut toml::Table) -> Result<bool> {
+    // Version 1 didn't have a version number
+    let mut version = match table.get("version") {
+        None => 1,
+        Some(toml::Value::Integer(version)) => *version,
+        Some(other) => bail!("version should be a whole number, not {other}"),
+    };
+
+    if version > CONFIG_VERSION {
+        log::warn!(
+            "Config file is version {version}, which is newer than this app knows about ({CONFIG_VERSION})"
+        );
+        return Ok(false);
+    }
+    if version == CONFIG_VERSION {
+        return Ok(false);
+    }
+
+    // Version 2 added the project history, which serde fills in with it's defaults
+    if version == 1 {
+        version = 2;
+    }
+    // Version 3 added the live control preferences, which serde fills in with their defaults too
+    if version == 2 {
+        version = 3;
+    }
+
+    table.insert("version".into(), toml::Value::Integer(version));
+    Ok(true)
+}
+
+/// Reads a config from the contents of a file, migrating it to the current layout.
+/// Returns true as well if it was migrated
+fn parse(contents: &str) -> Result<(Config, bool)> {
+    let mut table: toml::Table = toml::from_str(contents)?;
+    let migrated = migrate(&mut table)?;
+
+    Ok((toml::Value::Table(table).try_into()?, migrated))
+}
+
+/// The folder the config file and default database are kept in, it's created if it doesn't exist yet
+fn data_directory() -> Result<PathBuf> {
+    let Some(mut data_path) = dirs::data_dir() else {
+        bail!("Could not find OS data directory");
+    };
+    data_path.push("two_mover_3_pos");
+
+    fs::create_dir_all(&data_path)?;
+
+    Ok(data_path)
+}
+
+/// Where the config file is kept
+pub fn config_path() -> Result<PathBuf> {
+    Ok(data_directory()?.join("config.toml"))
+}
+
+/// The database used when no project has been opened
+pub fn default_database_path() -> Result<PathBuf> {
+    Ok(data_directory()?.join("database.db"))
+}
+
+/// A config file that couldn't be read. It's been copied to `backup` so it can be fixed by hand
+#[derive(Debug)]
+pub struct CorruptConfig {
+    pub backup: PathBuf,
+    pub reason: String,
+}
+
+impl fmt::Display for CorruptConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The config file couldn't be read, it's been backed up to {}: {}",
+            self.backup.display(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for CorruptConfig {}
+
+/// How many projects are kept in the recent projects list
+const RECENT_PROJECTS: usize = 10;
+
+/// The project files that have been opened
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectHistory {
+    /// Opened again when the app starts
+    pub last_opened: Option<PathBuf>,
+    /// The most recently opened project first
+    pub recent: Vec<PathBuf>,
+}
+
+impl ProjectHistory {
+    /// Remembers that a project was opened, moving it to the top of the recent list
+    pub fn opened(&mut self, project: PathBuf) {
+        self.recent.retain(|recent| *recent != project);
+        self.recent.insert(0, project.clone());
+        self.recent.truncate(RECENT_PROJECTS);
+        self.last_opened = Some(project);
+    }
 }
 
 impl ConfigFile {
-    /// Creates a new config file if one does not exist at the given location. If one exists then it's contents is read and returned as a `ConfigFile` struct
+    /// Reads the config file at `path`, creating it with default values if it doesn't exist yet.
+    /// Older layouts are migrated and anything missing is filled in with defaults.
+    /// If the file can't be read it's backed up and a `CorruptConfig` error is returned, carry on with `ConfigFile::reset`
     /// path: where to put the file. It should be a file ending in `.toml`.
     pub fn new(path: PathBuf) -> Result<Self> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .read(true)
-            .write(true)
-            .open(&path)?;
-
-        // If there is no config file yet, write default values to it
-        if file.metadata()?.len() == 0 {
-            file.write_all(toml::to_string_pretty(&Config::default())?.as_bytes())?;
-            file.seek(SeekFrom::Start(0))?;
+        if !path.exists() {
+            return Self::reset(path);
         }
 
-        // Get file contents
-        let mut file_contents = "".to_string();
-        file.read_to_string(&mut file_contents)?;
+        let contents = fs::read_to_string(&path)?;
+        let (config, migrated) = match parse(&contents) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let backup = path.with_extension("toml.bak");
+                fs::copy(&path, &backup)?;
+                return Err(CorruptConfig {
+                    backup,
+                    reason: e.to_string(),
+                }
+                .into());
+            }
+        };
 
-        // Create the config object with a new serialised copy of the config file
-        Ok(Self {
+        let config_file = Self { path, config };
+        if migrated {
+            config_file.write()?;
+        }
+        Ok(config_file)
+    }
+
+    /// Replaces the config file at `path` with default values
+    pub fn reset(path: PathBuf) -> Result<Self> {
+        let config_file = Self {
             path,
-            config: toml::from_str(&file_contents)?,
-        })
+            config: Config::default(),
+        };
+        config_file.write()?;
+        Ok(config_file)
+    }
+
+    /// Writes the in memory state of the config to the file
+    fn write(&self) -> Result<()> {
+        fs::write(&self.path, toml::to_string_pretty(&self.config)?)?;
+        Ok(())
     }
 
     /// Writes the specified config to the file & updates in memory state of config
     pub fn write_osc(&mut self, new_osc_config: OscPreferences) -> Result<()> {
         self.config.osc = new_osc_config;
+        self.write()
+    }
 
-        fs::write(&self.path, toml::to_string_pretty(&self.config)?)?;
+    /// Writes the live control settings to the file & updates in memory state of config
+    pub fn write_live(&mut self, new_live_config: LivePreferences) -> Result<()> {
+        self.config.live = new_live_config;
+        self.write()
+    }
 
-        Ok(())
+    /// The project history, as it was last read or written
+    pub fn projects(&self) -> &ProjectHistory {
+        &self.config.projects
+    }
+
+    /// Writes the project history to the file & updates in memory state of config
+    pub fn write_projects(&mut self, new_projects: ProjectHistory) -> Result<()> {
+        self.config.projects = new_projects;
+        self.write()
     }
 
     pub fn read(&mut self) -> Result<Config> {
-        self.config = toml::from_str(&fs::read_to_string(&self.path)?)?;
+        (self.config, _) = parse(&fs::read_to_string(&self.path)?)?;
         Ok(self.config.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_recent_projects() {
+        let mut projects = ProjectHistory::default();
+        projects.opened(PathBuf::from("a.db"));
+        projects.opened(PathBuf::from("b.db"));
+        projects.opened(PathBuf::from("a.db"));
+
+        assert_eq!(projects.last_opened, Some(PathBuf::from("a.db")));
+        assert_eq!(
+            projects.recent,
+            vec![PathBuf::from("a.db"), PathBuf::from("b.db")]
+        );
+
+        for i in 0..20 {
+            projects.opened(PathBuf::from(format!("{i}.db")));
+        }
+        assert_eq!(projects.recent.len(), RECENT_PROJECTS);
+    }
+
+    #[test]
+    fn test_new_creates_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let mut config_file = ConfigFile::new(path.clone()).unwrap();
+        assert!(path.exists());
+        assert_eq!(config_file.read().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_new_keeps_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let osc = OscPreferences {
+            host: ("10.0.0.2".parse().unwrap(), 9000),
+            desk: ("10.0.0.1".parse().unwrap(), 8001),
+            commands_per_second: 40.0,
+        };
+        ConfigFile::new(path.clone())
+            .unwrap()
+            .write_osc(osc.clone())
+            .unwrap();
+
+        let mut config_file = ConfigFile::new(path).unwrap();
+        assert_eq!(config_file.read().unwrap().osc, osc);
+    }
+
+    #[test]
+    fn test_missing_keys_filled_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "version = 2\n[osc]\nhost = [\"10.0.0.2\", 9000]\n").unwrap();
+
+        let mut config = ConfigFile::new(path).unwrap().read().unwrap();
+        assert_eq!(config.osc.host, ("10.0.0.2".parse().unwrap(), 9000));
+        assert_eq!(config.osc.desk, OscPreferences::default().desk);
+        assert_eq!(
+            config.osc.commands_per_second,
+            OscPreferences::default().commands_per_second
+        );
+        assert_eq!(config.projects, ProjectHistory::default());
+        assert_eq!(config.live, LivePreferences::default());
+
+        config.osc.host = OscPreferences::default().host;
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_migrate_version_1() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[osc]\nhost = [\"0.0.0.0\", 0]\ndesk = [\"10.0.0.1\", 8001]\n",
+        )
+        .unwrap();
+
+        let config = ConfigFile::new(path.clone()).unwrap().read().unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.osc.desk, ("10.0.0.1".parse().unwrap(), 8001));
+
+        // The migrated config is written back
+        let written: toml::Table = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            written.get("version"),
+            Some(&toml::Value::Integer(CONFIG_VERSION))
+        );
+    }
+
+    #[test]
+    fn test_corrupt_config_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let contents = "[osc\nhost = \"nonsense\"\n";
+        fs::write(&path, contents).unwrap();
+
+        let err = ConfigFile::new(path.clone()).err().unwrap();
+        let corrupt = err.downcast_ref::<CorruptConfig>().unwrap();
+        assert_eq!(fs::read_to_string(&corrupt.backup).unwrap(), contents);
+
+        // The original is left alone until it's reset
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+        let mut config_file = ConfigFile::reset(path).unwrap();
+        assert_eq!(config_file.read().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_wrong_types_are_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "version = 2\n[osc]\nhost = 5\n").unwrap();
+
+        let err = ConfigFile::new(path).err().unwrap();
+        assert!(err.is::<CorruptConfig>());
+    }
+}
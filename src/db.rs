@@ -1,13 +1,20 @@
-use std::path::PathBuf;
+//! Reads and writes the lights and paths in a project file, which is an SQLite database
+
+use std::{
+    fmt, fs, io,
+    path::{Path as FilePath, PathBuf},
+};
 
 use crate::{
-    light::Light,
-    path::{bezier::Bezier, cubic_bezier::CubicBezier, line::Line, PathEnum},
+    light::{Light, Mounting},
+    path::{
+        arc::Arc, bezier::Bezier, circle::Circle, composite::CompositePath,
+        cubic_bezier::CubicBezier, ellipse::Ellipse, line::Line, spline::Spline, Path, PathEnum,
+    },
 };
 
 use std::sync::LazyLock;
 
-use anyhow::{bail, Result};
 use include_dir::{include_dir, Dir};
 use isx::prelude::IsDefault;
 use rusqlite::{params, Connection};
@@ -21,32 +28,167 @@ static MIGRATIONS: LazyLock<Migrations<'static>> = LazyLock::new(|| {
     Migrations::from_directory(&MIGRATIONS_DIR).expect("Failed to load Database migrations")
 });
 
+/// Why something couldn't be read from or written to the database
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// SQLite failed, e.g. because the file isn't a database
+    Sqlite(rusqlite::Error),
+    /// The file's schema couldn't be brought up to date
+    Migration(rusqlite_migration::Error),
+    /// The light or path can't be saved, the message says why
+    Invalid(String),
+    /// There's no light or path of this kind with this id
+    NotFound { kind: String, id: i64 },
+    /// A path's shape couldn't be turned in to or read from JSON
+    Geometry(String),
+    /// A file couldn't be written or removed
+    Io(io::Error),
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::Sqlite(e) => write!(f, "{e}"),
+            DatabaseError::Migration(e) => write!(f, "Failed to update the database: {e}"),
+            DatabaseError::Invalid(message) | DatabaseError::Geometry(message) => {
+                write!(f, "{message}")
+            }
+            DatabaseError::NotFound { kind, id } => write!(f, "There is no {kind} with id {id}"),
+            DatabaseError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DatabaseError::Sqlite(e) => Some(e),
+            DatabaseError::Migration(e) => Some(e),
+            DatabaseError::Io(e) => Some(e),
+            DatabaseError::Invalid(_)
+            | DatabaseError::NotFound { .. }
+            | DatabaseError::Geometry(_) => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for DatabaseError {
+    fn from(e: rusqlite::Error) -> Self {
+        DatabaseError::Sqlite(e)
+    }
+}
+
+impl From<rusqlite_migration::Error> for DatabaseError {
+    fn from(e: rusqlite_migration::Error) -> Self {
+        DatabaseError::Migration(e)
+    }
+}
+
+impl From<serde_json::Error> for DatabaseError {
+    fn from(e: serde_json::Error) -> Self {
+        DatabaseError::Geometry(e.to_string())
+    }
+}
+
+impl From<io::Error> for DatabaseError {
+    fn from(e: io::Error) -> Self {
+        DatabaseError::Io(e)
+    }
+}
+
+/// The result of a database operation
+pub type Result<T, E = DatabaseError> = std::result::Result<T, E>;
+
+/// The `add_*` functions all return the id of the row they added
 pub struct Database {
     connection: Connection,
+    /// The file this database is stored in
+    path: PathBuf,
 }
 
 impl Database {
     /// Initialize the database, uses the migrations in ../migrations/
     pub fn new(file_name: PathBuf) -> Self {
-        let mut conn = Connection::open(file_name).expect("Failed to open Database file");
+        Self::open(file_name).expect("Failed to open Database file")
+    }
+
+    /// Opens a database file, creating it if it doesn't exist, and brings it's schema up to date.
+    /// Fails if the file isn't a database
+    pub fn open(file_name: PathBuf) -> Result<Self> {
+        let mut conn = Connection::open(&file_name)?;
 
         // Update the database schema
-        MIGRATIONS
-            .to_latest(&mut conn)
-            .expect("Failed to apply db migrations");
+        MIGRATIONS.to_latest(&mut conn)?;
+
+        // SQLite leaves foreign keys off unless it's asked, which would make the `ON DELETE CASCADE`s in the schema do nothing.
+        // Turned on after migrating so files with rows left behind by older versions still open
+        conn.pragma_update(None, "foreign_keys", true)?;
 
         // Create the object
-        Self { connection: conn }
+        Ok(Self {
+            connection: conn,
+            path: file_name,
+        })
     }
 
-    /// Add a light to the database
-    pub fn add_light(&self, light_to_add: &Light) -> Result<()> {
-        if light_to_add.is_default() {
-            bail!("Light has default values");
+    /// The file this database is stored in
+    pub fn path(&self) -> &FilePath {
+        &self.path
+    }
+
+    /// Goes up every time something is added, updated or deleted, so anything read from the database can tell when to read it again
+    pub fn changes(&self) -> u64 {
+        self.connection.total_changes()
+    }
+
+    /// Writes a copy of the whole database to another file, replacing it if it already exists
+    pub fn save_copy(&self, file_name: &FilePath) -> Result<()> {
+        if file_name == self.path {
+            return Err(DatabaseError::Invalid(
+                "Can't copy a database over itself".to_string(),
+            ));
+        }
+
+        // VACUUM INTO won't write over a file that already exists
+        if file_name.exists() {
+            fs::remove_file(file_name)?;
         }
 
         self.connection.execute(
-            "INSERT INTO Lights (coordinate_x, coordinate_y, coordinate_z, minimum_beam, maximum_beam, name, address) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "VACUUM INTO ?1",
+            params![file_name.to_string_lossy().to_string()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Runs `work` in a transaction, so either all of it is saved or none of it is.
+    /// Used inside another `transaction` it becomes part of that one, so things can be saved together
+    pub fn transaction<T, E: From<DatabaseError>>(
+        &self,
+        work: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        if !self.connection.is_autocommit() {
+            return work();
+        }
+
+        // Rolled back when it's dropped, if work fails
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(DatabaseError::from)?;
+        let result = work()?;
+        transaction.commit().map_err(DatabaseError::from)?;
+
+        Ok(result)
+    }
+
+    /// Add a light to the database
+    pub fn add_light(&self, light_to_add: &Light) -> Result<i64> {
+        validate_light(light_to_add)?;
+
+        self.connection.execute(
+            "INSERT INTO Lights (coordinate_x, coordinate_y, coordinate_z, minimum_beam, maximum_beam, name, address, mounting, mounting_rotation_x, mounting_rotation_y, mounting_rotation_z, pan_offset, tilt_offset, pan_inverted, tilt_inverted, pan_range, tilt_range) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
             params![
                 light_to_add.coordinates.x,
                 light_to_add.coordinates.y,
@@ -55,169 +197,697 @@ impl Database {
                 light_to_add.maximum_beam,
                 light_to_add.name,
                 light_to_add.address,
+                light_to_add.mounting.kind(),
+                light_to_add.mounting.rotation().x,
+                light_to_add.mounting.rotation().y,
+                light_to_add.mounting.rotation().z,
+                light_to_add.pan_offset,
+                light_to_add.tilt_offset,
+                light_to_add.pan_inverted,
+                light_to_add.tilt_inverted,
+                light_to_add.pan_range,
+                light_to_add.tilt_range,
                 ])?;
 
-        Ok(())
+        Ok(self.connection.last_insert_rowid())
     }
 
+    /// Gets every light, in the order they were added
     pub fn get_lights(&self) -> Result<Vec<Light>> {
         let mut statement = self.connection.prepare("SELECT * FROM Lights")?;
 
         let light_iterator = statement.query_map([], |row| {
             Ok(Light {
+                id: row.get(0)?,
                 coordinates: Vector3d::new(row.get(1)?, row.get(2)?, row.get(3)?),
                 minimum_beam: row.get(4)?,
                 maximum_beam: row.get(5)?,
                 name: row.get(6)?,
                 address: row.get(7)?,
+                mounting: Mounting::from_kind(
+                    &row.get::<_, String>(8)?,
+                    Vector3d::new(row.get(9)?, row.get(10)?, row.get(11)?),
+                ),
+                pan_offset: row.get(12)?,
+                tilt_offset: row.get(13)?,
+                pan_inverted: row.get(14)?,
+                tilt_inverted: row.get(15)?,
+                pan_range: row.get(16)?,
+                tilt_range: row.get(17)?,
             })
         })?;
 
         Ok(light_iterator.collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Add a named line to the database
-    pub fn add_line(&self, line_to_add: &Line) -> Result<()> {
-        if line_to_add.is_default() {
-            bail!("Line has default values");
-        }
+    /// Update a light that's already in the database, the row is found by it's id
+    pub fn update_light(&self, light: &Light) -> Result<()> {
+        validate_light(light)?;
 
-        if line_to_add.name.is_default() {
-            bail!("Line has no name");
-        }
-
-        self.connection.execute(
-            "INSERT INTO Lines (start_x, start_y, start_z, end_x, end_y, end_z, name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        let updated = self.connection.execute(
+            "UPDATE Lights SET coordinate_x = ?1, coordinate_y = ?2, coordinate_z = ?3, minimum_beam = ?4, maximum_beam = ?5, name = ?6, address = ?7, mounting = ?8, mounting_rotation_x = ?9, mounting_rotation_y = ?10, mounting_rotation_z = ?11, pan_offset = ?12, tilt_offset = ?13, pan_inverted = ?14, tilt_inverted = ?15, pan_range = ?16, tilt_range = ?17 WHERE id = ?18",
             params![
-                line_to_add.start.x,
-                line_to_add.start.y,
-                line_to_add.start.z,
-                line_to_add.end.x,
-                line_to_add.end.y,
-                line_to_add.end.z,
-                line_to_add.name,
+                light.coordinates.x,
+                light.coordinates.y,
+                light.coordinates.z,
+                light.minimum_beam,
+                light.maximum_beam,
+                light.name,
+                light.address,
+                light.mounting.kind(),
+                light.mounting.rotation().x,
+                light.mounting.rotation().y,
+                light.mounting.rotation().z,
+                light.pan_offset,
+                light.tilt_offset,
+                light.pan_inverted,
+                light.tilt_inverted,
+                light.pan_range,
+                light.tilt_range,
+                light.id,
             ],
         )?;
 
-        Ok(())
+        check_updated(updated, "Light", light.id)
+    }
+
+    /// Add any kind of path to the database. A composite path's segments are stored as references to paths that are already in the database
+    pub fn add_path(&self, path_to_add: &PathEnum) -> Result<i64> {
+        validate_path(path_to_add)?;
+
+        // Write the path and all of it's segments at once, so a half written path never ends up in the database
+        self.transaction(|| {
+            self.connection.execute(
+                "INSERT INTO Paths (name, kind, geometry) VALUES (?1, ?2, ?3)",
+                params![
+                    path_to_add.name(),
+                    path_to_add.kind(),
+                    path_geometry(path_to_add)?
+                ],
+            )?;
+            let path_id = self.connection.last_insert_rowid();
+            write_segments(&self.connection, path_id, path_to_add)?;
+
+            Ok(path_id)
+        })
     }
 
-    pub fn get_lines(&self) -> Result<Vec<Line>> {
-        let mut statement = self.connection.prepare("SELECT * FROM Lines")?;
+    /// Update any kind of path that's already in the database, the row is found by it's id. A composite path's segments are replaced
+    pub fn update_path(&self, path: &PathEnum) -> Result<()> {
+        validate_path(path)?;
+
+        self.transaction(|| {
+            let updated = self.connection.execute(
+                "UPDATE Paths SET name = ?1, geometry = ?2, modified = CURRENT_TIMESTAMP WHERE id = ?3 AND kind = ?4",
+                params![path.name(), path_geometry(path)?, path.id(), path.kind()],
+            )?;
+            check_updated(updated, path.kind(), path.id())?;
+
+            self.connection.execute(
+                "DELETE FROM PathSegments WHERE path_id = ?1",
+                params![path.id()],
+            )?;
+            write_segments(&self.connection, path.id(), path)
+        })
+    }
 
-        let line_iterator = statement.query_map([], |row| {
-            Ok(Line {
-                start: Vector3d::new(row.get(1)?, row.get(2)?, row.get(3)?),
-                end: Vector3d::new(row.get(4)?, row.get(5)?, row.get(6)?),
-                name: row.get(7)?,
-            })
-        })?;
+    /// Delete a light, found by it's id
+    pub fn delete_light(&self, id: i64) -> Result<()> {
+        let deleted = self
+            .connection
+            .execute("DELETE FROM Lights WHERE id = ?1", params![id])?;
+
+        check_updated(deleted, "Light", id)
+    }
 
-        Ok(line_iterator.collect::<Result<Vec<_>, _>>()?)
+    /// Delete any kind of path, found by it's id.
+    /// It's also taken out of any composite paths that use it
+    pub fn delete_path(&self, path: &PathEnum) -> Result<()> {
+        self.transaction(|| {
+            // The composite paths it's a segment of carry on without it, rather than stopping it being deleted.
+            // It's own segment rows go with it, as they cascade
+            self.connection.execute(
+                "DELETE FROM PathSegments WHERE segment_id = ?1",
+                params![path.id()],
+            )?;
+
+            let deleted = self.connection.execute(
+                "DELETE FROM Paths WHERE id = ?1 AND kind = ?2",
+                params![path.id(), path.kind()],
+            )?;
+            check_updated(deleted, path.kind(), path.id())
+        })
     }
 
-    /// Add a named bezier to the database
-    pub fn add_bezier(&self, bezier_to_add: &Bezier) -> Result<()> {
-        if bezier_to_add.is_default() {
-            bail!("Bezier curve has default values");
+    /// Gets every path, with composite paths after the paths they can be made of
+    pub fn get_paths(&self) -> Result<Vec<PathEnum>> {
+        let mut out_vec = self.get_segment_paths()?;
+        let mut composite_paths = self.read_paths(true)?;
+
+        // Composite paths refer to other paths, so fill their segments in now they've all been read
+        let mut segment_statement = self
+            .connection
+            .prepare("SELECT segment_id FROM PathSegments WHERE path_id = ?1 ORDER BY position")?;
+        for path in &mut composite_paths {
+            let PathEnum::CompositePath(composite_path) = path else {
+                continue;
+            };
+
+            let segment_ids = segment_statement
+                .query_map([composite_path.id], |row| row.get::<_, i64>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for id in segment_ids {
+                match out_vec.iter().find(|segment| segment.id() == id) {
+                    Some(segment) => composite_path.segments.push(segment.clone()),
+                    None => log::warn!(
+                        "Composite path {} refers to missing path {id}",
+                        composite_path.name
+                    ),
+                }
+            }
         }
 
-        self.connection.execute(
-            "INSERT INTO BezierCurves (start_x, start_y, start_z, midpoint_x, midpoint_y, midpoint_z, end_x, end_y, end_z, name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                bezier_to_add.start.x,
-                bezier_to_add.start.y,
-                bezier_to_add.start.z,
-                bezier_to_add.midpoint.x,
-                bezier_to_add.midpoint.y,
-                bezier_to_add.midpoint.z,
-                bezier_to_add.end.x,
-                bezier_to_add.end.y,
-                bezier_to_add.end.z,
-                bezier_to_add.name,
-            ],
+        out_vec.append(&mut composite_paths);
+
+        Ok(out_vec)
+    }
+
+    /// Gets every path that can be used as a segment of a composite path, which is all of them apart from composite paths
+    pub fn get_segment_paths(&self) -> Result<Vec<PathEnum>> {
+        self.read_paths(false)
+    }
+
+    /// Reads either the composite paths, without their segments, or every other path
+    fn read_paths(&self, composite: bool) -> Result<Vec<PathEnum>> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, name, kind, geometry FROM Paths WHERE (kind = 'CompositePath') = ?1 ORDER BY id",
         )?;
 
-        Ok(())
+        let rows = statement
+            .query_map([composite], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(id, name, kind, geometry)| path_from_row(id, name, kind, &geometry))
+            .collect()
     }
+}
 
-    pub fn get_beziers(&self) -> Result<Vec<Bezier>> {
-        let mut statement = self.connection.prepare("SELECT * FROM BezierCurves")?;
+/// The JSON stored in the geometry column of the Paths table. It's everything apart from the other columns and a composite path's segments
+fn path_geometry(path: &PathEnum) -> Result<String> {
+    let serde_json::Value::Object(mut fields) = serde_json::to_value(path)? else {
+        return Err(DatabaseError::Geometry(format!(
+            "{} {} isn't stored as a JSON object",
+            path.kind(),
+            path.id()
+        )));
+    };
+
+    for column in ["id", "name", "kind", "segments"] {
+        fields.remove(column);
+    }
 
-        let line_iterator = statement.query_map([], |row| {
-            Ok(Bezier {
-                start: Vector3d::new(row.get(1)?, row.get(2)?, row.get(3)?),
-                midpoint: Vector3d::new(row.get(4)?, row.get(5)?, row.get(6)?),
-                end: Vector3d::new(row.get(7)?, row.get(8)?, row.get(9)?),
-                name: row.get(10)?,
-            })
-        })?;
+    Ok(serde_json::Value::Object(fields).to_string())
+}
+
+/// Makes a path from a row of the Paths table. Composite paths are made without their segments
+fn path_from_row(id: i64, name: String, kind: String, geometry: &str) -> Result<PathEnum> {
+    let serde_json::Value::Object(mut fields) = serde_json::from_str(geometry)? else {
+        return Err(DatabaseError::Geometry(format!(
+            "The geometry of {kind} {id} isn't a JSON object"
+        )));
+    };
+
+    fields.insert("id".to_string(), id.into());
+    fields.insert("name".to_string(), name.into());
+    fields.insert("kind".to_string(), kind.into());
+
+    Ok(serde_json::from_value(serde_json::Value::Object(fields))?)
+}
+
+/// Writes the segments of a composite path that's stored in row `path_id`. Other kinds of path don't have any
+fn write_segments(connection: &Connection, path_id: i64, path: &PathEnum) -> Result<()> {
+    let PathEnum::CompositePath(composite_path) = path else {
+        return Ok(());
+    };
+
+    for (position, segment) in composite_path.segments.iter().enumerate() {
+        connection.execute(
+            "INSERT INTO PathSegments (path_id, position, segment_id) VALUES (?1, ?2, ?3)",
+            params![path_id, position, segment.id()],
+        )?;
+    }
+
+    Ok(())
+}
 
-        Ok(line_iterator.collect::<Result<Vec<_>, _>>()?)
+/// Turns an update or delete that didn't touch any rows in to an error
+fn check_updated(rows: usize, kind: &str, id: i64) -> Result<()> {
+    if rows == 0 {
+        return Err(DatabaseError::NotFound {
+            kind: kind.to_string(),
+            id,
+        });
     }
 
-    /// Add a named cubic bezier to the database
-    pub fn add_cubic_bezier(&self, cubic_bezier_to_add: &CubicBezier) -> Result<()> {
-        if cubic_bezier_to_add.is_default() {
-            bail!("Cubic bezier curve has default values");
+    Ok(())
+}
+
+/// Checks a path can be saved, these are the same checks `Database::add_path` makes
+pub fn validate_path(path: &PathEnum) -> Result<()> {
+    match path {
+        PathEnum::Line(line) => validate_line(line),
+        PathEnum::Bezier(bezier) => validate_bezier(bezier),
+        PathEnum::CubicBezier(cubic_bezier) => validate_cubic_bezier(cubic_bezier),
+        PathEnum::Spline(spline) => validate_spline(spline),
+        PathEnum::Circle(circle) => validate_circle(circle),
+        PathEnum::Arc(arc) => validate_arc(arc),
+        PathEnum::Ellipse(ellipse) => validate_ellipse(ellipse),
+        PathEnum::CompositePath(composite_path) => validate_composite_path(composite_path),
+    }
+}
+
+/// Checks a light can be saved, these are the same checks `Database::add_light` makes
+pub fn validate_light(light: &Light) -> Result<()> {
+    if light.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Light has default values".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_line(line: &Line) -> Result<()> {
+    if line.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Line has default values".to_string(),
+        ));
+    }
+
+    if line.name.is_default() {
+        return Err(DatabaseError::Invalid("Line has no name".to_string()));
+    }
+
+    Ok(())
+}
+
+fn validate_bezier(bezier: &Bezier) -> Result<()> {
+    if bezier.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Bezier curve has default values".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_cubic_bezier(cubic_bezier: &CubicBezier) -> Result<()> {
+    if cubic_bezier.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Cubic bezier curve has default values".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_spline(spline: &Spline) -> Result<()> {
+    if spline.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Spline has default values".to_string(),
+        ));
+    }
+
+    if spline.name.is_default() {
+        return Err(DatabaseError::Invalid("Spline has no name".to_string()));
+    }
+
+    if spline.waypoints.len() < 2 {
+        return Err(DatabaseError::Invalid(
+            "Spline needs at least 2 waypoints".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_circle(circle: &Circle) -> Result<()> {
+    if circle.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Circle has default values".to_string(),
+        ));
+    }
+
+    if circle.name.is_default() {
+        return Err(DatabaseError::Invalid("Circle has no name".to_string()));
+    }
+
+    if circle.radius <= 0.0 {
+        return Err(DatabaseError::Invalid(
+            "Circle radius must be more than 0".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_arc(arc: &Arc) -> Result<()> {
+    if arc.is_default() {
+        return Err(DatabaseError::Invalid("Arc has default values".to_string()));
+    }
+
+    if arc.name.is_default() {
+        return Err(DatabaseError::Invalid("Arc has no name".to_string()));
+    }
+
+    if arc.radius <= 0.0 {
+        return Err(DatabaseError::Invalid(
+            "Arc radius must be more than 0".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_ellipse(ellipse: &Ellipse) -> Result<()> {
+    if ellipse.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Ellipse has default values".to_string(),
+        ));
+    }
+
+    if ellipse.name.is_default() {
+        return Err(DatabaseError::Invalid("Ellipse has no name".to_string()));
+    }
+
+    if ellipse.radius_1 <= 0.0 || ellipse.radius_2 <= 0.0 {
+        return Err(DatabaseError::Invalid(
+            "Ellipse radii must be more than 0".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_composite_path(composite_path: &CompositePath) -> Result<()> {
+    if composite_path.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Composite path has default values".to_string(),
+        ));
+    }
+
+    if composite_path.name.is_default() {
+        return Err(DatabaseError::Invalid(
+            "Composite path has no name".to_string(),
+        ));
+    }
+
+    if composite_path.segments.is_empty() {
+        return Err(DatabaseError::Invalid(
+            "Composite path has no segments".to_string(),
+        ));
+    }
+
+    for segment in &composite_path.segments {
+        if let PathEnum::CompositePath(_) = segment {
+            return Err(DatabaseError::Invalid(
+                "Composite paths can't contain other composite paths".to_string(),
+            ));
         }
+        if segment.id() == 0 {
+            return Err(DatabaseError::Invalid(format!(
+                "Segment {} has not been saved yet",
+                segment.name()
+            )));
+        }
+    }
 
-        self.connection.execute(
-            "INSERT INTO CubicBezierCurves (start_x, start_y, start_z, end_x, end_y, end_z, handle_1_x, handle_1_y, handle_1_z, handle_2_x, handle_2_y, handle_2_z, name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-            params![
-                cubic_bezier_to_add.start.x,
-                cubic_bezier_to_add.start.y,
-                cubic_bezier_to_add.start.z,
-                cubic_bezier_to_add.end.x,
-                cubic_bezier_to_add.end.y,
-                cubic_bezier_to_add.end.z,
-                cubic_bezier_to_add.handle_1.x,
-                cubic_bezier_to_add.handle_1.y,
-                cubic_bezier_to_add.handle_1.z,
-                cubic_bezier_to_add.handle_2.x,
-                cubic_bezier_to_add.handle_2.y,
-                cubic_bezier_to_add.handle_2.z,
-                cubic_bezier_to_add.name,
-            ]
-        )?;
+    Ok(())
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn test_database() -> Database {
+        Database::new(":memory:".into())
     }
 
-    pub fn get_cubic_beziers(&self) -> Result<Vec<CubicBezier>> {
-        let mut statement = self.connection.prepare("SELECT * FROM CubicBezierCurves")?;
+    #[test]
+    fn test_save_copy() {
+        let directory = tempfile::tempdir().unwrap();
+        let database = Database::open(directory.path().join("venue.db")).unwrap();
+        database
+            .add_light(&Light {
+                name: "Spot 1".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let copy_path = directory.path().join("tour.db");
+        database.save_copy(&copy_path).unwrap();
+        // Saving again replaces the old copy
+        database.save_copy(&copy_path).unwrap();
+        assert!(database.save_copy(database.path()).is_err());
 
-        let line_iterator = statement.query_map([], |row| {
-            Ok(CubicBezier {
-                start: Vector3d::new(row.get(1)?, row.get(2)?, row.get(3)?),
-                end: Vector3d::new(row.get(4)?, row.get(5)?, row.get(6)?),
-                handle_1: Vector3d::new(row.get(7)?, row.get(8)?, row.get(9)?),
-                handle_2: Vector3d::new(row.get(10)?, row.get(11)?, row.get(12)?),
-                name: row.get(13)?,
+        let copy = Database::open(copy_path).unwrap();
+        assert_eq!(copy.get_lights().unwrap()[0].name, "Spot 1");
+    }
+
+    #[test]
+    fn test_open_not_a_database() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("notes.db");
+        fs::write(
+            &path,
+            "This is not a database, it's just some text that's long enough",
+        )
+        .unwrap();
+
+        assert!(Database::open(path).is_err());
+    }
+
+    #[test]
+    fn test_update_and_delete_light() {
+        let database = test_database();
+        let id = database
+            .add_light(&Light {
+                name: "Spot 1".to_string(),
+                address: 101,
+                ..Default::default()
             })
-        })?;
+            .unwrap();
+
+        let mut light = database.get_lights().unwrap()[0].clone();
+        assert_eq!(light.id, id);
+        let changes = database.changes();
+        light.address = 201;
+        database.update_light(&light).unwrap();
+        assert_eq!(database.get_lights().unwrap()[0].address, 201);
+        assert!(database.changes() > changes);
+
+        database.delete_light(id).unwrap();
+        assert!(database.get_lights().unwrap().is_empty());
+        assert!(database.delete_light(id).is_err());
+    }
 
-        Ok(line_iterator.collect::<Result<Vec<_>, _>>()?)
+    #[test]
+    fn test_update_spline_waypoints() {
+        let database = test_database();
+        let id = database
+            .add_path(&PathEnum::Spline(Spline {
+                name: "Walk".to_string(),
+                ..Spline::new(vec![
+                    Vector3d::new(0.0, 0.0, 0.0),
+                    Vector3d::new(1.0, 0.0, 0.0),
+                ])
+            }))
+            .unwrap();
+
+        database
+            .update_path(&PathEnum::Spline(Spline {
+                id,
+                name: "Walk".to_string(),
+                waypoints: vec![
+                    Vector3d::new(0.0, 0.0, 0.0),
+                    Vector3d::new(1.0, 0.0, 0.0),
+                    Vector3d::new(1.0, 1.0, 0.0),
+                ],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        let Some(PathEnum::Spline(spline)) = database.get_paths().unwrap().pop() else {
+            panic!("The spline should still be there");
+        };
+        assert_eq!(spline.waypoints.len(), 3);
     }
 
-    pub fn get_paths(&self) -> Result<Vec<PathEnum>> {
-        let mut lines: Vec<PathEnum> = self.get_lines()?.into_iter().map(PathEnum::Line).collect();
-        let mut bezier_curves: Vec<PathEnum> = self
-            .get_beziers()?
-            .into_iter()
-            .map(PathEnum::Bezier)
-            .collect();
-        let mut cubic_bezier_curves: Vec<PathEnum> = self
-            .get_cubic_beziers()?
-            .into_iter()
-            .map(PathEnum::CubicBezier)
-            .collect();
-
-        let mut out_vec: Vec<PathEnum> = vec![];
-        out_vec.append(&mut lines);
-        out_vec.append(&mut bezier_curves);
-        out_vec.append(&mut cubic_bezier_curves);
+    #[test]
+    fn test_every_kind_round_trips() {
+        let database = test_database();
+        let start = Vector3d::new(0.0, 0.0, 0.0);
+        let end = Vector3d::new(1.0, 2.0, 3.0);
+        let paths = vec![
+            PathEnum::Line(Line::new(start, end)),
+            PathEnum::Bezier(Bezier::new(start, Vector3d::new(0.5, 0.0, 0.0), end)),
+            PathEnum::CubicBezier(CubicBezier {
+                legacy_blend: true,
+                ..CubicBezier::new(start, end, Vector3d::new(0.5, 0.0, 0.0), end)
+            }),
+            PathEnum::Spline(Spline::new(vec![start, end])),
+            PathEnum::Circle(Circle::new(start, 2.0, Vector3d::new(0.0, 0.0, 1.0), 0.0)),
+            PathEnum::Arc(Arc::new(
+                start,
+                2.0,
+                Vector3d::new(0.0, 0.0, 1.0),
+                45.0,
+                90.0,
+            )),
+            PathEnum::Ellipse(Ellipse::new(
+                start,
+                2.0,
+                1.0,
+                Vector3d::new(0.0, 0.0, 1.0),
+                0.0,
+                -360.0,
+            )),
+        ];
+
+        let mut saved: Vec<PathEnum> = vec![];
+        for (i, path) in paths.into_iter().enumerate() {
+            let path = path.with_name(format!("Path {i}"));
+            let id = database.add_path(&path).unwrap();
+            saved.push(path.with_id(id));
+        }
+        let composite_path = PathEnum::CompositePath(CompositePath {
+            id: 0,
+            name: "Everything".to_string(),
+            segments: saved.clone(),
+            ..Default::default()
+        });
+        let id = database.add_path(&composite_path).unwrap();
+        saved.push(composite_path.with_id(id));
+
+        assert_eq!(database.get_paths().unwrap(), saved);
+    }
 
-        Ok(out_vec)
+    #[test]
+    fn test_delete_segment_of_composite_path() {
+        let database = test_database();
+        let line = PathEnum::Line(Line {
+            name: "Apron".to_string(),
+            ..Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0))
+        });
+        let riser = PathEnum::Line(Line {
+            name: "Riser".to_string(),
+            ..Line::new(Vector3d::new(1.0, 0.0, 0.0), Vector3d::new(1.0, 1.0, 0.0))
+        });
+        let line = line.clone().with_id(database.add_path(&line).unwrap());
+        let riser = riser.clone().with_id(database.add_path(&riser).unwrap());
+
+        database
+            .add_path(&PathEnum::CompositePath(CompositePath {
+                id: 0,
+                name: "Walk".to_string(),
+                segments: vec![line.clone(), riser],
+                ..Default::default()
+            }))
+            .unwrap();
+
+        database.delete_path(&line).unwrap();
+
+        let paths = database.get_paths().unwrap();
+        let Some(PathEnum::CompositePath(walk)) = paths.last() else {
+            panic!("The composite path should still be there");
+        };
+        assert_eq!(walk.segments.len(), 1);
+        assert_eq!(walk.segments[0].name(), "Riser");
+
+        // Deleting the composite path takes it's segment rows with it
+        database.delete_path(paths.last().unwrap()).unwrap();
+        let segment_rows: i64 = database
+            .connection
+            .query_row("SELECT COUNT(*) FROM PathSegments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(segment_rows, 0);
+    }
+
+    #[test]
+    fn test_migrate_to_unified_paths() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        // The last version with a table for each kind of path
+        MIGRATIONS.to_version(&mut connection, 8).unwrap();
+        connection
+            .execute_batch(
+                "INSERT INTO Lines (id, start_x, start_y, start_z, end_x, end_y, end_z, name) VALUES (1, 0, 0, 0, 4.5, 0, 0, 'Apron');
+                INSERT INTO CubicBezierCurves (id, start_x, start_y, start_z, end_x, end_y, end_z, handle_1_x, handle_1_y, handle_1_z, handle_2_x, handle_2_y, handle_2_z, name) VALUES (1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 1, 1, 0, 'Swoop');
+                INSERT INTO Splines (id, name) VALUES (1, 'Walk');
+                INSERT INTO SplineWaypoints (spline_id, position, x, y, z) VALUES (1, 1, 4.5, 2, 0), (1, 0, 4.5, 0, 0);
+                INSERT INTO CompositePaths (id, name) VALUES (1, 'Cross');
+                INSERT INTO CompositePathSegments (composite_path_id, position, segment_kind, segment_id) VALUES (1, 0, 'Line', 1), (1, 1, 'Spline', 1), (1, 2, 'Arc', 7);",
+            )
+            .unwrap();
+        MIGRATIONS.to_latest(&mut connection).unwrap();
+
+        let database = Database {
+            connection,
+            path: ":memory:".into(),
+        };
+        let paths = database.get_paths().unwrap();
+        let names: Vec<String> = paths.iter().map(|path| path.name()).collect();
+        assert_eq!(names, vec!["Apron", "Swoop", "Walk", "Cross"]);
+
+        let [PathEnum::Line(line), PathEnum::CubicBezier(swoop), PathEnum::Spline(walk), PathEnum::CompositePath(cross)] =
+            paths.as_slice()
+        else {
+            panic!("The paths should keep their kinds");
+        };
+        assert_eq!(line.end.x, 4.5);
+        assert!(swoop.legacy_blend);
+        assert_eq!(
+            walk.waypoints,
+            vec![Vector3d::new(4.5, 0.0, 0.0), Vector3d::new(4.5, 2.0, 0.0)]
+        );
+        // The segment that referred to a missing arc is dropped
+        assert_eq!(cross.segments, vec![paths[0].clone(), paths[2].clone()]);
+    }
+
+    #[test]
+    fn test_transaction_all_or_nothing() {
+        let database = test_database();
+        let line = PathEnum::Line(Line {
+            name: "Apron".to_string(),
+            ..Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0))
+        });
+
+        // The line is saved, then the unnamed spline fails
+        let result = database.transaction(|| {
+            database.add_path(&line)?;
+            database.add_path(&PathEnum::Spline(Spline::new(vec![
+                Vector3d::new(0.0, 0.0, 0.0),
+                Vector3d::new(1.0, 0.0, 0.0),
+            ])))
+        });
+        assert!(matches!(result, Err(DatabaseError::Invalid(_))));
+        assert!(database.get_paths().unwrap().is_empty());
+
+        database.transaction(|| database.add_path(&line)).unwrap();
+        assert_eq!(database.get_paths().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_migrations_valid() {
+        assert!(MIGRATIONS.validate().is_ok());
     }
 }
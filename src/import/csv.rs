@@ -0,0 +1,866 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use vector3d::Vector3d;
+
+use crate::{
+    db::{validate_light, validate_path, Database},
+    light::{Light, Mounting},
+    path::{
+        arc::Arc, bezier::Bezier, circle::Circle, cubic_bezier::CubicBezier, ellipse::Ellipse,
+        line::Line, spline::Spline, Path, PathEnum,
+    },
+};
+
+/// A table that can be written to or read from a CSV file.
+/// Composite paths aren't included as they only refer to other paths
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvTable {
+    Lights,
+    Lines,
+    Beziers,
+    CubicBeziers,
+    /// One row per waypoint, rows with the same name are joined in to one spline
+    Splines,
+    Circles,
+    Arcs,
+    Ellipses,
+}
+
+/// A column of a `CsvTable`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CsvColumn {
+    pub name: &'static str,
+    /// Rows can't be read without this column, optional columns use the default value when they're missing
+    pub required: bool,
+}
+
+const fn required(name: &'static str) -> CsvColumn {
+    CsvColumn {
+        name,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str) -> CsvColumn {
+    CsvColumn {
+        name,
+        required: false,
+    }
+}
+
+const LIGHT_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("address"),
+    required("coordinate_x"),
+    required("coordinate_y"),
+    required("coordinate_z"),
+    optional("minimum_beam"),
+    optional("maximum_beam"),
+    optional("mounting"),
+    optional("mounting_rotation_x"),
+    optional("mounting_rotation_y"),
+    optional("mounting_rotation_z"),
+    optional("pan_offset"),
+    optional("tilt_offset"),
+    optional("pan_inverted"),
+    optional("tilt_inverted"),
+    optional("pan_range"),
+    optional("tilt_range"),
+];
+
+const LINE_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("start_x"),
+    required("start_y"),
+    required("start_z"),
+    required("end_x"),
+    required("end_y"),
+    required("end_z"),
+];
+
+const BEZIER_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("start_x"),
+    required("start_y"),
+    required("start_z"),
+    required("midpoint_x"),
+    required("midpoint_y"),
+    required("midpoint_z"),
+    required("end_x"),
+    required("end_y"),
+    required("end_z"),
+];
+
+const CUBIC_BEZIER_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("start_x"),
+    required("start_y"),
+    required("start_z"),
+    required("end_x"),
+    required("end_y"),
+    required("end_z"),
+    required("handle_1_x"),
+    required("handle_1_y"),
+    required("handle_1_z"),
+    required("handle_2_x"),
+    required("handle_2_y"),
+    required("handle_2_z"),
+    optional("legacy_blend"),
+];
+
+const SPLINE_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("x"),
+    required("y"),
+    required("z"),
+];
+
+const CIRCLE_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("centre_x"),
+    required("centre_y"),
+    required("centre_z"),
+    required("radius"),
+    required("normal_x"),
+    required("normal_y"),
+    required("normal_z"),
+    optional("start_angle"),
+];
+
+const ARC_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("centre_x"),
+    required("centre_y"),
+    required("centre_z"),
+    required("radius"),
+    required("normal_x"),
+    required("normal_y"),
+    required("normal_z"),
+    optional("start_angle"),
+    required("sweep"),
+];
+
+const ELLIPSE_COLUMNS: &[CsvColumn] = &[
+    required("name"),
+    required("centre_x"),
+    required("centre_y"),
+    required("centre_z"),
+    required("radius_1"),
+    required("radius_2"),
+    required("normal_x"),
+    required("normal_y"),
+    required("normal_z"),
+    optional("start_angle"),
+    optional("sweep"),
+];
+
+/// Something read from a CSV file, ready to be saved
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvItem {
+    Light(Light),
+    Path(PathEnum),
+}
+
+/// A row (or rows, for splines) of a CSV file after it's been read
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedRow {
+    /// The line number in the file, the header is line 1
+    pub line: usize,
+    /// The item, or why it couldn't be read
+    pub item: Result<CsvItem, String>,
+}
+
+/// The contents of a CSV file, before they're mapped to a table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvFile {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// What to do when an imported item has the same name as one that already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Leave the existing one alone and don't import the new one
+    #[default]
+    Skip,
+    /// Replace the existing one with the new one
+    Overwrite,
+    /// Import the new one with a number added to it's name
+    Rename,
+}
+
+/// How many items an import added, skipped or overwrote
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+}
+
+impl CsvTable {
+    pub const ALL: [CsvTable; 8] = [
+        CsvTable::Lights,
+        CsvTable::Lines,
+        CsvTable::Beziers,
+        CsvTable::CubicBeziers,
+        CsvTable::Splines,
+        CsvTable::Circles,
+        CsvTable::Arcs,
+        CsvTable::Ellipses,
+    ];
+
+    /// The name shown to the user
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvTable::Lights => "Lights",
+            CsvTable::Lines => "Lines",
+            CsvTable::Beziers => "Bezier curves",
+            CsvTable::CubicBeziers => "Cubic bezier curves",
+            CsvTable::Splines => "Splines",
+            CsvTable::Circles => "Circles",
+            CsvTable::Arcs => "Arcs",
+            CsvTable::Ellipses => "Ellipses",
+        }
+    }
+
+    pub fn columns(&self) -> &'static [CsvColumn] {
+        match self {
+            CsvTable::Lights => LIGHT_COLUMNS,
+            CsvTable::Lines => LINE_COLUMNS,
+            CsvTable::Beziers => BEZIER_COLUMNS,
+            CsvTable::CubicBeziers => CUBIC_BEZIER_COLUMNS,
+            CsvTable::Splines => SPLINE_COLUMNS,
+            CsvTable::Circles => CIRCLE_COLUMNS,
+            CsvTable::Arcs => ARC_COLUMNS,
+            CsvTable::Ellipses => ELLIPSE_COLUMNS,
+        }
+    }
+
+    /// The `PathEnum::kind` of the paths in this table, `None` for lights
+    fn path_kind(&self) -> Option<&'static str> {
+        match self {
+            CsvTable::Lights => None,
+            CsvTable::Lines => Some("Line"),
+            CsvTable::Beziers => Some("Bezier"),
+            CsvTable::CubicBeziers => Some("CubicBezier"),
+            CsvTable::Splines => Some("Spline"),
+            CsvTable::Circles => Some("Circle"),
+            CsvTable::Arcs => Some("Arc"),
+            CsvTable::Ellipses => Some("Ellipse"),
+        }
+    }
+
+    /// Writes every row of this table to a CSV file, with a header row
+    pub fn export(&self, database: &Database) -> Result<String> {
+        let mut writer = ::csv::Writer::from_writer(vec![]);
+        writer.write_record(self.columns().iter().map(|column| column.name))?;
+
+        if let CsvTable::Lights = self {
+            for light in database.get_lights()? {
+                writer.write_record(light_record(&light))?;
+            }
+        } else {
+            let kind = self.path_kind();
+            for path in database.get_segment_paths()? {
+                if Some(path.kind()) != kind {
+                    continue;
+                }
+                for record in path_records(&path) {
+                    writer.write_record(record)?;
+                }
+            }
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+
+    /// Picks a file column for each of this table's columns by name, ignoring case, spaces and underscores
+    pub fn guess_mapping(&self, headers: &[String]) -> Vec<Option<usize>> {
+        let simplify = |name: &str| {
+            name.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+
+        self.columns()
+            .iter()
+            .map(|column| {
+                headers
+                    .iter()
+                    .position(|header| simplify(header) == simplify(column.name))
+            })
+            .collect()
+    }
+
+    /// Reads and validates every row of a file. `mapping` says which file column each of this table's columns is in
+    pub fn parse(&self, file: &CsvFile, mapping: &[Option<usize>]) -> Vec<ParsedRow> {
+        let rows = file.rows.iter().enumerate().map(|(i, row)| RowValues {
+            columns: self.columns(),
+            mapping,
+            row,
+            // The header is line 1
+            line: i + 2,
+        });
+
+        if let CsvTable::Splines = self {
+            return parse_splines(rows);
+        }
+
+        rows.map(|row| ParsedRow {
+            line: row.line,
+            item: self
+                .parse_row(&row)
+                .and_then(|item| {
+                    match &item {
+                        CsvItem::Light(light) => validate_light(light)?,
+                        CsvItem::Path(path) => validate_path(path)?,
+                    }
+                    Ok(item)
+                })
+                .map_err(|e| format!("{e:#}")),
+        })
+        .collect()
+    }
+
+    fn parse_row(&self, row: &RowValues<'_>) -> Result<CsvItem> {
+        let name = row.text("name")?.unwrap_or_default().to_string();
+
+        let path = match self {
+            CsvTable::Lights => {
+                let default = Light::default();
+                return Ok(CsvItem::Light(Light {
+                    id: 0,
+                    coordinates: row.vector("coordinate")?,
+                    minimum_beam: row.number("minimum_beam")?.unwrap_or(default.minimum_beam),
+                    maximum_beam: row.number("maximum_beam")?.unwrap_or(default.maximum_beam),
+                    name,
+                    address: row.required_number("address")?,
+                    mounting: match row.text("mounting")? {
+                        Some(kind) => {
+                            let Some(mounting) = ["Hung", "Standing", "Rotated"]
+                                .iter()
+                                .find(|mounting| mounting.eq_ignore_ascii_case(kind.trim()))
+                            else {
+                                bail!("{kind} is not a mounting, use Hung, Standing or Rotated");
+                            };
+                            Mounting::from_kind(mounting, row.vector("mounting_rotation")?)
+                        }
+                        None => default.mounting,
+                    },
+                    pan_offset: row.number("pan_offset")?.unwrap_or(default.pan_offset),
+                    tilt_offset: row.number("tilt_offset")?.unwrap_or(default.tilt_offset),
+                    pan_inverted: row.bool("pan_inverted")?.unwrap_or(default.pan_inverted),
+                    tilt_inverted: row.bool("tilt_inverted")?.unwrap_or(default.tilt_inverted),
+                    pan_range: row.number("pan_range")?.unwrap_or(default.pan_range),
+                    tilt_range: row.number("tilt_range")?.unwrap_or(default.tilt_range),
+                }));
+            }
+            CsvTable::Lines => PathEnum::Line(Line {
+                name,
+                ..Line::new(row.vector("start")?, row.vector("end")?)
+            }),
+            CsvTable::Beziers => PathEnum::Bezier(Bezier {
+                name,
+                ..Bezier::new(
+                    row.vector("start")?,
+                    row.vector("midpoint")?,
+                    row.vector("end")?,
+                )
+            }),
+            CsvTable::CubicBeziers => PathEnum::CubicBezier(CubicBezier {
+                name,
+                legacy_blend: row.bool("legacy_blend")?.unwrap_or(false),
+                ..CubicBezier::new(
+                    row.vector("start")?,
+                    row.vector("end")?,
+                    row.vector("handle_1")?,
+                    row.vector("handle_2")?,
+                )
+            }),
+            CsvTable::Splines => unreachable!("Splines are read by parse_splines"),
+            CsvTable::Circles => PathEnum::Circle(Circle {
+                name,
+                ..Circle::new(
+                    row.vector("centre")?,
+                    row.required_number("radius")?,
+                    row.vector("normal")?,
+                    row.number("start_angle")?.unwrap_or(0.0),
+                )
+            }),
+            CsvTable::Arcs => PathEnum::Arc(Arc {
+                name,
+                ..Arc::new(
+                    row.vector("centre")?,
+                    row.required_number("radius")?,
+                    row.vector("normal")?,
+                    row.number("start_angle")?.unwrap_or(0.0),
+                    row.required_number("sweep")?,
+                )
+            }),
+            CsvTable::Ellipses => PathEnum::Ellipse(Ellipse {
+                name,
+                ..Ellipse::new(
+                    row.vector("centre")?,
+                    row.required_number("radius_1")?,
+                    row.required_number("radius_2")?,
+                    row.vector("normal")?,
+                    row.number("start_angle")?.unwrap_or(0.0),
+                    row.number("sweep")?.unwrap_or(360.0),
+                )
+            }),
+        };
+
+        Ok(CsvItem::Path(path))
+    }
+}
+
+/// Joins rows with the same name in to splines, in the order they're first seen
+fn parse_splines<'a>(rows: impl Iterator<Item = RowValues<'a>>) -> Vec<ParsedRow> {
+    let mut parsed: Vec<ParsedRow> = vec![];
+
+    for row in rows {
+        let waypoint = row
+            .text("name")
+            .and_then(|name| Ok((name.unwrap_or_default().to_string(), row.vector("")?)));
+
+        let (name, waypoint) = match waypoint {
+            Ok(waypoint) => waypoint,
+            Err(e) => {
+                parsed.push(ParsedRow {
+                    line: row.line,
+                    item: Err(format!("{e:#}")),
+                });
+                continue;
+            }
+        };
+
+        let existing = parsed
+            .iter_mut()
+            .find_map(|parsed_row| match &mut parsed_row.item {
+                Ok(CsvItem::Path(PathEnum::Spline(spline))) if spline.name == name => Some(spline),
+                _ => None,
+            });
+
+        match existing {
+            Some(spline) => spline.waypoints.push(waypoint),
+            None => parsed.push(ParsedRow {
+                line: row.line,
+                item: Ok(CsvItem::Path(PathEnum::Spline(Spline {
+                    name,
+                    ..Spline::new(vec![waypoint])
+                }))),
+            }),
+        }
+    }
+
+    for row in &mut parsed {
+        if let Ok(CsvItem::Path(path)) = &row.item {
+            if let Err(e) = validate_path(path) {
+                row.item = Err(format!("{e:#}"));
+            }
+        }
+    }
+
+    parsed
+}
+
+/// The values of one row, looked up by the table's column names
+struct RowValues<'a> {
+    columns: &'static [CsvColumn],
+    mapping: &'a [Option<usize>],
+    row: &'a [String],
+    line: usize,
+}
+
+impl RowValues<'_> {
+    /// The text in a column, `None` if the column isn't mapped or the cell is empty
+    fn text(&self, column: &str) -> Result<Option<&str>> {
+        let Some(index) = self.columns.iter().position(|c| c.name == column) else {
+            bail!("{column} is not a column of this table");
+        };
+
+        let value = self.mapping[index]
+            .and_then(|file_column| self.row.get(file_column))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty());
+
+        if value.is_none() && self.columns[index].required {
+            bail!("{column} is empty");
+        }
+
+        Ok(value)
+    }
+
+    fn number<T: std::str::FromStr>(&self, column: &str) -> Result<Option<T>> {
+        match self.text(column)? {
+            Some(value) => {
+                Ok(Some(value.parse().ok().with_context(|| {
+                    format!("{column} should be a number, not {value}")
+                })?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn required_number<T: std::str::FromStr + Default>(&self, column: &str) -> Result<T> {
+        Ok(self.number(column)?.unwrap_or_default())
+    }
+
+    fn bool(&self, column: &str) -> Result<Option<bool>> {
+        match self.text(column)?.map(str::to_lowercase).as_deref() {
+            Some("true" | "yes" | "y" | "1") => Ok(Some(true)),
+            Some("false" | "no" | "n" | "0") => Ok(Some(false)),
+            Some(value) => bail!("{column} should be true or false, not {value}"),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the `<prefix>_x`, `<prefix>_y` and `<prefix>_z` columns, or `x`, `y` and `z` if the prefix is empty
+    fn vector(&self, prefix: &str) -> Result<Vector3d<f64>> {
+        let column = |axis: &str| {
+            if prefix.is_empty() {
+                axis.to_string()
+            } else {
+                format!("{prefix}_{axis}")
+            }
+        };
+
+        Ok(Vector3d::new(
+            self.required_number(&column("x"))?,
+            self.required_number(&column("y"))?,
+            self.required_number(&column("z"))?,
+        ))
+    }
+}
+
+/// Reads a CSV file, the first row is the headers
+pub fn read_csv(contents: &str) -> Result<CsvFile> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader
+        .headers()
+        .context("Failed to read the header row")?
+        .iter()
+        .map(str::to_string)
+        .collect();
+
+    let rows = reader
+        .records()
+        .map(|record| Ok(record?.iter().map(str::to_string).collect()))
+        .collect::<Result<Vec<Vec<String>>>>()
+        .context("Failed to read CSV file")?;
+
+    Ok(CsvFile { headers, rows })
+}
+
+fn vector_fields(vector: Vector3d<f64>) -> [String; 3] {
+    [
+        vector.x.to_string(),
+        vector.y.to_string(),
+        vector.z.to_string(),
+    ]
+}
+
+/// A light's values, in the order of `LIGHT_COLUMNS`
+fn light_record(light: &Light) -> Vec<String> {
+    let mut record = vec![light.name.clone(), light.address.to_string()];
+    record.extend(vector_fields(light.coordinates));
+    record.push(light.minimum_beam.to_string());
+    record.push(light.maximum_beam.to_string());
+    record.push(light.mounting.kind().to_string());
+    record.extend(vector_fields(light.mounting.rotation()));
+    record.push(light.pan_offset.to_string());
+    record.push(light.tilt_offset.to_string());
+    record.push(light.pan_inverted.to_string());
+    record.push(light.tilt_inverted.to_string());
+    record.push(light.pan_range.to_string());
+    record.push(light.tilt_range.to_string());
+    record
+}
+
+/// A path's values, in the order of it's table's columns. Splines give one record per waypoint
+fn path_records(path: &PathEnum) -> Vec<Vec<String>> {
+    let mut record = vec![path.name()];
+
+    match path {
+        PathEnum::Line(line) => {
+            record.extend(vector_fields(line.start));
+            record.extend(vector_fields(line.end));
+        }
+        PathEnum::Bezier(bezier) => {
+            record.extend(vector_fields(bezier.start));
+            record.extend(vector_fields(bezier.midpoint));
+            record.extend(vector_fields(bezier.end));
+        }
+        PathEnum::CubicBezier(cubic_bezier) => {
+            record.extend(vector_fields(cubic_bezier.start));
+            record.extend(vector_fields(cubic_bezier.end));
+            record.extend(vector_fields(cubic_bezier.handle_1));
+            record.extend(vector_fields(cubic_bezier.handle_2));
+            record.push(cubic_bezier.legacy_blend.to_string());
+        }
+        PathEnum::Spline(spline) => {
+            return spline
+                .waypoints
+                .iter()
+                .map(|waypoint| {
+                    let mut record = vec![spline.name.clone()];
+                    record.extend(vector_fields(*waypoint));
+                    record
+                })
+                .collect();
+        }
+        PathEnum::Circle(circle) => {
+            record.extend(vector_fields(circle.centre));
+            record.push(circle.radius.to_string());
+            record.extend(vector_fields(circle.normal));
+            record.push(circle.start_angle.to_string());
+        }
+        PathEnum::Arc(arc) => {
+            record.extend(vector_fields(arc.centre));
+            record.push(arc.radius.to_string());
+            record.extend(vector_fields(arc.normal));
+            record.push(arc.start_angle.to_string());
+            record.push(arc.sweep.to_string());
+        }
+        PathEnum::Ellipse(ellipse) => {
+            record.extend(vector_fields(ellipse.centre));
+            record.push(ellipse.radius_1.to_string());
+            record.push(ellipse.radius_2.to_string());
+            record.extend(vector_fields(ellipse.normal));
+            record.push(ellipse.start_angle.to_string());
+            record.push(ellipse.sweep.to_string());
+        }
+        PathEnum::CompositePath(_) => return vec![],
+    }
+
+    vec![record]
+}
+
+/// Adds a number to the end of `name` until it isn't in `taken`
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|number| format!("{name} ({number})"))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("There are always more numbers")
+}
+
+/// Saves the rows that were read successfully, dealing with names that already exist using `policy`
+pub fn save_csv_items(
+    database: &Database,
+    rows: &[ParsedRow],
+    policy: DuplicatePolicy,
+) -> Result<CsvImportSummary> {
+    // All or nothing, so a failed save doesn't leave half the file imported
+    database.transaction(|| {
+        let mut summary = CsvImportSummary::default();
+        let lights: Vec<Light> = database.get_lights()?;
+        let paths: Vec<PathEnum> = database.get_paths()?;
+
+        // The id of every name that's already used. Names are only duplicates of other names of the same kind
+        let mut taken: HashMap<(Option<&str>, String), i64> = lights
+            .into_iter()
+            .map(|light| ((None, light.name), light.id))
+            .chain(
+                paths
+                    .iter()
+                    .map(|path| ((Some(path.kind()), path.name()), path.id())),
+            )
+            .collect();
+
+        for row in rows {
+            let Ok(item) = &row.item else {
+                continue;
+            };
+
+            let (kind, name) = match item {
+                CsvItem::Light(light) => (None, light.name.clone()),
+                CsvItem::Path(path) => (Some(path.kind()), path.name()),
+            };
+
+            let mut item = item.clone();
+            if let Some(&existing_id) = taken.get(&(kind, name.clone())) {
+                match policy {
+                    DuplicatePolicy::Skip => {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    DuplicatePolicy::Overwrite => {
+                        // Updated in place so composite paths that use it still find it
+                        match item {
+                            CsvItem::Light(light) => database.update_light(&Light {
+                                id: existing_id,
+                                ..light
+                            })?,
+                            CsvItem::Path(path) => {
+                                database.update_path(&path.with_id(existing_id))?
+                            }
+                        };
+                        summary.overwritten += 1;
+                        continue;
+                    }
+                    DuplicatePolicy::Rename => {
+                        let names_of_kind: HashSet<String> = taken
+                            .keys()
+                            .filter(|(taken_kind, _)| *taken_kind == kind)
+                            .map(|(_, name)| name.clone())
+                            .collect();
+                        let new_name = unique_name(&name, &names_of_kind);
+                        match &mut item {
+                            CsvItem::Light(light) => light.name = new_name.clone(),
+                            CsvItem::Path(path) => *path = path.clone().with_name(new_name.clone()),
+                        }
+                        summary.renamed += 1;
+                    }
+                }
+            }
+
+            let (id, name) = match &item {
+                CsvItem::Light(light) => (database.add_light(light)?, light.name.clone()),
+                CsvItem::Path(path) => (database.add_path(path)?, path.name()),
+            };
+            taken.insert((kind, name), id);
+            summary.added += 1;
+        }
+
+        Ok(summary)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn test_database() -> Database {
+        Database::new(":memory:".into())
+    }
+
+    fn parse(table: CsvTable, contents: &str) -> Vec<ParsedRow> {
+        let file = read_csv(contents).unwrap();
+        let mapping = table.guess_mapping(&file.headers);
+        table.parse(&file, &mapping)
+    }
+
+    #[test]
+    fn test_guess_mapping() {
+        let headers = vec![
+            "Coordinate X".to_string(),
+            "NAME".to_string(),
+            "Notes".to_string(),
+        ];
+        let mapping = CsvTable::Lights.guess_mapping(&headers);
+        assert_eq!(mapping[0], Some(1));
+        assert_eq!(mapping[1], None);
+        assert_eq!(mapping[2], Some(0));
+    }
+
+    #[test]
+    fn test_parse_lights() {
+        let rows = parse(
+            CsvTable::Lights,
+            "name,address,coordinate_x,coordinate_y,coordinate_z,mounting,pan_inverted\n\
+             Spot 1,101,1,2,6,standing,yes\n\
+             Spot 2,,1,2,6,,\n\
+             Spot 3,103,1,two,6,,\n\
+             Spot 4,104,0,0,0,Sideways,\n",
+        );
+
+        assert_eq!(rows.len(), 4);
+        let Ok(CsvItem::Light(light)) = &rows[0].item else {
+            panic!("The first row is valid");
+        };
+        assert_eq!(light.address, 101);
+        assert_eq!(light.mounting, Mounting::Standing);
+        assert!(light.pan_inverted);
+        assert_eq!(light.pan_range, 540.0);
+
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].item, Err("address is empty".to_string()));
+        assert!(rows[2].item.is_err());
+        assert!(rows[3].item.is_err());
+    }
+
+    #[test]
+    fn test_parse_splines() {
+        let rows = parse(
+            CsvTable::Splines,
+            "name,x,y,z\nWalk,0,0,0\nWalk,1,0,0\nLonely,5,5,5\nWalk,1,1,0\n",
+        );
+
+        assert_eq!(rows.len(), 2);
+        let Ok(CsvItem::Path(PathEnum::Spline(spline))) = &rows[0].item else {
+            panic!("Walk has enough waypoints");
+        };
+        assert_eq!(spline.waypoints.len(), 3);
+        assert_eq!(
+            rows[1].item,
+            Err("Spline needs at least 2 waypoints".to_string())
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let database = test_database();
+        database
+            .add_path(&PathEnum::Line(Line {
+                name: "Apron".to_string(),
+                ..Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(4.5, 0.0, 0.0))
+            }))
+            .unwrap();
+
+        let exported = CsvTable::Lines.export(&database).unwrap();
+        let rows = parse(CsvTable::Lines, &exported);
+
+        let other_database = test_database();
+        save_csv_items(&other_database, &rows, DuplicatePolicy::Skip).unwrap();
+        let paths = other_database.get_paths().unwrap();
+        let [PathEnum::Line(line)] = paths.as_slice() else {
+            panic!("There should be one line");
+        };
+        assert_eq!(line.end.x, 4.5);
+    }
+
+    #[test]
+    fn test_duplicate_policies() {
+        let database = test_database();
+        let contents = "name,start_x,start_y,start_z,end_x,end_y,end_z\nApron,0,0,0,1,0,0\n";
+        let rows = parse(CsvTable::Lines, contents);
+        save_csv_items(&database, &rows, DuplicatePolicy::Skip).unwrap();
+
+        let summary = save_csv_items(&database, &rows, DuplicatePolicy::Skip).unwrap();
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(database.get_paths().unwrap().len(), 1);
+
+        // Overwritten in place, so it keeps it's id and anything that refers to it still finds it
+        let id = database.get_paths().unwrap()[0].id();
+        let moved = parse(
+            CsvTable::Lines,
+            "name,start_x,start_y,start_z,end_x,end_y,end_z\nApron,0,0,0,2,0,0\n",
+        );
+        let summary = save_csv_items(&database, &moved, DuplicatePolicy::Overwrite).unwrap();
+        assert_eq!(summary.overwritten, 1);
+        let paths = database.get_paths().unwrap();
+        let [PathEnum::Line(line)] = paths.as_slice() else {
+            panic!("The line should have been overwritten");
+        };
+        assert_eq!(line.id, id);
+        assert_eq!(line.end, Vector3d::new(2.0, 0.0, 0.0));
+
+        save_csv_items(&database, &rows, DuplicatePolicy::Rename).unwrap();
+        save_csv_items(&database, &rows, DuplicatePolicy::Rename).unwrap();
+        let names: Vec<String> = database
+            .get_paths()
+            .unwrap()
+            .iter()
+            .map(|path| path.name())
+            .collect();
+        assert_eq!(names, vec!["Apron", "Apron (2)", "Apron (3)"]);
+    }
+}
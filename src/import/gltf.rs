@@ -0,0 +1,286 @@
+use std::fs;
+
+use ::gltf::{buffer::Source, mesh::Mode, Gltf, Node};
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use vector3d::Vector3d;
+
+use crate::import::{
+    model::{join_polylines, polylines_to_paths, ModelImportSettings},
+    ImportedPath,
+};
+
+/// A column major 4x4 transform, as glTF stores them
+type Matrix = [[f64; 4]; 4];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Reads every line primitive (lines, line strips and line loops) out of a glTF or GLB file.
+/// Triangles and points are ignored. Each node's transform is applied, and paths are named after
+/// the node or mesh they're in. External buffers are looked for in `base_directory`
+pub fn import_gltf(
+    file: &[u8],
+    base_directory: Option<&std::path::Path>,
+    default_name: &str,
+    settings: &ModelImportSettings,
+) -> Result<Vec<ImportedPath>> {
+    let gltf = Gltf::from_slice(file).context("Failed to read glTF file")?;
+    let buffers = load_buffers(&gltf, base_directory)?;
+
+    let root_nodes: Vec<Node<'_>> = match gltf.default_scene().or(gltf.scenes().next()) {
+        Some(scene) => scene.nodes().collect(),
+        // Without a scene every node that isn't a child of another node is a root
+        None => {
+            let children: Vec<usize> = gltf
+                .nodes()
+                .flat_map(|node| {
+                    node.children()
+                        .map(|child| child.index())
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            gltf.nodes()
+                .filter(|node| !children.contains(&node.index()))
+                .collect()
+        }
+    };
+
+    let mut paths: Vec<ImportedPath> = vec![];
+    let mut unnamed = 0;
+    for node in root_nodes {
+        read_node(
+            &node,
+            &IDENTITY,
+            &buffers,
+            default_name,
+            &mut unnamed,
+            settings,
+            &mut paths,
+        )?;
+    }
+
+    Ok(paths)
+}
+
+/// Reads the line primitives of a node and all of it's children
+fn read_node(
+    node: &Node<'_>,
+    parent_transform: &Matrix,
+    buffers: &[Vec<u8>],
+    default_name: &str,
+    unnamed: &mut usize,
+    settings: &ModelImportSettings,
+    paths: &mut Vec<ImportedPath>,
+) -> Result<()> {
+    let local_transform = node
+        .transform()
+        .matrix()
+        .map(|column| column.map(f64::from));
+    let transform = multiply(parent_transform, &local_transform);
+
+    if let Some(mesh) = node.mesh() {
+        let mut polylines: Vec<Vec<Vector3d<f64>>> = vec![];
+
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+            let positions: Vec<Vector3d<f64>> = positions
+                .map(|[x, y, z]| {
+                    transform_point(&transform, Vector3d::new(x.into(), y.into(), z.into()))
+                })
+                .collect();
+
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            if indices.iter().any(|&i| i >= positions.len()) {
+                bail!(
+                    "A primitive in {} refers to a missing vertex",
+                    node_name(node)
+                );
+            }
+
+            let runs: Vec<Vec<usize>> = match primitive.mode() {
+                Mode::Lines => indices.chunks_exact(2).map(<[usize]>::to_vec).collect(),
+                Mode::LineStrip => vec![indices],
+                Mode::LineLoop => {
+                    let mut run = indices.clone();
+                    run.extend(indices.first());
+                    vec![run]
+                }
+                _ => continue,
+            };
+
+            polylines.extend(
+                join_polylines(runs)
+                    .into_iter()
+                    .map(|run| run.into_iter().map(|i| positions[i]).collect()),
+            );
+        }
+
+        if !polylines.is_empty() {
+            let name = match node.name().or(mesh.name()) {
+                Some(name) => name.to_string(),
+                None => {
+                    *unnamed += 1;
+                    format!("{default_name} {unnamed}")
+                }
+            };
+            paths.extend(polylines_to_paths(&name, polylines, settings));
+        }
+    }
+
+    for child in node.children() {
+        read_node(
+            &child,
+            &transform,
+            buffers,
+            default_name,
+            unnamed,
+            settings,
+            paths,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// A name for a node to use in error messages
+fn node_name(node: &Node<'_>) -> String {
+    match node.name() {
+        Some(name) => name.to_string(),
+        None => format!("node {}", node.index()),
+    }
+}
+
+/// Gets the data of every buffer, from the GLB's binary chunk, a base64 data URI, or a file next to the glTF file
+fn load_buffers(gltf: &Gltf, base_directory: Option<&std::path::Path>) -> Result<Vec<Vec<u8>>> {
+    gltf.buffers()
+        .map(|buffer| match buffer.source() {
+            Source::Bin => gltf
+                .blob
+                .clone()
+                .context("The GLB file is missing it's binary chunk"),
+            Source::Uri(uri) if uri.starts_with("data:") => {
+                let Some((_, data)) = uri.split_once(";base64,") else {
+                    bail!("Only base64 data URIs are supported");
+                };
+                base64::engine::general_purpose::STANDARD
+                    .decode(data)
+                    .context("Failed to decode embedded buffer")
+            }
+            Source::Uri(uri) => {
+                let Some(base_directory) = base_directory else {
+                    bail!("Can't find the external buffer {uri}");
+                };
+                let file = base_directory.join(uri.replace("%20", " "));
+                fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))
+            }
+        })
+        .collect()
+}
+
+/// Multiplies two column major matrices, applying `b` and then `a`
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (column, result_column) in result.iter_mut().enumerate() {
+        for (row, value) in result_column.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b[column][k]).sum();
+        }
+    }
+    result
+}
+
+fn transform_point(matrix: &Matrix, point: Vector3d<f64>) -> Vector3d<f64> {
+    let row = |row: usize| {
+        matrix[0][row] * point.x
+            + matrix[1][row] * point.y
+            + matrix[2][row] * point.z
+            + matrix[3][row]
+    };
+    Vector3d::new(row(0), row(1), row(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::PathEnum;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    /// A glTF file with a line strip of 3 vertices in an embedded buffer, on a node that's moved 2 units along x
+    fn test_file() -> String {
+        let positions: [f32; 9] = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, -1.0];
+        let bytes: Vec<u8> = positions.iter().flat_map(|p| p.to_le_bytes()).collect();
+        let data = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+        format!(
+            r#"{{
+                "asset": {{ "version": "2.0" }},
+                "scene": 0,
+                "scenes": [{{ "nodes": [0] }}],
+                "nodes": [{{ "name": "Cyc", "mesh": 0, "translation": [2.0, 0.0, 0.0] }}],
+                "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "mode": 3 }}] }}],
+                "accessors": [{{
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0.0, 0.0, -1.0], "max": [1.0, 1.0, 0.0]
+                }}],
+                "bufferViews": [{{ "buffer": 0, "byteLength": 36 }}],
+                "buffers": [{{ "byteLength": 36, "uri": "data:application/octet-stream;base64,{data}" }}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn test_import_gltf_line_strip() {
+        let paths = import_gltf(
+            test_file().as_bytes(),
+            None,
+            "Model",
+            &ModelImportSettings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].name, "Cyc");
+        assert_eq!(paths[0].segments.len(), 2);
+
+        let PathEnum::Line(line) = &paths[0].segments[1] else {
+            panic!("Line strips should become lines");
+        };
+        // Moved by the node, then y up is turned in to z up
+        assert_eq!(line.start, Vector3d::new(3.0, 0.0, 0.0));
+        assert_eq!(line.end, Vector3d::new(3.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_multiply() {
+        let translate = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [1.0, 2.0, 3.0, 1.0],
+        ];
+        let scale = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0, 0.0],
+            [0.0, 0.0, 2.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+
+        // Scaled first, then translated
+        let combined = multiply(&translate, &scale);
+        assert_eq!(
+            transform_point(&combined, Vector3d::new(1.0, 1.0, 1.0)),
+            Vector3d::new(3.0, 4.0, 5.0)
+        );
+    }
+}
@@ -0,0 +1,67 @@
+pub mod csv;
+pub mod gltf;
+pub mod model;
+pub mod obj;
+pub mod svg;
+
+use anyhow::Result;
+
+use crate::{
+    db::Database,
+    path::{composite::CompositePath, Path, PathEnum},
+};
+
+/// A path read from a file, made of one or more segments that follow on from each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPath {
+    pub name: String,
+    pub segments: Vec<PathEnum>,
+}
+
+/// Saves imported paths to the database, returning how many paths were saved.
+/// Paths with one segment are saved as that segment, paths with more are saved as each segment plus a composite path that chains them together
+pub fn save_imported_paths(database: &Database, paths: &[ImportedPath]) -> Result<usize> {
+    // All or nothing, so a failed import doesn't leave some of it's segments behind
+    database.transaction(|| {
+        let mut saved = 0;
+
+        for path in paths {
+            if let [segment] = path.segments.as_slice() {
+                database.add_path(segment)?;
+                saved += 1;
+                continue;
+            }
+
+            let mut saved_segments: Vec<PathEnum> = vec![];
+            for segment in &path.segments {
+                let id = database.add_path(segment)?;
+                saved_segments.push(segment.clone().with_id(id));
+            }
+
+            database.add_path(&PathEnum::CompositePath(CompositePath {
+                id: 0,
+                name: path.name.clone(),
+                ..CompositePath::new(saved_segments)
+            }))?;
+            saved += 1;
+        }
+
+        Ok(saved)
+    })
+}
+
+/// Names each segment after the path it's in, numbering them if there's more than one
+pub(crate) fn segment_name(path_name: &str, segment: usize, segment_count: usize) -> String {
+    if segment_count == 1 {
+        path_name.to_string()
+    } else {
+        format!("{path_name} {}", segment + 1)
+    }
+}
+
+impl ImportedPath {
+    /// The total length of all of this path's segments
+    pub fn length(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.length()).sum()
+    }
+}
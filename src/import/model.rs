@@ -0,0 +1,147 @@
+use vector3d::Vector3d;
+
+use crate::{
+    import::{segment_name, ImportedPath},
+    path::{line::Line, spline::Spline, PathEnum},
+};
+
+/// Which way is up in the file being imported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpAxis {
+    /// Blender's OBJ exporter and every glTF file. -z is forward
+    Y,
+    /// The same as this project, nothing is changed
+    Z,
+}
+
+/// What the points of each imported polyline are turned in to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PolylineShape {
+    /// A straight line between each pair of points, chained together
+    Lines,
+    /// One smooth spline that passes through every point
+    Spline,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelImportSettings {
+    pub up_axis: UpAxis,
+    /// Metres per unit in the file
+    pub scale: f64,
+    /// Where the file's (0, 0, 0) ends up, in this project's coordinates
+    pub origin: Vector3d<f64>,
+    pub shape: PolylineShape,
+}
+
+impl Default for ModelImportSettings {
+    fn default() -> Self {
+        Self {
+            up_axis: UpAxis::Y,
+            scale: 1.0,
+            origin: Vector3d::default(),
+            shape: PolylineShape::Lines,
+        }
+    }
+}
+
+impl ModelImportSettings {
+    /// Takes a point in the file's coordinates and places it in this project's z up space
+    pub(crate) fn place(&self, point: Vector3d<f64>) -> Vector3d<f64> {
+        let point = match self.up_axis {
+            // y up, -z forward becomes z up, +y forward
+            UpAxis::Y => Vector3d::new(point.x, -point.z, point.y),
+            UpAxis::Z => point,
+        };
+
+        self.origin + point * self.scale
+    }
+}
+
+/// Joins runs of vertex indices that follow on from each other in to longer polylines.
+/// Most exporters write a curve as lots of two point lines, this puts them back together
+pub(crate) fn join_polylines(runs: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    let mut polylines: Vec<Vec<usize>> = vec![];
+
+    for run in runs {
+        if run.len() < 2 {
+            continue;
+        }
+
+        match polylines.last_mut() {
+            Some(last) if last.last() == run.first() => last.extend_from_slice(&run[1..]),
+            _ => polylines.push(run),
+        }
+    }
+
+    polylines
+}
+
+/// Turns the polylines of one object in to named paths, numbering them if the object has more than one
+pub(crate) fn polylines_to_paths(
+    name: &str,
+    polylines: Vec<Vec<Vector3d<f64>>>,
+    settings: &ModelImportSettings,
+) -> Vec<ImportedPath> {
+    let polyline_count = polylines.len();
+
+    polylines
+        .into_iter()
+        .enumerate()
+        .map(|(i, points)| {
+            let name = segment_name(name, i, polyline_count);
+            let points: Vec<Vector3d<f64>> = points
+                .into_iter()
+                .map(|point| settings.place(point))
+                .collect();
+
+            let segments = match settings.shape {
+                PolylineShape::Spline => vec![PathEnum::Spline(Spline {
+                    name: name.clone(),
+                    ..Spline::new(points)
+                })],
+                PolylineShape::Lines => {
+                    let segment_count = points.len() - 1;
+                    points
+                        .windows(2)
+                        .enumerate()
+                        .map(|(j, pair)| {
+                            PathEnum::Line(Line {
+                                name: segment_name(&name, j, segment_count),
+                                ..Line::new(pair[0], pair[1])
+                            })
+                        })
+                        .collect()
+                }
+            };
+
+            ImportedPath { name, segments }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_join_polylines() {
+        let runs = vec![vec![0, 1], vec![1, 2], vec![2, 3], vec![5], vec![6, 7, 8]];
+        assert_eq!(join_polylines(runs), vec![vec![0, 1, 2, 3], vec![6, 7, 8]]);
+    }
+
+    #[test]
+    fn test_place_y_up() {
+        let settings = ModelImportSettings {
+            scale: 0.01,
+            origin: Vector3d::new(0.0, 0.0, 1.0),
+            ..Default::default()
+        };
+
+        // 2 units up and 3 units towards -z (forward)
+        let placed = settings.place(Vector3d::new(1.0, 2.0, -3.0));
+        assert!(eq_vector3d(placed, Vector3d::new(0.01, 0.03, 1.02)));
+    }
+}
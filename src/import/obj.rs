@@ -0,0 +1,186 @@
+use anyhow::{bail, Context, Result};
+use vector3d::Vector3d;
+
+use crate::import::{
+    model::{join_polylines, polylines_to_paths, ModelImportSettings},
+    ImportedPath,
+};
+
+/// The `l` elements of one `o` or `g` block in an OBJ file
+struct ObjObject {
+    name: String,
+    runs: Vec<Vec<usize>>,
+}
+
+/// Reads every polyline (`l` element) out of a Wavefront OBJ file. Faces and everything else are ignored.
+/// Paths are named after the object or group they're in, or `default_name` if they aren't in one
+pub fn import_obj(
+    document: &str,
+    default_name: &str,
+    settings: &ModelImportSettings,
+) -> Result<Vec<ImportedPath>> {
+    let mut vertices: Vec<Vector3d<f64>> = vec![];
+    let mut objects: Vec<ObjObject> = vec![ObjObject {
+        name: default_name.to_string(),
+        runs: vec![],
+    }];
+
+    for (i, line) in document.lines().enumerate() {
+        let line_number = i + 1;
+        let mut parts = line.split_whitespace();
+
+        match parts.next() {
+            Some("v") => {
+                let coordinates = parts
+                    .take(3)
+                    .map(|part| part.parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>()
+                    .with_context(|| format!("Invalid vertex on line {line_number}"))?;
+                let [x, y, z] = coordinates[..] else {
+                    bail!("Vertex on line {line_number} needs 3 coordinates");
+                };
+                vertices.push(Vector3d::new(x, y, z));
+            }
+            Some(element @ ("o" | "g")) => {
+                let name = parts.collect::<Vec<&str>>().join(" ");
+                // Blender writes a `g` inside each `o`, only start a new object if it isn't the same one
+                if element == "g" && objects.last().is_some_and(|object| object.name == name) {
+                    continue;
+                }
+                objects.push(ObjObject {
+                    name: if name.is_empty() {
+                        default_name.to_string()
+                    } else {
+                        name
+                    },
+                    runs: vec![],
+                });
+            }
+            Some("l") => {
+                let run = parts
+                    .map(|part| vertex_index(part, vertices.len()))
+                    .collect::<Result<Vec<usize>>>()
+                    .with_context(|| format!("Invalid line element on line {line_number}"))?;
+                objects
+                    .last_mut()
+                    .expect("There is always at least one object")
+                    .runs
+                    .push(run);
+            }
+            _ => {}
+        }
+    }
+
+    let mut paths: Vec<ImportedPath> = vec![];
+    for object in objects {
+        let polylines = join_polylines(object.runs)
+            .into_iter()
+            .map(|polyline| polyline.into_iter().map(|i| vertices[i]).collect())
+            .collect();
+
+        paths.extend(polylines_to_paths(&object.name, polylines, settings));
+    }
+
+    Ok(paths)
+}
+
+/// Turns an OBJ vertex reference (`3`, `-1` or `3/7`) in to an index in to the vertices read so far.
+/// OBJ counts from 1, and negative numbers count back from the last vertex
+fn vertex_index(reference: &str, vertex_count: usize) -> Result<usize> {
+    let number = reference.split('/').next().unwrap_or_default();
+    let index: i64 = number
+        .parse()
+        .with_context(|| format!("{reference} is not a vertex number"))?;
+
+    let index = match index {
+        1.. => index - 1,
+        ..=-1 => vertex_count as i64 + index,
+        0 => bail!("OBJ vertex numbers start at 1"),
+    };
+
+    if index < 0 || index >= vertex_count as i64 {
+        bail!("Vertex {reference} has not been defined yet");
+    }
+
+    Ok(index as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        import::model::{PolylineShape, UpAxis},
+        path::{Path, PathEnum},
+    };
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_vertex_index() {
+        assert_eq!(vertex_index("1", 3).unwrap(), 0);
+        assert_eq!(vertex_index("3/2", 3).unwrap(), 2);
+        assert_eq!(vertex_index("-1", 3).unwrap(), 2);
+        assert!(vertex_index("4", 3).is_err());
+        assert!(vertex_index("0", 3).is_err());
+    }
+
+    #[test]
+    fn test_import_obj() {
+        let document = "\
+# Blender export
+o Apron
+v 0 0 0
+v 1 0 0
+v 2 0 -1
+l 1 2
+l 2 3
+o Riser
+v 0 1 0
+v 0 2 0
+v 0 2 1
+f 1 2 3
+l -3 -2 -1
+";
+
+        let settings = ModelImportSettings {
+            up_axis: UpAxis::Y,
+            ..Default::default()
+        };
+
+        let paths = import_obj(document, "Model", &settings).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].name, "Apron");
+        assert_eq!(paths[0].segments.len(), 2);
+        assert_eq!(paths[0].segments[1].name(), "Apron 2");
+
+        let PathEnum::Line(line) = &paths[0].segments[1] else {
+            panic!("Polylines should become lines");
+        };
+        // -z in the file is +y here
+        assert_eq!(line.end, Vector3d::new(2.0, 1.0, 0.0));
+
+        let PathEnum::Line(line) = &paths[1].segments[0] else {
+            panic!("Polylines should become lines");
+        };
+        assert_eq!(line.start, Vector3d::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_import_obj_spline() {
+        let document = "v 0 0 0\nv 1 0 0\nv 1 1 0\nl 1 2 3\n";
+
+        let settings = ModelImportSettings {
+            up_axis: UpAxis::Z,
+            shape: PolylineShape::Spline,
+            ..Default::default()
+        };
+
+        let paths = import_obj(document, "Model", &settings).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].name, "Model");
+        let PathEnum::Spline(spline) = &paths[0].segments[0] else {
+            panic!("Polylines should become a spline");
+        };
+        assert_eq!(spline.waypoints.len(), 3);
+    }
+}
@@ -0,0 +1,608 @@
+use std::f64::consts::PI;
+
+use anyhow::{bail, Context, Result};
+use vector3d::Vector3d;
+
+use crate::{
+    import::{segment_name, ImportedPath},
+    path::{bezier::Bezier, cubic_bezier::CubicBezier, line::Line, PathEnum},
+};
+
+/// Which plane the drawing is laid on to in 3D space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SvgPlane {
+    /// A ground plan: the top of the drawing is upstage (+y)
+    Floor,
+    /// A wall facing the audience: the top of the drawing is up (+z)
+    Wall,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgImportSettings {
+    pub plane: SvgPlane,
+    /// Metres per SVG unit
+    pub scale: f64,
+    /// Where the SVG's (0, 0) ends up. This also sets the height of the floor, or how far upstage the wall is
+    pub origin: Vector3d<f64>,
+}
+
+impl Default for SvgImportSettings {
+    fn default() -> Self {
+        Self {
+            plane: SvgPlane::Floor,
+            // Most drawings are in millimetres
+            scale: 0.001,
+            origin: Vector3d::default(),
+        }
+    }
+}
+
+impl SvgImportSettings {
+    /// Takes a point in the SVG's coordinates and places it in 3D space.
+    /// SVG's y axis points down the page, so it's flipped
+    fn place(&self, point: Vector3d<f64>) -> Vector3d<f64> {
+        let across = point.x * self.scale;
+        let up_page = -point.y * self.scale;
+
+        match self.plane {
+            SvgPlane::Floor => self.origin + Vector3d::new(across, up_page, 0.0),
+            SvgPlane::Wall => self.origin + Vector3d::new(across, 0.0, up_page),
+        }
+    }
+}
+
+/// A piece of an SVG path, in the SVG's own 2D coordinates (z is always 0)
+#[derive(Debug, Clone, PartialEq)]
+enum SvgSegment {
+    Line(Vector3d<f64>, Vector3d<f64>),
+    Quadratic(Vector3d<f64>, Vector3d<f64>, Vector3d<f64>),
+    Cubic(Vector3d<f64>, Vector3d<f64>, Vector3d<f64>, Vector3d<f64>),
+}
+
+/// Reads every `<path>` element in an SVG document and turns it in to an `ImportedPath`.
+/// Paths are named after their `id` attribute, or `default_name` and a number if they don't have one
+pub fn import_svg(
+    document: &str,
+    default_name: &str,
+    settings: &SvgImportSettings,
+) -> Result<Vec<ImportedPath>> {
+    let document = roxmltree::Document::parse(document).context("Failed to read SVG file")?;
+
+    let mut paths: Vec<ImportedPath> = vec![];
+    for (i, element) in document
+        .descendants()
+        .filter(|node| node.has_tag_name("path"))
+        .enumerate()
+    {
+        let name = match element.attribute("id") {
+            Some(id) => id.to_string(),
+            None => format!("{default_name} {}", i + 1),
+        };
+
+        let Some(path_data) = element.attribute("d") else {
+            continue;
+        };
+        let svg_segments = parse_path_data(path_data)
+            .with_context(|| format!("Failed to read the path data of {name}"))?;
+
+        let segment_count = svg_segments.len();
+        let segments: Vec<PathEnum> = svg_segments
+            .into_iter()
+            .enumerate()
+            .map(|(i, segment)| to_path(segment, segment_name(&name, i, segment_count), settings))
+            .collect();
+
+        if !segments.is_empty() {
+            paths.push(ImportedPath { name, segments });
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Turns a segment of an SVG path in to a path in 3D space
+fn to_path(segment: SvgSegment, name: String, settings: &SvgImportSettings) -> PathEnum {
+    match segment {
+        SvgSegment::Line(start, end) => PathEnum::Line(Line {
+            name,
+            ..Line::new(settings.place(start), settings.place(end))
+        }),
+        SvgSegment::Quadratic(start, control, end) => PathEnum::Bezier(Bezier {
+            name,
+            ..Bezier::new(
+                settings.place(start),
+                settings.place(control),
+                settings.place(end),
+            )
+        }),
+        SvgSegment::Cubic(start, control_1, control_2, end) => PathEnum::CubicBezier(CubicBezier {
+            name,
+            ..CubicBezier::new(
+                settings.place(start),
+                settings.place(end),
+                settings.place(control_1),
+                settings.place(control_2),
+            )
+        }),
+    }
+}
+
+/// Reads the numbers and commands out of an SVG path's `d` attribute
+struct PathDataReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PathDataReader<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.data.len()
+            && (self.data[self.position].is_ascii_whitespace() || self.data[self.position] == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.data.len()
+    }
+
+    /// Takes the next command letter, if the next thing is a command letter
+    fn command(&mut self) -> Option<char> {
+        self.skip_separators();
+        let next = *self.data.get(self.position)?;
+
+        // e and E are exponents in numbers, not commands
+        if next.is_ascii_alphabetic() && next != b'e' && next != b'E' {
+            self.position += 1;
+            Some(next as char)
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.skip_separators();
+        let start = self.position;
+
+        let mut seen_point = false;
+        let mut seen_exponent = false;
+        while let Some(&next) = self.data.get(self.position) {
+            let is_sign = next == b'+' || next == b'-';
+            let after_exponent =
+                self.position > start && matches!(self.data[self.position - 1], b'e' | b'E');
+
+            let part_of_number = next.is_ascii_digit()
+                || (is_sign && (self.position == start || after_exponent))
+                || (next == b'.' && !seen_point && !seen_exponent)
+                || (matches!(next, b'e' | b'E') && !seen_exponent && self.position > start);
+
+            if !part_of_number {
+                break;
+            }
+
+            seen_point |= next == b'.';
+            seen_exponent |= matches!(next, b'e' | b'E');
+            self.position += 1;
+        }
+
+        let text = std::str::from_utf8(&self.data[start..self.position])?;
+        text.parse::<f64>()
+            .with_context(|| format!("Expected a number at character {start}"))
+    }
+
+    /// Arc flags are a single 0 or 1 and don't need a separator after them
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+        let flag = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => bail!("Expected an arc flag at character {}", self.position),
+        };
+        self.position += 1;
+
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<Vector3d<f64>> {
+        Ok(Vector3d::new(self.number()?, self.number()?, 0.0))
+    }
+}
+
+/// Turns an SVG path's `d` attribute in to a list of segments.
+/// Supports every command: M, L, H, V, Q, T, C, S, A and Z, in both absolute and relative forms
+fn parse_path_data(data: &str) -> Result<Vec<SvgSegment>> {
+    let mut reader = PathDataReader::new(data);
+    let mut segments: Vec<SvgSegment> = vec![];
+
+    let mut current = Vector3d::default();
+    let mut subpath_start = Vector3d::default();
+    // The last control point, used to mirror for the smooth curve commands S and T
+    let mut last_control: Option<(char, Vector3d<f64>)> = None;
+    let mut last_command: Option<char> = None;
+
+    while !reader.at_end() {
+        let command = match reader.command() {
+            Some(command) => command,
+            // Numbers without a command repeat the last one, apart from move which repeats as line
+            None => match last_command {
+                Some('M') => 'L',
+                Some('m') => 'l',
+                Some('Z' | 'z') | None => {
+                    bail!("Expected a command at character {}", reader.position)
+                }
+                Some(command) => command,
+            },
+        };
+
+        let relative = command.is_ascii_lowercase();
+        let origin = if relative {
+            current
+        } else {
+            Vector3d::default()
+        };
+
+        let mut control: Option<(char, Vector3d<f64>)> = None;
+        match command.to_ascii_uppercase() {
+            'M' => {
+                current = origin + reader.point()?;
+                subpath_start = current;
+            }
+            'L' => {
+                let end = origin + reader.point()?;
+                segments.push(SvgSegment::Line(current, end));
+                current = end;
+            }
+            'H' => {
+                let x = reader.number()? + origin.x;
+                let end = Vector3d::new(x, current.y, 0.0);
+                segments.push(SvgSegment::Line(current, end));
+                current = end;
+            }
+            'V' => {
+                let y = reader.number()? + origin.y;
+                let end = Vector3d::new(current.x, y, 0.0);
+                segments.push(SvgSegment::Line(current, end));
+                current = end;
+            }
+            'Q' => {
+                let control_point = origin + reader.point()?;
+                let end = origin + reader.point()?;
+                segments.push(SvgSegment::Quadratic(current, control_point, end));
+                control = Some(('Q', control_point));
+                current = end;
+            }
+            'T' => {
+                let control_point = mirrored_control(last_control, 'Q', current);
+                let end = origin + reader.point()?;
+                segments.push(SvgSegment::Quadratic(current, control_point, end));
+                control = Some(('Q', control_point));
+                current = end;
+            }
+            'C' => {
+                let control_1 = origin + reader.point()?;
+                let control_2 = origin + reader.point()?;
+                let end = origin + reader.point()?;
+                segments.push(SvgSegment::Cubic(current, control_1, control_2, end));
+                control = Some(('C', control_2));
+                current = end;
+            }
+            'S' => {
+                let control_1 = mirrored_control(last_control, 'C', current);
+                let control_2 = origin + reader.point()?;
+                let end = origin + reader.point()?;
+                segments.push(SvgSegment::Cubic(current, control_1, control_2, end));
+                control = Some(('C', control_2));
+                current = end;
+            }
+            'A' => {
+                let radius_x = reader.number()?;
+                let radius_y = reader.number()?;
+                let rotation = reader.number()?;
+                let large_arc = reader.flag()?;
+                let sweep = reader.flag()?;
+                let end = origin + reader.point()?;
+                segments.append(&mut arc_to_cubics(
+                    current, radius_x, radius_y, rotation, large_arc, sweep, end,
+                ));
+                current = end;
+            }
+            'Z' => {
+                if (subpath_start - current).norm2() > 0.0 {
+                    segments.push(SvgSegment::Line(current, subpath_start));
+                }
+                current = subpath_start;
+            }
+            _ => bail!("Unknown path command {command}"),
+        }
+
+        last_control = control;
+        last_command = Some(command);
+    }
+
+    Ok(segments)
+}
+
+/// The smooth curve commands use the last control point mirrored through the current point,
+/// as long as the last command was the same kind of curve. Otherwise they use the current point
+fn mirrored_control(
+    last_control: Option<(char, Vector3d<f64>)>,
+    kind: char,
+    current: Vector3d<f64>,
+) -> Vector3d<f64> {
+    match last_control {
+        Some((last_kind, control)) if last_kind == kind => current * 2.0 - control,
+        _ => current,
+    }
+}
+
+/// Converts an SVG elliptical arc in to cubic Beziers, one for each quarter turn or less.
+/// Follows the SVG spec's conversion from endpoints to a centre: https://www.w3.org/TR/SVG11/implnote.html#ArcImplementationNotes
+fn arc_to_cubics(
+    start: Vector3d<f64>,
+    radius_x: f64,
+    radius_y: f64,
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    end: Vector3d<f64>,
+) -> Vec<SvgSegment> {
+    if (end - start).norm2() == 0.0 {
+        return vec![];
+    }
+
+    let mut radius_x = radius_x.abs();
+    let mut radius_y = radius_y.abs();
+    if radius_x == 0.0 || radius_y == 0.0 {
+        return vec![SvgSegment::Line(start, end)];
+    }
+
+    let (sin_phi, cos_phi) = rotation.to_radians().sin_cos();
+
+    // Move the start point so the middle of the chord is at the origin and the ellipse's axes line up with x and y
+    let half_chord = (start - end) / 2.0;
+    let x1 = cos_phi * half_chord.x + sin_phi * half_chord.y;
+    let y1 = -sin_phi * half_chord.x + cos_phi * half_chord.y;
+
+    // Scale up radii that are too small to reach between the points
+    let lambda = x1.powi(2) / radius_x.powi(2) + y1.powi(2) / radius_y.powi(2);
+    if lambda > 1.0 {
+        radius_x *= lambda.sqrt();
+        radius_y *= lambda.sqrt();
+    }
+
+    let numerator = radius_x.powi(2) * radius_y.powi(2)
+        - radius_x.powi(2) * y1.powi(2)
+        - radius_y.powi(2) * x1.powi(2);
+    let denominator = radius_x.powi(2) * y1.powi(2) + radius_y.powi(2) * x1.powi(2);
+    let sign = if large_arc != sweep { 1.0 } else { -1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+
+    let centre_x1 = coefficient * radius_x * y1 / radius_y;
+    let centre_y1 = -coefficient * radius_y * x1 / radius_x;
+
+    let middle = (start + end) / 2.0;
+    let centre = Vector3d::new(
+        cos_phi * centre_x1 - sin_phi * centre_y1 + middle.x,
+        sin_phi * centre_x1 + cos_phi * centre_y1 + middle.y,
+        0.0,
+    );
+
+    let start_angle = vector_angle(
+        (1.0, 0.0),
+        ((x1 - centre_x1) / radius_x, (y1 - centre_y1) / radius_y),
+    );
+    let mut sweep_angle = vector_angle(
+        ((x1 - centre_x1) / radius_x, (y1 - centre_y1) / radius_y),
+        ((-x1 - centre_x1) / radius_x, (-y1 - centre_y1) / radius_y),
+    );
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * PI;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * PI;
+    }
+
+    // Takes a point on the unit circle and puts it on the ellipse
+    let on_ellipse = |x: f64, y: f64| {
+        Vector3d::new(
+            centre.x + radius_x * cos_phi * x - radius_y * sin_phi * y,
+            centre.y + radius_x * sin_phi * x + radius_y * cos_phi * y,
+            0.0,
+        )
+    };
+
+    // A cubic Bezier can only closely match up to a quarter of a circle
+    let pieces = (sweep_angle.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let piece_angle = sweep_angle / pieces as f64;
+    let handle_length = 4.0 / 3.0 * (piece_angle / 4.0).tan();
+
+    let mut segments: Vec<SvgSegment> = vec![];
+    let mut piece_start = start;
+    for piece in 0..pieces {
+        let angle_1 = start_angle + piece_angle * piece as f64;
+        let angle_2 = angle_1 + piece_angle;
+        let (sin_1, cos_1) = angle_1.sin_cos();
+        let (sin_2, cos_2) = angle_2.sin_cos();
+
+        let control_1 = on_ellipse(cos_1 - handle_length * sin_1, sin_1 + handle_length * cos_1);
+        let control_2 = on_ellipse(cos_2 + handle_length * sin_2, sin_2 - handle_length * cos_2);
+        // Finish exactly on the end point so there's no gap to the next segment
+        let piece_end = if piece == pieces - 1 {
+            end
+        } else {
+            on_ellipse(cos_2, sin_2)
+        };
+
+        segments.push(SvgSegment::Cubic(
+            piece_start,
+            control_1,
+            control_2,
+            piece_end,
+        ));
+        piece_start = piece_end;
+    }
+
+    segments
+}
+
+/// The signed angle (in radians) from vector u to vector v
+fn vector_angle(u: (f64, f64), v: (f64, f64)) -> f64 {
+    (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use percentage::Percentage;
+
+    use crate::path::Path;
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Vector3d<f64> {
+        Vector3d::new(x, y, 0.0)
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let segments = parse_path_data("M 0,0 L 10 0 l0-5 H2 v1 Z").unwrap();
+
+        assert_eq!(
+            segments,
+            vec![
+                SvgSegment::Line(point(0.0, 0.0), point(10.0, 0.0)),
+                SvgSegment::Line(point(10.0, 0.0), point(10.0, -5.0)),
+                SvgSegment::Line(point(10.0, -5.0), point(2.0, -5.0)),
+                SvgSegment::Line(point(2.0, -5.0), point(2.0, -4.0)),
+                SvgSegment::Line(point(2.0, -4.0), point(0.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_implicit_lines_and_compact_numbers() {
+        // After a move, extra pairs of numbers are lines. Numbers can also run together
+        let segments = parse_path_data("m1-1 2.5.5 1e1,0").unwrap();
+
+        assert_eq!(
+            segments,
+            vec![
+                SvgSegment::Line(point(1.0, -1.0), point(3.5, -0.5)),
+                SvgSegment::Line(point(3.5, -0.5), point(13.5, -0.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_curves() {
+        let segments =
+            parse_path_data("M0 0 Q 5 5 10 0 T 20 0 C 20 5 30 5 30 0 s 10 -5 10 0").unwrap();
+
+        assert_eq!(
+            segments,
+            vec![
+                SvgSegment::Quadratic(point(0.0, 0.0), point(5.0, 5.0), point(10.0, 0.0)),
+                SvgSegment::Quadratic(point(10.0, 0.0), point(15.0, -5.0), point(20.0, 0.0)),
+                SvgSegment::Cubic(
+                    point(20.0, 0.0),
+                    point(20.0, 5.0),
+                    point(30.0, 5.0),
+                    point(30.0, 0.0)
+                ),
+                SvgSegment::Cubic(
+                    point(30.0, 0.0),
+                    point(30.0, -5.0),
+                    point(40.0, -5.0),
+                    point(40.0, 0.0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_command() {
+        assert!(parse_path_data("M 0 0 X 1 1").is_err());
+    }
+
+    #[test]
+    fn test_arc_semicircle() {
+        // Half a circle of radius 5 from (0, 0) to (10, 0), going through (5, 5) as SVG's y points down
+        let segments = parse_path_data("M 0 0 A 5 5 0 0 0 10 0").unwrap();
+        assert_eq!(segments.len(), 2);
+
+        let SvgSegment::Cubic(start, control_1, control_2, end) = segments[0].clone() else {
+            panic!("Arcs should become cubic Beziers");
+        };
+        let quarter = CubicBezier::new(start, end, control_1, control_2);
+
+        assert!(eq_vector3d(start, point(0.0, 0.0)));
+        assert!(eq_vector3d(end, point(5.0, 5.0)));
+
+        // Half way along a quarter circle should still be (very nearly) on the circle
+        let middle = quarter.point_at(&Percentage::from_decimal(0.5));
+        let radius = (middle - point(5.0, 0.0)).norm2().sqrt();
+        assert!((radius - 5.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_arc_compact_flags() {
+        let segments = parse_path_data("M0 0a5 5 0 1010 0").unwrap();
+
+        let SvgSegment::Cubic(.., end) = segments.last().unwrap().clone() else {
+            panic!("Arcs should become cubic Beziers");
+        };
+        assert!(eq_vector3d(end, point(10.0, 0.0)));
+    }
+
+    #[test]
+    fn test_import_floor() {
+        let document = r#"<svg xmlns="http://www.w3.org/2000/svg">
+            <g><path id="apron" d="M 0 0 L 1000 -2000"/></g>
+            <path d="M 0 0 L 0 1000 L 1000 1000"/>
+        </svg>"#;
+
+        let settings = SvgImportSettings {
+            plane: SvgPlane::Floor,
+            scale: 0.001,
+            origin: Vector3d::new(1.0, 0.0, 0.5),
+        };
+
+        let paths = import_svg(document, "Track", &settings).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].name, "apron");
+        assert_eq!(paths[1].name, "Track 2");
+        assert_eq!(paths[1].segments.len(), 2);
+        assert_eq!(paths[1].segments[1].name(), "Track 2 2");
+
+        let PathEnum::Line(line) = &paths[0].segments[0] else {
+            panic!("Line commands should become lines");
+        };
+        assert!(eq_vector3d(line.start, Vector3d::new(1.0, 0.0, 0.5)));
+        assert!(eq_vector3d(line.end, Vector3d::new(2.0, 2.0, 0.5)));
+    }
+
+    #[test]
+    fn test_import_wall() {
+        let document = r#"<svg><path d="M 0 0 L 1 -3"/></svg>"#;
+
+        let settings = SvgImportSettings {
+            plane: SvgPlane::Wall,
+            scale: 1.0,
+            origin: Vector3d::new(0.0, 8.0, 0.0),
+        };
+
+        let paths = import_svg(document, "Wall", &settings).unwrap();
+        let PathEnum::Line(line) = &paths[0].segments[0] else {
+            panic!("Line commands should become lines");
+        };
+        assert!(eq_vector3d(line.end, Vector3d::new(1.0, 8.0, 3.0)));
+    }
+}
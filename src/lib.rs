@@ -1,11 +1,61 @@
 #![warn(clippy::all, rust_2018_idioms)]
+//! Positions moving head lights in 3D space on EOS v2 lighting desks.
+//!
+//! The app is built on the modules below, which don't need egui so they can be used from other show tools too:
+//! - [`light`] for where a light is and the pan/tilt it needs to point at a spot
+//! - [`path`] for the shapes lights can follow
+//! - [`move_calculator`] to work out a move along a path, turn it in to EOS commands and send them to a desk
+//! - [`live`] to follow a path live from a fader or encoder, sending pan and tilt straight to the desk
+//! - [`db`] to read and write lights and paths in a project file
+//!
+//! ```
+//! use two_mover_3_pos::{
+//!     light::Light,
+//!     move_calculator::{calculate_move, frames_to_commands, CueSettings, Easing, MoveOptions},
+//!     path::line::Line,
+//!     vector3d::Vector3d,
+//! };
+//!
+//! let light = Light {
+//!     name: "Spot 1".to_string(),
+//!     address: 101,
+//!     coordinates: Vector3d::new(0.0, 0.0, 5.0),
+//!     ..Default::default()
+//! };
+//! let apron = Line::new(Vector3d::new(-2.0, 1.0, 0.0), Vector3d::new(2.0, 1.0, 0.0));
+//!
+//! let options = MoveOptions {
+//!     move_time: 2.0,
+//!     easing: Easing::EaseInOut,
+//!     ..MoveOptions::default()
+//! };
+//! let frames = calculate_move(&apron, &[light], &options)?;
+//! let cues = CueSettings {
+//!     first_cue: 5,
+//!     ..Default::default()
+//! };
+//! let commands = frames_to_commands(&frames, &cues)?;
+//! assert_eq!(commands[2], "Record Cue 1/5.00 Time 0.20");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
 mod app;
+mod cli;
 mod components;
 mod config;
-mod db;
-mod light;
+pub mod db;
+mod import;
+pub mod light;
+pub mod live;
+pub mod move_calculator;
 mod only_one_toggleable_item;
-mod path;
+pub mod path;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub use app::App;
+pub use cli::run_cli;
+
+// The types from these are used all through the public modules, so they're here to save depending on the same versions
+pub use percentage;
+pub use vector3d;
@@ -1,20 +1,74 @@
+//! Lights in the rig, and the pan and tilt maths to point them at things
+
 use isx::prelude::IsDefault;
+use serde::{Deserialize, Serialize};
 use trig::Trig;
 use vector3d::Vector3d;
 
-#[derive(Debug, Default, PartialEq, Clone)]
+/// A moving head light in the rig
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Light {
+    /// The row this light is stored in, 0 if it has not been saved yet
+    pub id: i64,
     pub coordinates: Vector3d<f64>,
     pub minimum_beam: u16,
     pub maximum_beam: u16,
     pub name: String,
     pub address: u16,
+    pub mounting: Mounting,
+    /// Pan value (in degrees) that the fixture reports when it is facing forwards (+y when hung)
+    pub pan_offset: f64,
+    /// Tilt value (in degrees) that the fixture reports when it is pointing straight out of it's base
+    pub tilt_offset: f64,
+    pub pan_inverted: bool,
+    pub tilt_inverted: bool,
+    /// Total pan travel of the fixture in degrees, centred on pan 0
+    pub pan_range: f64,
+    /// Total tilt travel of the fixture in degrees, centred on tilt 0
+    pub tilt_range: f64,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            coordinates: Vector3d::default(),
+            minimum_beam: 0,
+            maximum_beam: 0,
+            name: String::default(),
+            address: 0,
+            mounting: Mounting::default(),
+            pan_offset: 0.0,
+            tilt_offset: 0.0,
+            pan_inverted: false,
+            tilt_inverted: false,
+            // The most common range for a moving head
+            pan_range: 540.0,
+            tilt_range: 270.0,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// How a light is physically mounted in the rig
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Mounting {
+    /// Hanging from a truss or bar, base up and beam pointing down at tilt 0
+    #[default]
+    Hung,
+    /// Standing on the floor, base down and beam pointing up at tilt 0
+    Standing,
+    /// Any other orientation, the rotation (in degrees) around the x, y and z axes that takes a hung light to this one
+    Rotated(Vector3d<f64>),
+}
+
+/// Where a light is pointing
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct LightState {
+    /// In degrees, as the fixture reports it
     pub pan: f64,
+    /// In degrees, as the fixture reports it
     pub tilt: f64,
+    /// The channel of the light this is for
     pub address: u16,
 }
 
@@ -24,22 +78,114 @@ impl IsDefault for Light {
     }
 }
 
+impl Mounting {
+    /// The rotation (in degrees) around the x, y and z axes that takes a hung light to this mounting
+    pub fn rotation(&self) -> Vector3d<f64> {
+        match self {
+            Mounting::Hung => Vector3d::new(0.0, 0.0, 0.0),
+            // Flipped over around the y axis so pan 0 still faces forwards
+            Mounting::Standing => Vector3d::new(0.0, 180.0, 0.0),
+            Mounting::Rotated(rotation) => *rotation,
+        }
+    }
+
+    /// The name used to store this mounting in the database
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Mounting::Hung => "Hung",
+            Mounting::Standing => "Standing",
+            Mounting::Rotated(_) => "Rotated",
+        }
+    }
+
+    /// Rebuilds a mounting from the values stored in the database. Unknown kinds are treated as hung
+    pub fn from_kind(kind: &str, rotation: Vector3d<f64>) -> Self {
+        match kind {
+            "Standing" => Mounting::Standing,
+            "Rotated" => Mounting::Rotated(rotation),
+            _ => Mounting::Hung,
+        }
+    }
+
+    /// Takes a direction in world space and returns it relative to the light's base, as if the light were hung
+    fn to_fixture_space(self, direction: Vector3d<f64>) -> Vector3d<f64> {
+        // The mounting rotates around x, then y, then z so we undo it in the opposite order
+        let rotation = self.rotation();
+        let direction = rotate_z(direction, -rotation.z);
+        let direction = rotate_y(direction, -rotation.y);
+        rotate_x(direction, -rotation.x)
+    }
+
+    /// The opposite of `to_fixture_space`, takes a direction relative to the light's base back to world space
+    fn to_world_space(self, direction: Vector3d<f64>) -> Vector3d<f64> {
+        let rotation = self.rotation();
+        let direction = rotate_x(direction, rotation.x);
+        let direction = rotate_y(direction, rotation.y);
+        rotate_z(direction, rotation.z)
+    }
+}
+
+fn rotate_x(vector: Vector3d<f64>, degrees: f64) -> Vector3d<f64> {
+    let (sin, cos) = (degrees.sind(), degrees.cosd());
+    Vector3d::new(
+        vector.x,
+        vector.y * cos - vector.z * sin,
+        vector.y * sin + vector.z * cos,
+    )
+}
+
+fn rotate_y(vector: Vector3d<f64>, degrees: f64) -> Vector3d<f64> {
+    let (sin, cos) = (degrees.sind(), degrees.cosd());
+    Vector3d::new(
+        vector.x * cos + vector.z * sin,
+        vector.y,
+        -vector.x * sin + vector.z * cos,
+    )
+}
+
+fn rotate_z(vector: Vector3d<f64>, degrees: f64) -> Vector3d<f64> {
+    let (sin, cos) = (degrees.sind(), degrees.cosd());
+    Vector3d::new(
+        vector.x * cos - vector.y * sin,
+        vector.x * sin + vector.y * cos,
+        vector.z,
+    )
+}
+
+/// Wraps an angle (in degrees) in to the range -180 < angle <= 180
+fn wrap_degrees(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(360.0);
+    if wrapped > 180.0 {
+        wrapped - 360.0
+    } else {
+        wrapped
+    }
+}
+
 impl Light {
     /// Calculates the required pan and tilt for this light to point at the specified point in space
     /// coordinates in form (x,y,z) with z being up, x right and y forward
     pub fn point_at(&self, coordinate: Vector3d<f64>) -> LightState {
         // Desmos link: https://www.desmos.com/3d/ez2rjf9ahn (We're working with y up, this is working with z up fyi)
 
-        // Calculate distances between two points in x,y and z as well as the "as the crow flies distance" that we'll call distance_straight
-        let distance_x = coordinate.x - self.coordinates.x;
-        let distance_y = coordinate.y - self.coordinates.y;
-        let distance_z = coordinate.z - self.coordinates.z;
-        let distance_straight = (distance_x.powi(2) + distance_y.powi(2)).sqrt();
+        // Work out where the point is relative to the light's base, this lets us do the rest of the maths as if the light was hung
+        let direction = self
+            .mounting
+            .to_fixture_space(coordinate - self.coordinates);
+
+        // The "as the crow flies" distance across the light's base that we'll call distance_straight
+        let distance_straight = (direction.x.powi(2) + direction.y.powi(2)).sqrt();
 
         // Now some trig with those values to calculate the pan and tilt (in degrees)
-        // Adding f64::MIN_POSITIVE to stop divide by zero errors
-        let pan = (coordinate.x - self.coordinates.x).atan2d(&(coordinate.y - self.coordinates.y));
-        let tilt = -((distance_straight / distance_z + f64::MIN_POSITIVE).atand()); // * -1 as it is always -ve
+        // Tilt is measured from straight down, so a point directly below is 0 and one level with the light is 90
+        let pan = direction.x.atan2d(&direction.y);
+        let tilt = distance_straight.atan2d(&-direction.z);
+
+        // Then flip the direction if the fixture moves the other way and move the zero points to where the fixture has them
+        let pan = if self.pan_inverted { -pan } else { pan };
+        let tilt = if self.tilt_inverted { -tilt } else { tilt };
+        let pan = wrap_degrees(pan + self.pan_offset);
+        let tilt = tilt + self.tilt_offset;
 
         LightState {
             pan,
@@ -47,9 +193,95 @@ impl Light {
             address: self.address,
         }
     }
+
+    /// Like `point_at` but picks whichever pan/tilt solution inside the light's range is the shortest move from `previous`.
+    /// With no previous state the solution `point_at` gives is preferred. If no solution fits in the range then the unlimited closest one is used
+    pub fn point_at_closest(
+        &self,
+        coordinate: Vector3d<f64>,
+        previous: Option<&LightState>,
+    ) -> LightState {
+        let unlimited = self.point_at(coordinate);
+        let target = previous.unwrap_or(&unlimited);
+
+        let candidates = self.equivalent_states(&unlimited);
+        let in_range: Vec<&LightState> = candidates
+            .iter()
+            .filter(|state| self.in_range(state))
+            .collect();
+
+        let closest = if in_range.is_empty() {
+            closest_state(candidates.iter(), target)
+        } else {
+            closest_state(in_range.into_iter(), target)
+        };
+
+        closest.unwrap_or(unlimited)
+    }
+
+    /// Which way the beam goes (as a unit vector in world space) when the light is set to `state`.
+    /// The opposite of `point_at`, so it can be used to check a move really points where it should
+    pub fn beam_direction(&self, state: &LightState) -> Vector3d<f64> {
+        // Undo the fixture's zero points and directions to get back to pan and tilt as if it was hung
+        let pan = state.pan - self.pan_offset;
+        let tilt = state.tilt - self.tilt_offset;
+        let pan = if self.pan_inverted { -pan } else { pan };
+        let tilt = if self.tilt_inverted { -tilt } else { tilt };
+
+        // Tilt is measured from straight down and pan from +y, see `point_at`
+        let direction = Vector3d::new(
+            tilt.sind() * pan.sind(),
+            tilt.sind() * pan.cosd(),
+            -tilt.cosd(),
+        );
+        self.mounting.to_world_space(direction)
+    }
+
+    /// Checks if a state is inside the light's pan and tilt range
+    pub fn in_range(&self, state: &LightState) -> bool {
+        state.pan.abs() <= self.pan_range / 2.0 && state.tilt.abs() <= self.tilt_range / 2.0
+    }
+
+    /// Every pan/tilt pair that points the light in the same direction as `state`:
+    /// pan plus any multiple of 360, or pan flipped round by 180 with the tilt mirrored through it's zero point
+    fn equivalent_states(&self, state: &LightState) -> Vec<LightState> {
+        // Enough whole turns either way to cover the pan range
+        let turns = (self.pan_range / 360.0).ceil().max(0.0) as i32 + 1;
+
+        let mut states = vec![];
+        for turn in -turns..=turns {
+            let pan = state.pan + 360.0 * turn as f64;
+            states.push(LightState {
+                pan,
+                tilt: state.tilt,
+                address: state.address,
+            });
+            states.push(LightState {
+                pan: pan + 180.0,
+                tilt: 2.0 * self.tilt_offset - state.tilt,
+                address: state.address,
+            });
+        }
+
+        states
+    }
+}
+
+/// Finds the state that needs the least combined pan and tilt movement to get to from `target`
+fn closest_state<'a>(
+    states: impl Iterator<Item = &'a LightState>,
+    target: &LightState,
+) -> Option<LightState> {
+    let distance =
+        |state: &LightState| (state.pan - target.pan).abs() + (state.tilt - target.tilt).abs();
+
+    states
+        .min_by(|state_1, state_2| distance(state_1).total_cmp(&distance(state_2)))
+        .cloned()
 }
 
 impl LightState {
+    /// The EOS commands that point the light this way
     pub fn to_commands(&self) -> Vec<String> {
         vec![
             format!("{} Pan {:.4}", self.address, self.pan),
@@ -60,15 +292,11 @@ impl LightState {
 
 #[cfg(test)]
 mod tests {
+    use crate::test_util::float_eq;
+
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
 
-    /// Checks if two floating point values are equal, leaving some wiggle room for floating point error
-    fn float_eq(value_1: f64, value_2: f64) -> bool {
-        let diff = value_1 - value_2;
-        diff < 0.000001 && diff > -0.000001
-    }
-
     fn eq_light_state(state_1: LightState, state_2: LightState) -> bool {
         let address_eq = state_1.address == state_2.address;
         let pan_eq = float_eq(state_1.pan, state_2.pan);
@@ -89,6 +317,7 @@ mod tests {
             maximum_beam: 0,
             name: "N/A".to_string(),
             address: 5,
+            ..Default::default()
         };
 
         let point = Vector3d {
@@ -118,6 +347,7 @@ mod tests {
             maximum_beam: 0,
             name: "N/A".to_string(),
             address: 5,
+            ..Default::default()
         };
 
         let point = Vector3d {
@@ -147,6 +377,7 @@ mod tests {
             maximum_beam: 0,
             name: "N/A".to_string(),
             address: 5,
+            ..Default::default()
         };
 
         let point = Vector3d {
@@ -176,6 +407,7 @@ mod tests {
             maximum_beam: 0,
             name: "N/A".to_string(),
             address: 5,
+            ..Default::default()
         };
 
         let point = Vector3d {
@@ -192,4 +424,292 @@ mod tests {
 
         assert!(eq_light_state(light.point_at(point), out_light_state));
     }
+
+    #[test]
+    fn test_light_point_at_standing() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            address: 5,
+            mounting: Mounting::Standing,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 1.0,
+            y: 0.0,
+            z: 1.0,
+        };
+
+        let out_light_state = LightState {
+            pan: -90.0,
+            tilt: 45.0,
+            address: 5,
+        };
+
+        assert!(eq_light_state(light.point_at(point), out_light_state));
+    }
+
+    #[test]
+    fn test_light_point_at_side_mounted() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            address: 5,
+            mounting: Mounting::Rotated(Vector3d {
+                x: 0.0,
+                y: 90.0,
+                z: 0.0,
+            }),
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: -1.0,
+            y: 1.0,
+            z: 0.0,
+        };
+
+        let out_light_state = LightState {
+            pan: 0.0,
+            tilt: 45.0,
+            address: 5,
+        };
+
+        assert!(eq_light_state(light.point_at(point), out_light_state));
+    }
+
+    #[test]
+    fn test_light_point_at_offsets() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 6.0,
+                y: 4.0,
+                z: 1.0,
+            },
+            address: 5,
+            pan_offset: 90.0,
+            tilt_offset: 10.0,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 7.0,
+            y: 4.0,
+            z: 0.0,
+        };
+
+        let out_light_state = LightState {
+            pan: 180.0,
+            tilt: 55.0,
+            address: 5,
+        };
+
+        assert!(eq_light_state(light.point_at(point), out_light_state));
+    }
+
+    #[test]
+    fn test_light_point_at_inverted() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 6.0,
+                y: 4.0,
+                z: 1.0,
+            },
+            address: 5,
+            pan_offset: 10.0,
+            pan_inverted: true,
+            tilt_inverted: true,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 7.0,
+            y: 4.0,
+            z: 0.0,
+        };
+
+        let out_light_state = LightState {
+            pan: -80.0,
+            tilt: -45.0,
+            address: 5,
+        };
+
+        assert!(eq_light_state(light.point_at(point), out_light_state));
+    }
+
+    #[test]
+    fn test_light_beam_direction() {
+        let target = Vector3d::new(3.0, -2.0, 0.5);
+        let lights = [
+            Light {
+                coordinates: Vector3d::new(1.0, 2.0, 6.0),
+                pan_offset: 30.0,
+                tilt_offset: -10.0,
+                ..Default::default()
+            },
+            Light {
+                coordinates: Vector3d::new(-1.0, 0.0, 0.0),
+                mounting: Mounting::Standing,
+                pan_inverted: true,
+                ..Default::default()
+            },
+            Light {
+                coordinates: Vector3d::new(0.0, 5.0, 3.0),
+                mounting: Mounting::Rotated(Vector3d::new(90.0, 0.0, 45.0)),
+                tilt_inverted: true,
+                ..Default::default()
+            },
+        ];
+
+        for light in lights {
+            let state = light.point_at_closest(target, None);
+            let direction = light.beam_direction(&state);
+
+            let expected = target - light.coordinates;
+            let expected = expected / expected.norm2().sqrt();
+            assert!(
+                float_eq(direction.x, expected.x),
+                "{direction:?} {expected:?}"
+            );
+            assert!(
+                float_eq(direction.y, expected.y),
+                "{direction:?} {expected:?}"
+            );
+            assert!(
+                float_eq(direction.z, expected.z),
+                "{direction:?} {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_light_point_at_closest_no_previous() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 6.0,
+                y: 4.0,
+                z: 1.0,
+            },
+            address: 5,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 5.0,
+            y: 3.0,
+            z: 0.0,
+        };
+
+        assert!(eq_light_state(
+            light.point_at_closest(point, None),
+            light.point_at(point)
+        ));
+    }
+
+    #[test]
+    fn test_light_point_at_closest_wraps_pan() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            address: 5,
+            ..Default::default()
+        };
+
+        // Just past the point where atan2 jumps from +180 to -180
+        let point = Vector3d {
+            x: -0.1,
+            y: -1.0,
+            z: 0.0,
+        };
+
+        let previous = LightState {
+            pan: 179.0,
+            tilt: 45.0,
+            address: 5,
+        };
+
+        let closest = light.point_at_closest(point, Some(&previous));
+        assert!(float_eq(closest.pan, light.point_at(point).pan + 360.0));
+        assert!(float_eq(closest.tilt, light.point_at(point).tilt));
+    }
+
+    #[test]
+    fn test_light_point_at_closest_flips() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            address: 5,
+            pan_range: 360.0,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 0.0,
+            y: -1.0,
+            z: 0.0,
+        };
+
+        // Sat at the edge of the pan range, so the only short move is to flip the tilt over
+        let previous = LightState {
+            pan: 0.0,
+            tilt: -40.0,
+            address: 5,
+        };
+
+        let out_light_state = LightState {
+            pan: 0.0,
+            tilt: -45.0,
+            address: 5,
+        };
+
+        assert!(eq_light_state(
+            light.point_at_closest(point, Some(&previous)),
+            out_light_state
+        ));
+    }
+
+    #[test]
+    fn test_light_point_at_closest_stays_in_range() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            address: 5,
+            pan_range: 540.0,
+            ..Default::default()
+        };
+
+        let point = Vector3d {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        // Near the end of the pan range, so carrying on round to pan 450 isn't possible
+        let previous = LightState {
+            pan: 265.0,
+            tilt: 40.0,
+            address: 5,
+        };
+
+        let closest = light.point_at_closest(point, Some(&previous));
+        assert!(light.in_range(&closest));
+        assert!(float_eq(closest.pan, 270.0));
+        assert!(float_eq(closest.tilt, -45.0));
+    }
 }
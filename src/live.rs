@@ -0,0 +1,528 @@
+//! Points lights at a position along a path as it changes, for busking a follow spot along a track without recording cues.
+//! The position can come from anywhere, including an OSC or MIDI controller using [`OscControl`] and [`MidiControl`]
+
+use std::{
+    fmt, io,
+    net::UdpSocket,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use eosc_rs::eos_desk::EosDesk;
+#[cfg(feature = "midi")]
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use percentage::Percentage;
+use rosc::{OscPacket, OscType};
+
+use crate::{
+    light::{Light, LightState},
+    move_calculator::{DeskConnection, MoveError, OutputError},
+    path::{arc_length::ArcLengthTable, Path},
+};
+
+/// Works out the commands to point lights at a position along a path, leaving out any that haven't changed since last time
+pub struct LiveTracker<P: Path> {
+    path: P,
+    lights: Vec<Light>,
+    /// Only there if positions are spaced evenly by distance along the path
+    arc_length_table: Option<Arc<ArcLengthTable>>,
+    /// Where each light was last pointed, so it takes the shortest route to the next position
+    states: Vec<Option<LightState>>,
+    /// The commands last sent for each light, in the same order as `LightState::to_commands`
+    sent: Vec<Vec<String>>,
+}
+
+impl<P: Path> LiveTracker<P> {
+    /// constant_speed measures positions by distance along the path, rather than by the path's own percentages
+    pub fn new(path: P, lights: Vec<Light>, constant_speed: bool) -> Result<Self, MoveError> {
+        if lights.is_empty() {
+            return Err(MoveError::NoLights);
+        }
+
+        Ok(Self {
+            arc_length_table: constant_speed.then(|| path.arc_length_table()),
+            path,
+            states: vec![None; lights.len()],
+            sent: vec![vec![]; lights.len()],
+            lights,
+        })
+    }
+
+    /// The commands that point the lights `fraction` of the way along the path (0 to 1).
+    /// Only pans and tilts that have changed since the last call are included
+    pub fn move_to(&mut self, fraction: f64) -> Vec<String> {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let point = match &self.arc_length_table {
+            Some(table) => self.path.point_at(&table.percentage_at_fraction(fraction)),
+            None => self.path.point_at(&Percentage::from_decimal(fraction)),
+        };
+
+        let mut commands = vec![];
+        for (i, light) in self.lights.iter().enumerate() {
+            let state = light.point_at_closest(point, self.states[i].as_ref());
+            let light_commands = state.to_commands();
+
+            // Compared after formatting so changes too small for the desk to see aren't sent
+            for (j, command) in light_commands.iter().enumerate() {
+                if self.sent[i].get(j) != Some(command) {
+                    commands.push(command.clone());
+                }
+            }
+
+            self.states[i] = Some(state);
+            self.sent[i] = light_commands;
+        }
+
+        commands
+    }
+
+    /// Sends every pan and tilt next time, even if they haven't changed. Useful if the desk may have missed some
+    pub fn resend_all(&mut self) {
+        for sent in &mut self.sent {
+            sent.clear();
+        }
+    }
+}
+
+/// Keeps updates to the desk below a rate, so a fast moving fader doesn't flood it
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    interval: Duration,
+    last_update: Option<Instant>,
+}
+
+impl Throttle {
+    pub fn new(updates_per_second: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(updates_per_second.max(1.0).recip()),
+            last_update: None,
+        }
+    }
+
+    /// How long until the next update can go, zero if it can go now
+    pub fn remaining(&self, now: Instant) -> Duration {
+        match self.last_update {
+            Some(last_update) => (last_update + self.interval).saturating_duration_since(now),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Remembers that an update has just been sent
+    pub fn updated(&mut self, now: Instant) {
+        self.last_update = Some(now);
+    }
+}
+
+/// A connection to the desk that's kept open while tracking, so commands go straight out
+pub struct LiveOutput {
+    desk: EosDesk,
+}
+
+impl LiveOutput {
+    pub fn connect(connection: &DeskConnection) -> Result<Self, OutputError> {
+        let desk = EosDesk::new(
+            (connection.host.ip(), connection.host.port()),
+            (connection.desk.ip(), connection.desk.port()),
+        )
+        .map_err(|e| OutputError::Connect(Box::new(e)))?;
+
+        Ok(Self { desk })
+    }
+
+    /// Sends the commands over UDP, which doesn't wait for the desk so it's fine to call from the UI
+    pub fn send(&self, commands: &[String]) -> Result<(), OutputError> {
+        for command in commands {
+            self.desk
+                .command(command)
+                .map_err(|source| OutputError::Send {
+                    command: command.clone(),
+                    source: Box::new(source),
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// How often the listening threads check if they've been stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Listens for positions sent over OSC, for controllers like TouchOSC or the desk's own encoders.
+/// Messages to `address` with a float argument from 0 to 1, or an int from 0 to 100 (%), are sent on as fractions along the path.
+/// Stops listening when dropped
+pub struct OscControl {
+    stop: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl OscControl {
+    pub fn listen(port: u16, address: String, positions: mpsc::Sender<f64>) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker_stop = Arc::clone(&stop);
+        let worker = thread::spawn(move || {
+            let mut buffer = [0; rosc::decoder::MTU];
+            while !worker_stop.load(Ordering::Relaxed) {
+                // Timing out just means nothing was sent, so go round and check if it's time to stop
+                let Ok(length) = socket.recv(&mut buffer) else {
+                    continue;
+                };
+                let Ok((_, packet)) = rosc::decoder::decode_udp(&buffer[..length]) else {
+                    log::warn!("Ignoring a live control packet that isn't OSC");
+                    continue;
+                };
+
+                for position in osc_positions(packet, &address) {
+                    if positions.send(position).is_err() {
+                        // Nobody is listening any more
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            stop,
+            worker: Some(worker),
+        })
+    }
+}
+
+impl Drop for OscControl {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The positions (0 to 1) in any messages to `address`, looking inside bundles too
+fn osc_positions(packet: OscPacket, address: &str) -> Vec<f64> {
+    match packet {
+        OscPacket::Message(message) if message.addr == address => message
+            .args
+            .first()
+            .and_then(|argument| match argument {
+                OscType::Float(value) => Some(*value as f64),
+                OscType::Double(value) => Some(*value),
+                OscType::Int(value) => Some(*value as f64 / 100.0),
+                _ => None,
+            })
+            .map(|position| position.clamp(0.0, 1.0))
+            .into_iter()
+            .collect(),
+        OscPacket::Message(_) => vec![],
+        OscPacket::Bundle(bundle) => bundle
+            .content
+            .into_iter()
+            .flat_map(|packet| osc_positions(packet, address))
+            .collect(),
+    }
+}
+
+/// What this app is called in the system's list of MIDI clients
+#[cfg(feature = "midi")]
+const MIDI_CLIENT_NAME: &str = "Two Mover 3 Pos";
+
+/// Why a MIDI input couldn't be listened to
+#[derive(Debug)]
+pub enum MidiError {
+    /// The system's MIDI couldn't be used, e.g. because there's no MIDI support installed
+    #[cfg(feature = "midi")]
+    Init(midir::InitError),
+    /// There's no input port with this name, or no ports at all if the name is empty
+    NoPort(String),
+    /// The port was found but couldn't be opened
+    #[cfg(feature = "midi")]
+    Connect(midir::ConnectErrorKind),
+    /// The app was built without the `midi` feature
+    Unsupported,
+}
+
+impl fmt::Display for MidiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "midi")]
+            MidiError::Init(e) => write!(f, "{e}"),
+            MidiError::NoPort(name) if name.is_empty() => write!(f, "There are no MIDI inputs"),
+            MidiError::NoPort(name) => write!(f, "There's no MIDI input called {name}"),
+            #[cfg(feature = "midi")]
+            MidiError::Connect(e) => write!(f, "{e}"),
+            MidiError::Unsupported => write!(f, "This copy of the app was built without MIDI"),
+        }
+    }
+}
+
+impl std::error::Error for MidiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "midi")]
+            MidiError::Init(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The names of the MIDI inputs that can be listened to, for picking one in the preferences
+#[cfg(feature = "midi")]
+pub fn midi_ports() -> Vec<String> {
+    let Ok(input) = MidiInput::new(MIDI_CLIENT_NAME) else {
+        return vec![];
+    };
+
+    input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect()
+}
+
+/// There are never any MIDI inputs without the `midi` feature
+#[cfg(not(feature = "midi"))]
+pub fn midi_ports() -> Vec<String> {
+    vec![]
+}
+
+/// Listens for a MIDI control change, like a fader or encoder, on the MIDI input called `port_name` (or the first one if it's empty).
+/// The value (0 to 127) of `controller` on `channel` (1 to 16) is sent on as a fraction along the path.
+/// Stops listening when dropped, which closes the input
+pub struct MidiControl {
+    #[cfg(feature = "midi")]
+    _connection: MidiInputConnection<()>,
+}
+
+#[cfg(feature = "midi")]
+impl MidiControl {
+    pub fn listen(
+        port_name: &str,
+        channel: u8,
+        controller: u8,
+        positions: mpsc::Sender<f64>,
+    ) -> Result<Self, MidiError> {
+        let mut input = MidiInput::new(MIDI_CLIENT_NAME).map_err(MidiError::Init)?;
+        // Only control changes are wanted, so don't bother passing on clock ticks and the like
+        input.ignore(Ignore::All);
+
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| {
+                port_name.is_empty() || input.port_name(port).is_ok_and(|name| name == port_name)
+            })
+            .ok_or_else(|| MidiError::NoPort(port_name.to_string()))?;
+
+        let connection = input
+            .connect(
+                &port,
+                MIDI_CLIENT_NAME,
+                move |_, message, _| {
+                    let Some(change) = control_change(message) else {
+                        return;
+                    };
+                    if change.channel == channel && change.controller == controller {
+                        // Nobody is listening any more if this fails, and the connection is about to be dropped
+                        let _ = positions.send(change.value as f64 / 127.0);
+                    }
+                },
+                (),
+            )
+            .map_err(|e| MidiError::Connect(e.kind()))?;
+
+        Ok(Self {
+            _connection: connection,
+        })
+    }
+}
+
+#[cfg(not(feature = "midi"))]
+impl MidiControl {
+    pub fn listen(
+        _port_name: &str,
+        _channel: u8,
+        _controller: u8,
+        _positions: mpsc::Sender<f64>,
+    ) -> Result<Self, MidiError> {
+        Err(MidiError::Unsupported)
+    }
+}
+
+/// A MIDI control change message
+#[cfg(feature = "midi")]
+#[derive(Debug, PartialEq)]
+struct ControlChange {
+    /// 1 to 16, like it's shown on controllers
+    channel: u8,
+    controller: u8,
+    value: u8,
+}
+
+/// The control change in a whole MIDI message, if it is one
+#[cfg(feature = "midi")]
+fn control_change(message: &[u8]) -> Option<ControlChange> {
+    match *message {
+        [status, controller, value] if status & 0xF0 == 0xB0 => Some(ControlChange {
+            channel: (status & 0x0F) + 1,
+            controller,
+            value,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rosc::{OscBundle, OscMessage, OscTime};
+    use vector3d::Vector3d;
+
+    use crate::path::line::Line;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn tracker() -> LiveTracker<Line> {
+        let light = Light {
+            coordinates: Vector3d::new(0.0, 0.0, 5.0),
+            address: 101,
+            ..Default::default()
+        };
+        let path = Line::new(Vector3d::new(-2.0, 1.0, 0.0), Vector3d::new(2.0, 1.0, 0.0));
+        LiveTracker::new(path, vec![light], true).unwrap()
+    }
+
+    #[test]
+    fn test_tracker_only_sends_changes() {
+        let mut tracker = tracker();
+
+        let first = tracker.move_to(0.5);
+        assert_eq!(first.len(), 2);
+        assert!(first[0].starts_with("101 Pan"));
+        assert!(first[1].starts_with("101 Tilt"));
+
+        assert!(tracker.move_to(0.5).is_empty());
+
+        tracker.resend_all();
+        assert_eq!(tracker.move_to(0.5), first);
+    }
+
+    #[test]
+    fn test_tracker_sends_moves() {
+        let mut tracker = tracker();
+        tracker.move_to(0.0);
+
+        // Moving along the line changes both pan and tilt for a light above the middle of it
+        let commands = tracker.move_to(0.25);
+        assert_eq!(commands.len(), 2);
+        assert_ne!(commands, tracker.move_to(1.0));
+    }
+
+    #[test]
+    fn test_tracker_needs_lights() {
+        let path = Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0));
+        assert!(matches!(
+            LiveTracker::new(path, vec![], false),
+            Err(MoveError::NoLights)
+        ));
+    }
+
+    #[test]
+    fn test_throttle() {
+        let mut throttle = Throttle::new(10.0);
+        let start = Instant::now();
+        assert_eq!(throttle.remaining(start), Duration::ZERO);
+
+        throttle.updated(start);
+        assert_eq!(throttle.remaining(start), Duration::from_millis(100));
+        assert_eq!(
+            throttle.remaining(start + Duration::from_millis(60)),
+            Duration::from_millis(40)
+        );
+        assert_eq!(
+            throttle.remaining(start + Duration::from_millis(150)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_osc_positions() {
+        let message = |addr: &str, argument| {
+            OscPacket::Message(OscMessage {
+                addr: addr.to_string(),
+                args: vec![argument],
+            })
+        };
+
+        assert_eq!(
+            osc_positions(message("/position", OscType::Float(0.25)), "/position"),
+            vec![0.25]
+        );
+        assert_eq!(
+            osc_positions(message("/position", OscType::Int(50)), "/position"),
+            vec![0.5]
+        );
+        assert_eq!(
+            osc_positions(message("/position", OscType::Double(3.0)), "/position"),
+            vec![1.0]
+        );
+        assert!(osc_positions(message("/other", OscType::Float(0.25)), "/position").is_empty());
+
+        let bundle = OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![
+                message("/position", OscType::Float(0.1)),
+                message("/other", OscType::Float(0.2)),
+                message("/position", OscType::String("0.3".to_string())),
+                message("/position", OscType::Float(0.4)),
+            ],
+        });
+        assert_eq!(
+            osc_positions(bundle, "/position"),
+            vec![0.1f32 as f64, 0.4f32 as f64]
+        );
+    }
+
+    #[test]
+    fn test_osc_control_listens() {
+        let port = UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let (sender, receiver) = mpsc::channel();
+        let _control = OscControl::listen(port, "/position".to_string(), sender).unwrap();
+
+        let packet = rosc::encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/position".to_string(),
+            args: vec![OscType::Float(0.75)],
+        }))
+        .unwrap();
+        UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .send_to(&packet, ("127.0.0.1", port))
+            .unwrap();
+
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(0.75));
+    }
+
+    #[cfg(feature = "midi")]
+    #[test]
+    fn test_control_change() {
+        assert_eq!(
+            control_change(&[0xB1, 7, 100]),
+            Some(ControlChange {
+                channel: 2,
+                controller: 7,
+                value: 100
+            })
+        );
+
+        // Notes and program changes aren't control changes
+        assert_eq!(control_change(&[0x90, 60, 127]), None);
+        assert_eq!(control_change(&[0xC0, 5]), None);
+    }
+}
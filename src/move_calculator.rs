@@ -0,0 +1,1390 @@
+//! Works out where lights need to point as they follow a path, and turns that in to cues for an EOS desk
+
+use std::{
+    fmt,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use eosc_rs::eos_desk::EosDesk;
+use percentage::Percentage;
+use serde::Serialize;
+use vector3d::Vector3d;
+
+use crate::{
+    light::{Light, LightState},
+    path::Path,
+};
+
+/// Where every light is pointing at one point during a move
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Frame {
+    /// How long (in seconds) it takes to get to this frame from the last one
+    pub delay: f64,
+    /// One for each light, in the same order the lights were given in
+    pub light_states: Vec<LightState>,
+}
+
+/// Why a move couldn't be worked out
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveError {
+    /// There has to be at least one light to move
+    NoLights,
+    /// There has to be at least one frame in the move
+    NoFrames,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoLights => write!(f, "A move needs at least one light"),
+            MoveError::NoFrames => write!(f, "A move needs at least one frame"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Where each light is along the path compared to the others
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Spread {
+    /// Every light points at the same spot
+    #[default]
+    Together,
+    /// A wave, where each light trails the one before it by this fraction of the path (0 to 1)
+    Phase(f64),
+    /// A wave, where each light trails the one before it by this distance (in metres) along the path
+    PhaseDistance(f64),
+    /// The lights are spread evenly along the whole path the entire time, each one covering it's own part of it
+    Fan,
+}
+
+impl Spread {
+    /// How far along the path (0 to 1) each of `lights` is, `progress` (0 to 1) of the way through the move.
+    /// On a closed path lights trailing behind the start carry on from the end, on an open path they wait at the start
+    pub fn fractions(self, progress: f64, lights: usize, length: f64, closed: bool) -> Vec<f64> {
+        let trail = match self {
+            Spread::Together => 0.0,
+            Spread::Phase(fraction) => fraction,
+            Spread::PhaseDistance(distance) if length > 0.0 => distance / length,
+            Spread::PhaseDistance(_) => 0.0,
+            Spread::Fan => {
+                return (0..lights)
+                    .map(|light| (progress + light as f64) / lights as f64)
+                    .collect()
+            }
+        };
+
+        (0..lights)
+            .map(|light| {
+                let fraction = progress - trail * light as f64;
+                if closed {
+                    fraction.rem_euclid(1.0)
+                } else {
+                    fraction.clamp(0.0, 1.0)
+                }
+            })
+            .collect()
+    }
+}
+
+/// How far along the path the lights are as the move goes on, so they can speed up and slow down rather than moving at one speed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum Easing {
+    /// The same speed all the way through
+    #[default]
+    Linear,
+    /// Starts slowly and speeds up to the end
+    EaseIn,
+    /// Starts at full speed and slows down to the end
+    EaseOut,
+    /// Speeds up to the middle then slows down again
+    EaseInOut,
+    /// Like `EaseInOut`, but the speed also changes gently at the ends so there's no jolt when it starts or stops
+    SCurve,
+    /// Goes in straight lines between the keyframes, from the start of the path at the start of the move to the end of it at the end
+    Custom(Vec<Keyframe>),
+}
+
+/// A point on a custom easing curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    /// How far through the move (0 to 1)
+    pub time: f64,
+    /// How far along the path (0 to 1)
+    pub position: f64,
+}
+
+impl Easing {
+    /// How far along the path (0 to 1) the lights are `time` (0 to 1) of the way through the move
+    pub fn position_at(&self, time: f64) -> f64 {
+        let t = time.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Easing::EaseInOut => 1.0 - 2.0 * (1.0 - t) * (1.0 - t),
+            Easing::SCurve => t * t * t * (t * (t * 6.0 - 15.0) + 10.0),
+            Easing::Custom(keyframes) => {
+                let mut keyframes = keyframes.clone();
+                keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+                let mut previous = Keyframe {
+                    time: 0.0,
+                    position: 0.0,
+                };
+                for keyframe in keyframes.into_iter().chain([Keyframe {
+                    time: 1.0,
+                    position: 1.0,
+                }]) {
+                    if t <= keyframe.time {
+                        // Keyframes at the same time jump straight to the last one
+                        if keyframe.time <= previous.time {
+                            return keyframe.position.clamp(0.0, 1.0);
+                        }
+                        let between = (t - previous.time) / (keyframe.time - previous.time);
+                        let position =
+                            previous.position + (keyframe.position - previous.position) * between;
+                        return position.clamp(0.0, 1.0);
+                    }
+                    previous = keyframe;
+                }
+                1.0
+            }
+        }
+    }
+}
+
+/// An error from the OSC library, which doesn't make it's own error type public
+type OscError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Why commands couldn't be sent to the desk
+#[derive(Debug)]
+pub enum OutputError {
+    /// The socket to send from couldn't be opened
+    Connect(OscError),
+    /// One of the commands couldn't be sent, the ones before it were
+    Send { command: String, source: OscError },
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::Connect(e) => write!(f, "Failed to connect to the desk: {e}"),
+            OutputError::Send { command, source } => {
+                write!(f, "Failed to send \"{command}\" to the desk: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OutputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OutputError::Connect(e) => Some(e.as_ref()),
+            OutputError::Send { source, .. } => Some(source.as_ref()),
+        }
+    }
+}
+
+/// How a move is made, apart from the path and lights it uses
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveOptions {
+    /// How many points should be calculated - must be >0 and the higher it is, the more accurate the move will be
+    pub frames: u16,
+    /// How long the whole move takes, in seconds
+    pub move_time: f64,
+    /// Space the frames evenly by distance along the path, rather than by the path's own percentages
+    pub constant_speed: bool,
+    /// Where each light is along the path compared to the others
+    pub spread: Spread,
+    /// How fast the lights move along the path as the move goes on
+    pub easing: Easing,
+}
+
+impl Default for MoveOptions {
+    fn default() -> Self {
+        Self {
+            frames: 10,
+            move_time: 1.0,
+            constant_speed: true,
+            spread: Spread::Together,
+            easing: Easing::Linear,
+        }
+    }
+}
+
+/// Calculates where each of the lights needs to point during a move.
+/// The frames are all the same length, easing changes how far apart they are along the path
+pub fn calculate_move<P: Path>(
+    path: &P,
+    lights: &[Light],
+    options: &MoveOptions,
+) -> Result<Vec<Frame>, MoveError> {
+    // Ensure that we actually have lights
+    if lights.is_empty() {
+        return Err(MoveError::NoLights);
+    }
+    if options.frames == 0 {
+        return Err(MoveError::NoFrames);
+    }
+
+    let mut out_frames: Vec<Frame> = vec![];
+
+    // Loop over each frame...
+    for path_points in frame_points(path, lights.len(), options) {
+        let mut current_frame = Frame {
+            delay: options.move_time / (options.frames as f64),
+            light_states: vec![],
+        };
+
+        // ...And for each frame point the current light at the right spot, taking the shortest route from where it was last frame
+        for (i, light) in lights.iter().enumerate() {
+            let previous_state = out_frames
+                .last()
+                .map(|previous_frame: &Frame| &previous_frame.light_states[i]);
+            current_frame
+                .light_states
+                .push(light.point_at_closest(path_points[i], previous_state));
+        }
+        out_frames.push(current_frame);
+    }
+
+    Ok(out_frames)
+}
+
+/// Where on the path each light points in each frame of a move, see `calculate_move`
+pub fn frame_points<P: Path>(
+    path: &P,
+    lights: usize,
+    options: &MoveOptions,
+) -> Vec<Vec<Vector3d<f64>>> {
+    // Taking the reciprocal of the number of frames gives us how many percent per frame
+    let percent_per_frame: f64 = (options.frames as f64).recip();
+    let arc_length_table = path.arc_length_table();
+    let closed = path.is_closed();
+
+    (0..options.frames)
+        .map(|frame| {
+            let progress = options
+                .easing
+                .position_at(percent_per_frame * (frame as f64));
+            options
+                .spread
+                .fractions(progress, lights, arc_length_table.length(), closed)
+                .into_iter()
+                .map(|fraction| {
+                    if options.constant_speed {
+                        path.point_at(&arc_length_table.percentage_at_fraction(fraction))
+                    } else {
+                        path.point_at(&Percentage::from_decimal(fraction))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The highest cue number EOS allows, in thousandths of a cue
+const MAX_CUE: u64 = 9_999_999;
+/// The most parts EOS allows in a cue
+const MAX_PARTS: usize = 99;
+/// The highest cue list number EOS allows
+const MAX_CUE_LIST: u32 = 999;
+
+/// How each frame of a move is numbered when it's recorded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CueNumbering {
+    /// Point cues this far apart, starting at the first cue. EOS allows up to 3 decimal places
+    Decimal { step: f64 },
+    /// A whole cue for each frame, one after the other
+    Whole,
+    /// One cue with a part for each frame, so the move runs from a single go
+    Parts,
+}
+
+impl Default for CueNumbering {
+    fn default() -> Self {
+        CueNumbering::Decimal { step: 0.01 }
+    }
+}
+
+/// Cues that are already on the desk and mustn't be recorded over, from `first` to `last` including both
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CueRange {
+    pub first: f64,
+    pub last: f64,
+}
+
+impl CueRange {
+    /// Whether a cue number, in thousandths of a cue, is in the range
+    fn contains(&self, cue: u64) -> bool {
+        let first = (self.first * 1000.0).round();
+        let last = (self.last * 1000.0).round();
+        (first..=last).contains(&(cue as f64))
+    }
+}
+
+impl fmt::Display for CueRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}", self.first, self.last)
+    }
+}
+
+/// A flag set on the first cue of a move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CueFlag {
+    /// Mark the lights in to position before the move starts
+    Mark,
+    /// Record everything in the first cue, so changes to earlier cues don't track in to the move
+    Block,
+}
+
+/// Which cues a move is recorded in to, and how they're set up
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueSettings {
+    pub cue_list: u32,
+    pub first_cue: u32,
+    pub numbering: CueNumbering,
+    pub protected: Option<CueRange>,
+    /// Each cue is labelled with this and which frame it is, e.g. "Apron 3/10"
+    pub label: Option<String>,
+    pub first_cue_flag: Option<CueFlag>,
+    /// The last cue follows on to the first, so the move goes round and round
+    pub loop_back: bool,
+    /// Point cues can carry on past the next whole cue, rather than stopping before they record over it
+    pub past_next_cue: bool,
+}
+
+impl Default for CueSettings {
+    fn default() -> Self {
+        Self {
+            cue_list: 1,
+            first_cue: 1,
+            numbering: CueNumbering::default(),
+            protected: None,
+            label: None,
+            first_cue_flag: None,
+            loop_back: false,
+            past_next_cue: false,
+        }
+    }
+}
+
+/// Why a move's frames can't be numbered as cues
+#[derive(Debug, Clone, PartialEq)]
+pub enum CueError {
+    /// EOS cue lists go from 1 to 999
+    BadList(u32),
+    /// Point cues have to be a whole number of thousandths apart
+    BadStep(f64),
+    /// The last frame would be after the highest cue number EOS allows
+    TooHigh { last: String },
+    /// There are more frames than EOS allows parts in a cue
+    TooManyParts(usize),
+    /// Point cues would reach the next whole cue, which is likely already on the desk
+    RunsIntoNextCue { last: String, next: u32 },
+    /// A frame would be recorded over one of the protected cues
+    Protected { cue: String, range: CueRange },
+}
+
+impl fmt::Display for CueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CueError::BadList(list) => {
+                write!(f, "Cue lists can be from 1 to {MAX_CUE_LIST}, not {list}")
+            }
+            CueError::BadStep(step) => write!(
+                f,
+                "Point cues can be 0.001 apart or more, in steps of 0.001, not {step}"
+            ),
+            CueError::TooHigh { last } => write!(
+                f,
+                "The move would end at cue {last}, but EOS only goes up to cue 9999.999"
+            ),
+            CueError::TooManyParts(frames) => write!(
+                f,
+                "EOS allows {MAX_PARTS} parts in a cue, but the move has {frames} frames"
+            ),
+            CueError::RunsIntoNextCue { last, next } => write!(
+                f,
+                "The move would end at cue {last}, running in to cue {next}. Use fewer frames, a smaller step or whole cues, or let it carry on past the next cue"
+            ),
+            CueError::Protected { cue, range } => write!(
+                f,
+                "The move would record over cue {cue}, which is in the protected cues {range}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CueError {}
+
+/// Writes a cue number in thousandths with `places` decimal places, e.g. 5010 with 2 places is 5.01
+fn format_cue(cue: u64, places: u32) -> String {
+    if places == 0 {
+        return format!("{}", cue / 1000);
+    }
+    let decimals = (cue % 1000) / 10_u64.pow(3 - places);
+    format!(
+        "{}.{:0>width$}",
+        cue / 1000,
+        decimals,
+        width = places as usize
+    )
+}
+
+impl CueSettings {
+    /// The cue number of each of `frames` frames with it's cue list, as they're written in commands.
+    /// Checks they all fit in EOS and stay clear of the protected cues, so nothing is recorded if any of them won't work
+    pub fn cue_numbers(&self, frames: usize) -> Result<Vec<String>, CueError> {
+        if !(1..=MAX_CUE_LIST).contains(&self.cue_list) {
+            return Err(CueError::BadList(self.cue_list));
+        }
+
+        let first = self.first_cue as u64 * 1000;
+        let (step, places) = match self.numbering {
+            CueNumbering::Decimal { step } => {
+                let thousandths = (step * 1000.0).round();
+                if !(thousandths >= 1.0 && (step * 1000.0 - thousandths).abs() < 1e-6) {
+                    return Err(CueError::BadStep(step));
+                }
+                let thousandths = thousandths as u64;
+                // Only as many decimal places as the step needs, so a step of 0.01 gives 5.00, 5.01...
+                let places = match thousandths {
+                    t if t % 1000 == 0 => 0,
+                    t if t % 100 == 0 => 1,
+                    t if t % 10 == 0 => 2,
+                    _ => 3,
+                };
+                (thousandths, places)
+            }
+            CueNumbering::Whole => (1000, 0),
+            CueNumbering::Parts => {
+                if frames > MAX_PARTS {
+                    return Err(CueError::TooManyParts(frames));
+                }
+                (0, 0)
+            }
+        };
+
+        let cues: Vec<u64> = (0..frames as u64).map(|i| first + step * i).collect();
+        if let Some(last) = cues.last().filter(|last| **last > MAX_CUE) {
+            return Err(CueError::TooHigh {
+                last: format_cue(*last, places),
+            });
+        }
+        if let (CueNumbering::Decimal { .. }, false, Some(last)) =
+            (self.numbering, self.past_next_cue, cues.last())
+        {
+            if *last >= first + 1000 {
+                return Err(CueError::RunsIntoNextCue {
+                    last: format_cue(*last, places),
+                    next: self.first_cue + 1,
+                });
+            }
+        }
+        if let Some(range) = self.protected {
+            if let Some(cue) = cues.iter().find(|cue| range.contains(**cue)) {
+                return Err(CueError::Protected {
+                    cue: format_cue(*cue, places),
+                    range,
+                });
+            }
+        }
+
+        Ok(cues
+            .iter()
+            .enumerate()
+            .map(|(i, cue)| match self.numbering {
+                CueNumbering::Parts => {
+                    format!("{}/{} Part {}", self.cue_list, format_cue(*cue, 0), i + 1)
+                }
+                CueNumbering::Decimal { .. } | CueNumbering::Whole => {
+                    format!("{}/{}", self.cue_list, format_cue(*cue, places))
+                }
+            })
+            .collect())
+    }
+}
+
+/// The commands that record one frame of a move
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameCommands {
+    /// The cue, or part of a cue, the frame is recorded in to, with it's cue list
+    pub cue_number: String,
+    pub commands: Vec<String>,
+}
+
+/// Turns frames in to EOS commands, recording each frame in to it's own cue, or part of a cue, as set by `cues`.
+/// Commands that link the cues together come with the last frame
+pub fn frame_commands(
+    frames: &[Frame],
+    cues: &CueSettings,
+) -> Result<Vec<FrameCommands>, CueError> {
+    let cue_numbers = cues.cue_numbers(frames.len())?;
+    // Flags and links are for whole cues, not parts
+    let whole_cue = |cue_number: &str| match cues.numbering {
+        CueNumbering::Parts => format!("{}/{}", cues.cue_list, cues.first_cue),
+        CueNumbering::Decimal { .. } | CueNumbering::Whole => cue_number.to_string(),
+    };
+
+    let mut out_frames: Vec<FrameCommands> = vec![];
+    // When each frame starts, for the delays of cue parts
+    let mut start = 0.0;
+    for (i, (frame, cue_number)) in frames.iter().zip(&cue_numbers).enumerate() {
+        let mut out_commands: Vec<String> = vec![];
+        for light_state in &frame.light_states {
+            out_commands.append(&mut light_state.to_commands());
+        }
+
+        out_commands.push(format!("Record Cue {} Time {:.2}", cue_number, frame.delay));
+        match cues.numbering {
+            // Every part starts from the same go, so each one waits until the one before it has finished
+            CueNumbering::Parts => {
+                out_commands.push(format!("Cue {} Delay {:.2}", cue_number, start));
+            }
+            CueNumbering::Decimal { .. } | CueNumbering::Whole => {
+                out_commands.push(format!("Cue {} Follow {:.2}", cue_number, frame.delay));
+            }
+        }
+        start += frame.delay;
+
+        if let Some(label) = &cues.label {
+            out_commands.push(format!(
+                "Cue {} Label {} {}/{}",
+                cue_number,
+                label,
+                i + 1,
+                frames.len()
+            ));
+        }
+        if i == 0 {
+            match cues.first_cue_flag {
+                Some(CueFlag::Mark) => {
+                    out_commands.push(format!("Cue {} Mark", whole_cue(cue_number)))
+                }
+                Some(CueFlag::Block) => {
+                    out_commands.push(format!("Cue {} Block", whole_cue(cue_number)))
+                }
+                None => {}
+            }
+        }
+
+        out_frames.push(FrameCommands {
+            cue_number: cue_number.clone(),
+            commands: out_commands,
+        });
+    }
+
+    if let (true, Some(first), Some(last)) =
+        (cues.loop_back, cue_numbers.first(), out_frames.last_mut())
+    {
+        let last_cue = whole_cue(&last.cue_number);
+        // A cue of parts follows itself once every part has finished
+        if cues.numbering == CueNumbering::Parts {
+            last.commands
+                .push(format!("Cue {} Follow {:.2}", last_cue, start));
+        }
+        last.commands
+            .push(format!("Cue {} Link {}", last_cue, whole_cue(first)));
+    }
+
+    Ok(out_frames)
+}
+
+/// Turns frames in to a set of EOS commands, see `frame_commands`
+pub fn frames_to_commands(frames: &[Frame], cues: &CueSettings) -> Result<Vec<String>, CueError> {
+    Ok(frame_commands(frames, cues)?
+        .into_iter()
+        .flat_map(|frame| frame.commands)
+        .collect())
+}
+
+/// Where to send commands to the desk from and to, and how quickly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeskConnection {
+    /// The address to send from, port 0 picks any free port
+    pub host: SocketAddr,
+    pub desk: SocketAddr,
+    /// Desks can miss commands that arrive too quickly, so they're spaced out to this rate
+    pub commands_per_second: f64,
+}
+
+impl DeskConnection {
+    /// How long to wait between commands. Never more than a second
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f64(self.commands_per_second.max(1.0).recip())
+    }
+}
+
+/// Commands being sent to the desk on a background thread, so whatever started it can carry on.
+/// Dropping it cancels the commands that haven't been sent yet
+pub struct OutputJob {
+    total: usize,
+    /// How many commands have been sent without failing
+    sent: Arc<AtomicUsize>,
+    /// Dropped to tell the worker to stop
+    cancel: Option<mpsc::Sender<()>>,
+    failures: mpsc::Receiver<OutputError>,
+    worker: thread::JoinHandle<()>,
+}
+
+impl OutputJob {
+    /// Starts sending the commands, spaced out to `connection.commands_per_second`.
+    /// A command that fails doesn't stop the rest, see `OutputJob::failures`
+    pub fn start(commands: Vec<String>, connection: DeskConnection) -> Self {
+        let total = commands.len();
+        let sent = Arc::new(AtomicUsize::new(0));
+        let (cancel, cancelled) = mpsc::channel::<()>();
+        let (failure_sender, failures) = mpsc::channel();
+
+        let worker_sent = Arc::clone(&sent);
+        let worker = thread::spawn(move || {
+            let host = connection.host;
+            let desk = match EosDesk::new(
+                (host.ip(), host.port()),
+                (connection.desk.ip(), connection.desk.port()),
+            ) {
+                Ok(desk) => desk,
+                Err(e) => {
+                    // Nobody is listening if the job has been dropped already
+                    let _ = failure_sender.send(OutputError::Connect(Box::new(e)));
+                    return;
+                }
+            };
+
+            for (i, command) in commands.into_iter().enumerate() {
+                // Wait between commands, stopping straight away if the job is cancelled while waiting
+                let wait = if i == 0 {
+                    Duration::ZERO
+                } else {
+                    connection.interval()
+                };
+                if cancelled.recv_timeout(wait) != Err(mpsc::RecvTimeoutError::Timeout) {
+                    return;
+                }
+
+                match desk.command(&command) {
+                    Ok(()) => {
+                        worker_sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(source) => {
+                        let _ = failure_sender.send(OutputError::Send {
+                            command,
+                            source: Box::new(source),
+                        });
+                    }
+                }
+            }
+        });
+
+        Self {
+            total,
+            sent,
+            cancel: Some(cancel),
+            failures,
+            worker,
+        }
+    }
+
+    /// How many commands have been sent so far, out of how many there are
+    pub fn progress(&self) -> (usize, usize) {
+        (self.sent.load(Ordering::Relaxed), self.total)
+    }
+
+    /// Stops sending commands. The one being sent right now still goes
+    pub fn cancel(&mut self) {
+        self.cancel = None;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_none()
+    }
+
+    /// True once every command has been tried, or the job has been cancelled and stopped
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_finished()
+    }
+
+    /// The commands that have failed since this was last called. Doesn't wait for any more
+    pub fn failures(&self) -> Vec<OutputError> {
+        self.failures.try_iter().collect()
+    }
+
+    /// Waits for every command to be tried, returning all of the failures that haven't been seen yet
+    pub fn wait(self) -> Vec<OutputError> {
+        let OutputJob {
+            cancel,
+            failures,
+            worker,
+            ..
+        } = self;
+
+        // A panic in the worker would just mean some commands weren't sent, which the progress already shows
+        let _ = worker.join();
+        drop(cancel);
+
+        failures.try_iter().collect()
+    }
+}
+
+/// Sends the commands to the desk over OSC, blocking until they've all been tried.
+/// Returns the first command that failed, if any did
+pub fn output_commands(
+    commands: &[String],
+    connection: &DeskConnection,
+) -> Result<(), OutputError> {
+    match OutputJob::start(commands.to_vec(), *connection)
+        .wait()
+        .into_iter()
+        .next()
+    {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector3d::Vector3d;
+
+    use crate::path::{circle::Circle, line::Line, PathEnum};
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_calculate_move_no_pan_jump() {
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            address: 5,
+            ..Default::default()
+        };
+
+        // Passes behind the light, where the raw pan jumps from +180 to -180
+        let path = PathEnum::Line(Line::new(
+            Vector3d {
+                x: 1.0,
+                y: -1.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: -1.0,
+                y: -1.0,
+                z: 0.0,
+            },
+        ));
+
+        let frames = calculate_move(
+            &path,
+            &[light],
+            &MoveOptions {
+                constant_speed: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for pair in frames.windows(2) {
+            let pan_change = pair[1].light_states[0].pan - pair[0].light_states[0].pan;
+            assert!(pan_change.abs() < 20.0, "Pan jumped by {pan_change}");
+        }
+    }
+
+    #[test]
+    fn test_calculate_move_constant_speed() {
+        // Directly above the midpoint of the curve, so equal distances along the curve give equal changes in pan
+        let light = Light {
+            coordinates: Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 4.0,
+            },
+            address: 5,
+            ..Default::default()
+        };
+
+        let path = PathEnum::Line(Line::new(
+            Vector3d {
+                x: -2.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 2.0,
+                y: 1.0,
+                z: 0.0,
+            },
+        ));
+
+        let even_frames = calculate_move(
+            &path,
+            std::slice::from_ref(&light),
+            &MoveOptions {
+                frames: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let raw_frames = calculate_move(
+            &path,
+            &[light],
+            &MoveOptions {
+                frames: 4,
+                constant_speed: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Lines are already evenly spaced, so both ways of spacing the frames should agree
+        for (even_frame, raw_frame) in even_frames.iter().zip(raw_frames.iter()) {
+            let pan_diff = even_frame.light_states[0].pan - raw_frame.light_states[0].pan;
+            let tilt_diff = even_frame.light_states[0].tilt - raw_frame.light_states[0].tilt;
+            assert!(pan_diff.abs() < 0.001 && tilt_diff.abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_calculate_move_errors() {
+        let path = Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0));
+
+        assert_eq!(
+            calculate_move(&path, &[], &MoveOptions::default()),
+            Err(MoveError::NoLights)
+        );
+        assert_eq!(
+            calculate_move(
+                &path,
+                &[Light::default()],
+                &MoveOptions {
+                    frames: 0,
+                    ..Default::default()
+                }
+            ),
+            Err(MoveError::NoFrames)
+        );
+    }
+
+    /// Two lights in the same place, so they should point the same way whenever they're at the same spot on the path
+    fn twin_lights() -> [Light; 2] {
+        let light = Light {
+            coordinates: Vector3d::new(0.0, -3.0, 5.0),
+            ..Default::default()
+        };
+        [
+            light.clone(),
+            Light {
+                address: 2,
+                ..light
+            },
+        ]
+    }
+
+    fn assert_same_state(a: &LightState, b: &LightState) {
+        assert!(
+            (a.pan - b.pan).abs() < 0.001 && (a.tilt - b.tilt).abs() < 0.001,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn test_spread_fractions() {
+        assert_eq!(Spread::Together.fractions(0.5, 2, 4.0, false), [0.5, 0.5]);
+        assert_eq!(
+            Spread::Fan.fractions(0.0, 4, 4.0, false),
+            [0.0, 0.25, 0.5, 0.75]
+        );
+        assert_eq!(
+            Spread::PhaseDistance(1.0).fractions(0.5, 3, 4.0, false),
+            [0.5, 0.25, 0.0]
+        );
+        // Open paths hold the trailing lights at the start...
+        assert_eq!(
+            Spread::Phase(0.25).fractions(0.25, 3, 4.0, false),
+            [0.25, 0.0, 0.0]
+        );
+        // ...closed ones wrap them round from the end
+        assert_eq!(
+            Spread::Phase(0.25).fractions(0.25, 3, 4.0, true),
+            [0.25, 0.0, 0.75]
+        );
+    }
+
+    #[test]
+    fn test_calculate_move_phase_open_path() {
+        let path = Line::new(Vector3d::new(-2.0, 0.0, 0.0), Vector3d::new(2.0, 0.0, 0.0));
+        let frames = calculate_move(
+            &path,
+            &twin_lights(),
+            &MoveOptions {
+                constant_speed: false,
+                spread: Spread::Phase(0.2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The second light is 2 frames behind the first...
+        for frame in 2..10 {
+            assert_same_state(
+                &frames[frame].light_states[1],
+                &frames[frame - 2].light_states[0],
+            );
+        }
+        // ...and waits at the start until it's caught up
+        for frame in &frames[..2] {
+            assert_same_state(&frame.light_states[1], &frames[0].light_states[0]);
+        }
+    }
+
+    #[test]
+    fn test_calculate_move_phase_closed_path_wraps() {
+        let path = Circle::new(
+            Vector3d::new(0.0, 0.0, 0.0),
+            2.0,
+            Vector3d::new(0.0, 0.0, 1.0),
+            0.0,
+        );
+        assert!(path.is_closed());
+        let frames = calculate_move(
+            &path,
+            &twin_lights(),
+            &MoveOptions {
+                spread: Spread::Phase(0.2),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Trailing behind the start puts the second light near the end of the circle
+        for frame in 0..10 {
+            assert_same_state(
+                &frames[frame].light_states[1],
+                &frames[(frame + 8) % 10].light_states[0],
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_move_phase_distance_and_fan() {
+        // 4m long, so 1m behind is a quarter of the path, or 2 frames out of 8
+        let path = Line::new(Vector3d::new(-2.0, 0.0, 0.0), Vector3d::new(2.0, 0.0, 0.0));
+        let lights = twin_lights();
+
+        let frames = calculate_move(
+            &path,
+            &lights,
+            &MoveOptions {
+                frames: 8,
+                spread: Spread::PhaseDistance(1.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for frame in 2..8 {
+            assert_same_state(
+                &frames[frame].light_states[1],
+                &frames[frame - 2].light_states[0],
+            );
+        }
+
+        // Fanned out, the second light covers the second half while the first covers the first
+        let frames = calculate_move(
+            &path,
+            &lights,
+            &MoveOptions {
+                frames: 8,
+                spread: Spread::Fan,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let together = calculate_move(
+            &path,
+            &lights[..1],
+            &MoveOptions {
+                frames: 16,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for (frame, fanned) in frames.iter().enumerate() {
+            assert_same_state(&fanned.light_states[0], &together[frame].light_states[0]);
+            assert_same_state(
+                &fanned.light_states[1],
+                &together[frame + 8].light_states[0],
+            );
+        }
+    }
+
+    #[test]
+    fn test_easing_ends() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::SCurve,
+            Easing::Custom(vec![]),
+        ] {
+            assert_eq!(easing.position_at(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.position_at(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.position_at(-1.0), 0.0, "{easing:?}");
+            assert_eq!(easing.position_at(2.0), 1.0, "{easing:?}");
+        }
+
+        assert!(Easing::EaseIn.position_at(0.25) < 0.25);
+        assert!(Easing::EaseOut.position_at(0.25) > 0.25);
+        assert_eq!(Easing::EaseInOut.position_at(0.5), 0.5);
+        assert_eq!(Easing::SCurve.position_at(0.5), 0.5);
+        // The S-curve starts off slower than ease in and out
+        assert!(Easing::SCurve.position_at(0.1) < Easing::EaseInOut.position_at(0.1));
+    }
+
+    #[test]
+    fn test_easing_custom() {
+        // Out of order, with a hold in the middle
+        let easing = Easing::Custom(vec![
+            Keyframe {
+                time: 0.75,
+                position: 0.5,
+            },
+            Keyframe {
+                time: 0.25,
+                position: 0.5,
+            },
+        ]);
+        assert_eq!(easing.position_at(0.125), 0.25);
+        assert_eq!(easing.position_at(0.5), 0.5);
+        assert_eq!(easing.position_at(0.875), 0.75);
+
+        // A keyframe at the very start jumps straight there
+        let easing = Easing::Custom(vec![Keyframe {
+            time: 0.0,
+            position: 0.5,
+        }]);
+        assert_eq!(easing.position_at(0.0), 0.5);
+        assert_eq!(easing.position_at(0.5), 0.75);
+    }
+
+    #[test]
+    fn test_calculate_move_easing() {
+        let path = Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(4.0, 0.0, 0.0));
+        let points = frame_points(
+            &path,
+            1,
+            &MoveOptions {
+                frames: 4,
+                constant_speed: false,
+                easing: Easing::EaseIn,
+                ..Default::default()
+            },
+        );
+        let xs: Vec<f64> = points.iter().map(|frame| frame[0].x).collect();
+        assert_eq!(xs, [0.0, 0.25, 1.0, 2.25]);
+
+        // The frames get further apart along the path, but still take the same time
+        let frames = calculate_move(
+            &path,
+            &[Light::default()],
+            &MoveOptions {
+                frames: 4,
+                move_time: 2.0,
+                easing: Easing::EaseIn,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(frames.iter().all(|frame| frame.delay == 0.5));
+    }
+
+    #[test]
+    fn test_cue_numbers() {
+        let cues = |numbering| CueSettings {
+            first_cue: 5,
+            numbering,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cues(CueNumbering::default()).cue_numbers(3),
+            Ok(vec![
+                "1/5.00".to_string(),
+                "1/5.01".to_string(),
+                "1/5.02".to_string()
+            ])
+        );
+        // More than 100 frames only carry on past the next whole cue when it's allowed
+        assert_eq!(
+            cues(CueNumbering::default()).cue_numbers(100).unwrap()[99],
+            "1/5.99"
+        );
+        assert_eq!(
+            cues(CueNumbering::default()).cue_numbers(150),
+            Err(CueError::RunsIntoNextCue {
+                last: "6.49".to_string(),
+                next: 6
+            })
+        );
+        assert_eq!(
+            CueSettings {
+                past_next_cue: true,
+                ..cues(CueNumbering::default())
+            }
+            .cue_numbers(150)
+            .unwrap()[149],
+            "1/6.49"
+        );
+        assert_eq!(
+            cues(CueNumbering::Decimal { step: 0.005 }).cue_numbers(3),
+            Ok(vec![
+                "1/5.000".to_string(),
+                "1/5.005".to_string(),
+                "1/5.010".to_string()
+            ])
+        );
+        assert_eq!(
+            cues(CueNumbering::Decimal { step: 0.5 }).cue_numbers(2),
+            Ok(vec!["1/5.0".to_string(), "1/5.5".to_string()])
+        );
+        assert_eq!(
+            cues(CueNumbering::Whole).cue_numbers(3),
+            Ok(vec![
+                "1/5".to_string(),
+                "1/6".to_string(),
+                "1/7".to_string()
+            ])
+        );
+        assert_eq!(
+            cues(CueNumbering::Parts).cue_numbers(2),
+            Ok(vec!["1/5 Part 1".to_string(), "1/5 Part 2".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_cue_numbers_errors() {
+        let mut cues = CueSettings {
+            first_cue: 9999,
+            numbering: CueNumbering::Whole,
+            ..Default::default()
+        };
+        assert_eq!(
+            cues.cue_numbers(2),
+            Err(CueError::TooHigh {
+                last: "10000".to_string()
+            })
+        );
+
+        cues.first_cue = 5;
+        cues.cue_list = 0;
+        assert_eq!(cues.cue_numbers(2), Err(CueError::BadList(0)));
+
+        cues.cue_list = 2;
+        cues.numbering = CueNumbering::Decimal { step: 0.0005 };
+        assert_eq!(cues.cue_numbers(2), Err(CueError::BadStep(0.0005)));
+
+        cues.numbering = CueNumbering::Parts;
+        assert_eq!(cues.cue_numbers(100), Err(CueError::TooManyParts(100)));
+
+        let range = CueRange {
+            first: 5.5,
+            last: 6.0,
+        };
+        cues.numbering = CueNumbering::default();
+        cues.protected = Some(range);
+        assert!(cues.cue_numbers(50).is_ok());
+        assert_eq!(
+            cues.cue_numbers(51),
+            Err(CueError::Protected {
+                cue: "5.50".to_string(),
+                range
+            })
+        );
+    }
+
+    #[test]
+    fn test_frames_to_commands_parts() {
+        let state = LightState {
+            address: 5,
+            pan: 0.0,
+            tilt: 0.0,
+        };
+        let light_commands = state.to_commands().len();
+        let frame = Frame {
+            delay: 0.5,
+            light_states: vec![state],
+        };
+
+        let commands = frames_to_commands(
+            &[frame.clone(), frame],
+            &CueSettings {
+                cue_list: 2,
+                first_cue: 3,
+                numbering: CueNumbering::Parts,
+                loop_back: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            commands[light_commands..light_commands + 2],
+            [
+                "Record Cue 2/3 Part 1 Time 0.50",
+                "Cue 2/3 Part 1 Delay 0.00"
+            ]
+        );
+        assert_eq!(
+            commands[light_commands * 2 + 2..],
+            [
+                "Record Cue 2/3 Part 2 Time 0.50",
+                "Cue 2/3 Part 2 Delay 0.50",
+                "Cue 2/3 Follow 1.00",
+                "Cue 2/3 Link 2/3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frames_to_commands_labels_flags_and_loop() {
+        let frame = Frame {
+            delay: 0.25,
+            light_states: vec![],
+        };
+
+        let commands = frames_to_commands(
+            &[frame.clone(), frame],
+            &CueSettings {
+                cue_list: 3,
+                first_cue: 10,
+                label: Some("Apron".to_string()),
+                first_cue_flag: Some(CueFlag::Block),
+                loop_back: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            commands,
+            [
+                "Record Cue 3/10.00 Time 0.25",
+                "Cue 3/10.00 Follow 0.25",
+                "Cue 3/10.00 Label Apron 1/2",
+                "Cue 3/10.00 Block",
+                "Record Cue 3/10.01 Time 0.25",
+                "Cue 3/10.01 Follow 0.25",
+                "Cue 3/10.01 Label Apron 2/2",
+                "Cue 3/10.01 Link 3/10.00",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frame_commands_grouping() {
+        let frame = Frame {
+            delay: 0.5,
+            light_states: vec![],
+        };
+
+        let frames = frame_commands(
+            &[frame.clone(), frame],
+            &CueSettings {
+                first_cue: 4,
+                numbering: CueNumbering::Whole,
+                loop_back: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            frames,
+            [
+                FrameCommands {
+                    cue_number: "1/4".to_string(),
+                    commands: vec![
+                        "Record Cue 1/4 Time 0.50".to_string(),
+                        "Cue 1/4 Follow 0.50".to_string()
+                    ],
+                },
+                FrameCommands {
+                    cue_number: "1/5".to_string(),
+                    commands: vec![
+                        "Record Cue 1/5 Time 0.50".to_string(),
+                        "Cue 1/5 Follow 0.50".to_string(),
+                        "Cue 1/5 Link 1/4".to_string()
+                    ],
+                },
+            ]
+        );
+    }
+
+    /// A socket standing in for the desk, and a connection that sends to it
+    fn fake_desk(commands_per_second: f64) -> (std::net::UdpSocket, DeskConnection) {
+        let desk = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        desk.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let connection = DeskConnection {
+            host: "127.0.0.1:0".parse().unwrap(),
+            desk: desk.local_addr().unwrap(),
+            commands_per_second,
+        };
+        (desk, connection)
+    }
+
+    #[test]
+    fn test_output_job_sends_everything() {
+        let (desk, connection) = fake_desk(1000.0);
+        let commands: Vec<String> = (1..=5).map(|i| format!("Chan {i} Full")).collect();
+
+        let job = OutputJob::start(commands.clone(), connection);
+        for command in &commands {
+            let mut buffer = [0; 256];
+            let length = desk.recv(&mut buffer).unwrap();
+            let packet = String::from_utf8_lossy(&buffer[..length]);
+            assert!(packet.contains(&format!("{command} ENTER")));
+        }
+
+        assert_eq!(job.progress().1, 5);
+        let sent = Arc::clone(&job.sent);
+        assert!(job.wait().is_empty());
+        assert_eq!(sent.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn test_output_job_cancel() {
+        // Slow enough that only the first command goes before it's cancelled
+        let (desk, connection) = fake_desk(1.0);
+        let commands = vec!["Chan 1 Full".to_string(); 10];
+
+        let mut job = OutputJob::start(commands, connection);
+        desk.recv(&mut [0; 256]).unwrap();
+        job.cancel();
+        assert!(job.is_cancelled());
+
+        let sent = Arc::clone(&job.sent);
+        assert!(job.wait().is_empty());
+        assert_eq!(sent.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_output_commands_connect_failure() {
+        let (_desk, mut connection) = fake_desk(1000.0);
+        // Not an address this machine has, so it can't be bound to
+        connection.host = "192.0.2.1:0".parse().unwrap();
+
+        assert!(matches!(
+            output_commands(&["Chan 1 Full".to_string()], &connection),
+            Err(OutputError::Connect(_))
+        ));
+    }
+}
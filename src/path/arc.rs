@@ -0,0 +1,156 @@
+//! Part of a circle
+
+use isx::prelude::IsDefault;
+use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::path::{
+    arc_length::ArcLengthTable,
+    ellipse::point_on_ellipse,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
+
+/// Part of a circle lying flat in the plane facing `normal`
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Arc {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
+    pub name: String,
+    pub centre: Vector3d<f64>,
+    pub radius: f64,
+    pub normal: Vector3d<f64>,
+    /// Angle (in degrees) around the circle that the path starts at
+    pub start_angle: f64,
+    /// How far round the circle (in degrees) the path goes, negative values go round the other way
+    pub sweep: f64,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
+}
+
+impl IsDefault for Arc {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Path for Arc {
+    fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        // An arc is just part of an ellipse with both radii the same
+        point_on_ellipse(
+            self.centre,
+            self.radius,
+            self.radius,
+            self.normal,
+            self.start_angle + self.sweep * index.value(),
+        )
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(
+            &[self.centre, self.normal],
+            &[self.radius, self.start_angle, self.sweep],
+        )
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
+}
+
+impl Arc {
+    /// Make a new Arc where you do not care about it's name
+    pub fn new(
+        centre: Vector3d<f64>,
+        radius: f64,
+        normal: Vector3d<f64>,
+        start_angle: f64,
+        sweep: f64,
+    ) -> Self {
+        Self {
+            id: 0,
+            name: String::default(),
+            centre,
+            radius,
+            normal,
+            start_angle,
+            sweep,
+            arc_length: ShapeCache::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use percentage::Percentage;
+
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    /// Half a circle across a wall facing the audience, going over the top from stage left to stage right
+    fn wall_arc() -> Arc {
+        Arc::new(
+            Vector3d {
+                x: 0.0,
+                y: 10.0,
+                z: 2.0,
+            },
+            4.0,
+            Vector3d {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            },
+            180.0,
+            -180.0,
+        )
+    }
+
+    #[test]
+    fn test_point_begin() {
+        assert!(eq_vector3d(
+            wall_arc().point_at(&Percentage::from_decimal(0.0)),
+            Vector3d {
+                x: -4.0,
+                y: 10.0,
+                z: 2.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_middle() {
+        assert!(eq_vector3d(
+            wall_arc().point_at(&Percentage::from_decimal(0.5)),
+            Vector3d {
+                x: 0.0,
+                y: 10.0,
+                z: 6.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_end() {
+        assert!(eq_vector3d(
+            wall_arc().point_at(&Percentage::from_decimal(1.0)),
+            Vector3d {
+                x: 4.0,
+                y: 10.0,
+                z: 2.0,
+            }
+        ))
+    }
+}
@@ -0,0 +1,182 @@
+//! Measuring distances along any path
+
+use percentage::{Percentage, PercentageDecimal};
+
+use crate::path::Path;
+
+/// A lookup table of how far along a path each percentage is, used to move along a path at constant speed.
+/// Build one per path and reuse it when querying lots of points, as building it samples the path many times
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthTable {
+    /// Distance along the path at evenly spaced percentages, starting at 0% and ending at 100%
+    distances: Vec<f64>,
+}
+
+impl ArcLengthTable {
+    /// How many straight segments the path is split in to when measuring it
+    const SEGMENTS: usize = 256;
+
+    /// Measures the path by adding up the lengths of lots of short straight segments along it
+    pub fn new<P: Path>(path: &P) -> Self {
+        let mut distances = Vec::with_capacity(Self::SEGMENTS + 1);
+        let mut previous_point = path.point_at(&Percentage::from_decimal(0.0));
+        let mut total = 0.0;
+        distances.push(total);
+
+        for i in 1..=Self::SEGMENTS {
+            let point = path.point_at(&Percentage::from_decimal(i as f64 / Self::SEGMENTS as f64));
+            total += (point - previous_point).norm2().sqrt();
+            distances.push(total);
+            previous_point = point;
+        }
+
+        Self { distances }
+    }
+
+    /// The total length of the path
+    pub fn length(&self) -> f64 {
+        *self.distances.last().unwrap_or(&0.0)
+    }
+
+    /// Finds the percentage in to the path that is `distance` along it. Distances outside of the path are clamped to it's ends
+    pub fn percentage_at_distance(&self, distance: f64) -> PercentageDecimal {
+        let length = self.length();
+        if length <= 0.0 || distance <= 0.0 {
+            return Percentage::from_decimal(0.0);
+        }
+        if distance >= length {
+            return Percentage::from_decimal(1.0);
+        }
+
+        // Find the segment that the distance lands in, then go the right amount along it
+        let segment = self.distances.partition_point(|&d| d <= distance) - 1;
+        let segment_start = self.distances[segment];
+        let segment_length = self.distances[segment + 1] - segment_start;
+        let along_segment = if segment_length > 0.0 {
+            (distance - segment_start) / segment_length
+        } else {
+            0.0
+        };
+
+        Percentage::from_decimal(
+            ((segment as f64 + along_segment) / Self::SEGMENTS as f64).clamp(0.0, 1.0),
+        )
+    }
+
+    /// Same as `percentage_at_distance` but with the distance given as a fraction of the path's length
+    pub fn percentage_at_fraction(&self, fraction: f64) -> PercentageDecimal {
+        self.percentage_at_distance(fraction * self.length())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector3d::Vector3d;
+
+    use crate::path::{bezier::Bezier, line::Line};
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    /// Checks if two floating point values are equal, leaving some wiggle room for the table being an approximation
+    fn roughly_eq(value_1: f64, value_2: f64) -> bool {
+        let diff = value_1 - value_2;
+        diff < 0.001 && diff > -0.001
+    }
+
+    #[test]
+    fn test_line_length() {
+        let path = Line::new(
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 3.0,
+                y: 4.0,
+                z: 0.0,
+            },
+        );
+
+        assert!(roughly_eq(path.length(), 5.0));
+    }
+
+    #[test]
+    fn test_line_point_at_distance() {
+        let path = Line::new(
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 3.0,
+                y: 4.0,
+                z: 0.0,
+            },
+        );
+
+        let point = path.point_at_distance(2.5);
+        assert!(roughly_eq(point.x, 1.5));
+        assert!(roughly_eq(point.y, 2.0));
+    }
+
+    #[test]
+    fn test_clamps_to_ends() {
+        let table = ArcLengthTable::new(&Line::new(
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        ));
+
+        assert_eq!(table.percentage_at_distance(-1.0).value(), 0.0);
+        assert_eq!(table.percentage_at_distance(2.0).value(), 1.0);
+    }
+
+    #[test]
+    fn test_bezier_even_spacing() {
+        // A tight curve, where raw percentages bunch up around the midpoint
+        let path = Bezier::new(
+            Vector3d {
+                x: 0.0,
+                y: 4.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 4.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        );
+        let table = ArcLengthTable::new(&path);
+
+        let points: Vec<Vector3d<f64>> = (0..=10)
+            .map(|i| path.point_at(&table.percentage_at_fraction(i as f64 / 10.0)))
+            .collect();
+
+        let step_lengths: Vec<f64> = points
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).norm2().sqrt())
+            .collect();
+
+        for step_length in &step_lengths {
+            assert!(
+                (step_length - step_lengths[0]).abs() < 0.01,
+                "Steps are not evenly spaced: {step_lengths:?}"
+            );
+        }
+    }
+}
@@ -1,7 +1,15 @@
+//! Quadratic Bezier curves
+
 use isx::prelude::IsDefault;
+use serde::{Deserialize, Serialize};
 use vector3d::Vector3d;
 
-use crate::path::{line::Line, Path};
+use crate::path::{
+    arc_length::ArcLengthTable,
+    line::Line,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
 
 impl IsDefault for Bezier {
     fn is_default(&self) -> bool {
@@ -9,12 +17,18 @@ impl IsDefault for Bezier {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+/// A quadratic Bezier curve, pulled towards `midpoint`
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Bezier {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
     pub name: String,
     pub start: Vector3d<f64>,
     pub midpoint: Vector3d<f64>,
     pub end: Vector3d<f64>,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
 }
 
 impl Path for Bezier {
@@ -31,16 +45,30 @@ impl Path for Bezier {
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(&[self.start, self.midpoint, self.end], &[])
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
 }
 
 impl Bezier {
     /// Make a new Bezier where you don't care about it's name
     pub fn new(start: Vector3d<f64>, midpoint: Vector3d<f64>, end: Vector3d<f64>) -> Self {
         Self {
+            id: 0,
             name: String::default(),
             start,
             midpoint,
             end,
+            arc_length: ShapeCache::default(),
         }
     }
 }
@@ -70,12 +98,7 @@ mod tests {
             z: 0.0,
         };
 
-        let path = Bezier {
-            name: String::default(),
-            start,
-            midpoint,
-            end,
-        };
+        let path = Bezier::new(start, midpoint, end);
 
         assert_eq!(path.point_at(&Percentage::from_decimal(0.0)), start)
     }
@@ -97,12 +120,7 @@ mod tests {
             y: 0.0,
             z: 0.0,
         };
-        let path = Bezier {
-            name: String::default(),
-            start,
-            midpoint,
-            end,
-        };
+        let path = Bezier::new(start, midpoint, end);
 
         assert_eq!(path.point_at(&Percentage::from_decimal(1.0)), end)
     }
@@ -124,12 +142,7 @@ mod tests {
             y: 0.0,
             z: 0.0,
         };
-        let path = Bezier {
-            name: String::default(),
-            start,
-            midpoint,
-            end,
-        };
+        let path = Bezier::new(start, midpoint, end);
 
         assert_eq!(
             path.point_at(&Percentage::from_decimal(0.5)),
@@ -0,0 +1,149 @@
+//! Whole circles
+
+use isx::prelude::IsDefault;
+use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::path::{
+    arc_length::ArcLengthTable,
+    ellipse::point_on_ellipse,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
+
+/// A circle lying flat in the plane facing `normal`, going all the way round once. Use an `Arc` for part of one
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Circle {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
+    pub name: String,
+    pub centre: Vector3d<f64>,
+    pub radius: f64,
+    pub normal: Vector3d<f64>,
+    /// Angle (in degrees) around the circle that the path starts and ends at
+    pub start_angle: f64,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
+}
+
+impl IsDefault for Circle {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Path for Circle {
+    fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        // A circle is just an ellipse with both radii the same
+        point_on_ellipse(
+            self.centre,
+            self.radius,
+            self.radius,
+            self.normal,
+            self.start_angle + 360.0 * index.value(),
+        )
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(
+            &[self.centre, self.normal],
+            &[self.radius, self.start_angle],
+        )
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
+}
+
+impl Circle {
+    /// Make a new Circle where you do not care about it's name
+    pub fn new(
+        centre: Vector3d<f64>,
+        radius: f64,
+        normal: Vector3d<f64>,
+        start_angle: f64,
+    ) -> Self {
+        Self {
+            id: 0,
+            name: String::default(),
+            centre,
+            radius,
+            normal,
+            start_angle,
+            arc_length: ShapeCache::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use percentage::Percentage;
+
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn floor_circle() -> Circle {
+        Circle::new(
+            Vector3d {
+                x: 0.0,
+                y: 2.0,
+                z: 0.0,
+            },
+            3.0,
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            90.0,
+        )
+    }
+
+    #[test]
+    fn test_point_begin() {
+        assert!(eq_vector3d(
+            floor_circle().point_at(&Percentage::from_decimal(0.0)),
+            Vector3d {
+                x: 0.0,
+                y: 5.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_middle() {
+        assert!(eq_vector3d(
+            floor_circle().point_at(&Percentage::from_decimal(0.5)),
+            Vector3d {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_closed() {
+        assert!(floor_circle().is_closed());
+    }
+
+    #[test]
+    fn test_length() {
+        let length = floor_circle().length();
+        assert!((length - 6.0 * std::f64::consts::PI).abs() < 0.01);
+    }
+}
@@ -0,0 +1,266 @@
+//! Paths made of other paths joined end to end
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use isx::prelude::IsDefault;
+use percentage::{Percentage, PercentageDecimal};
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::path::{arc_length::ArcLengthTable, shape_cache::ShapeCache, Path, PathEnum};
+
+/// How far apart (in metres) the end of one segment and the start of the next can be before it counts as a gap
+const GAP_TOLERANCE: f64 = 0.001;
+
+/// A path made by following other paths one after another
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CompositePath {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
+    pub name: String,
+    /// Saved in their own table rather than with the rest of the path, so missing ones are left empty
+    #[serde(default)]
+    pub segments: Vec<PathEnum>,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
+    /// The length of each segment, kept so `point_at` doesn't measure them all every time
+    #[serde(skip)]
+    pub segment_lengths: ShapeCache<Vec<f64>>,
+}
+
+/// A place where one segment of a composite path doesn't end where the next one starts
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gap {
+    /// Index of the segment before the gap
+    pub after_segment: usize,
+    pub distance: f64,
+}
+
+impl IsDefault for CompositePath {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Path for CompositePath {
+    fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        let lengths = self.segment_lengths();
+        let total_length: f64 = lengths.iter().sum();
+
+        let Some(first_segment) = self.segments.first() else {
+            return Vector3d::default();
+        };
+        if total_length <= 0.0 {
+            return first_segment.point_at(&Percentage::from_decimal(0.0));
+        }
+
+        // Each segment gets a share of the percentage the same size as it's share of the length
+        let mut distance = index.value() * total_length;
+        for (segment, length) in self.segments.iter().zip(lengths.iter()) {
+            if distance <= *length {
+                let segment_index = if *length > 0.0 {
+                    distance / length
+                } else {
+                    0.0
+                };
+                return segment.point_at(&Percentage::from_decimal(segment_index.clamp(0.0, 1.0)));
+            }
+            distance -= length;
+        }
+
+        // Floating point error can leave us just past the end of the last segment
+        self.segments[self.segments.len() - 1].point_at(&Percentage::from_decimal(1.0))
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for segment in &self.segments {
+            segment.shape_key().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
+}
+
+impl CompositePath {
+    /// Make a new CompositePath where you do not care about it's name
+    pub fn new(segments: Vec<PathEnum>) -> Self {
+        Self {
+            id: 0,
+            name: String::default(),
+            segments,
+            arc_length: ShapeCache::default(),
+            segment_lengths: ShapeCache::default(),
+        }
+    }
+
+    /// The length of each segment, in order. Measured once, then kept until a segment changes shape
+    pub fn segment_lengths(&self) -> Arc<Vec<f64>> {
+        self.segment_lengths.get(self.shape_key(), || {
+            self.segments
+                .iter()
+                .map(|segment| segment.length())
+                .collect()
+        })
+    }
+
+    /// Finds everywhere that a segment doesn't end where the next one starts
+    pub fn gaps(&self) -> Vec<Gap> {
+        self.segments
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, pair)| {
+                let end = pair[0].point_at(&Percentage::from_decimal(1.0));
+                let start = pair[1].point_at(&Percentage::from_decimal(0.0));
+                let distance = (start - end).norm2().sqrt();
+
+                (distance > GAP_TOLERANCE).then_some(Gap {
+                    after_segment: i,
+                    distance,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::line::Line;
+    use crate::test_util::{eq_vector3d, float_eq};
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    /// An L shape, 3 along x then 1 along y
+    fn l_shape() -> CompositePath {
+        CompositePath::new(vec![
+            PathEnum::Line(Line::new(
+                Vector3d {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                Vector3d {
+                    x: 3.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+            )),
+            PathEnum::Line(Line::new(
+                Vector3d {
+                    x: 3.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                Vector3d {
+                    x: 3.0,
+                    y: 1.0,
+                    z: 0.0,
+                },
+            )),
+        ])
+    }
+
+    #[test]
+    fn test_point_begin() {
+        assert!(eq_vector3d(
+            l_shape().point_at(&Percentage::from_decimal(0.0)),
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_end() {
+        assert!(eq_vector3d(
+            l_shape().point_at(&Percentage::from_decimal(1.0)),
+            Vector3d {
+                x: 3.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_split_by_length() {
+        // The first segment is 3/4 of the length, so half way is still on it
+        assert!(eq_vector3d(
+            l_shape().point_at(&Percentage::from_decimal(0.5)),
+            Vector3d {
+                x: 2.0,
+                y: 0.0,
+                z: 0.0,
+            }
+        ));
+        assert!(eq_vector3d(
+            l_shape().point_at(&Percentage::from_decimal(0.875)),
+            Vector3d {
+                x: 3.0,
+                y: 0.5,
+                z: 0.0,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_segment_lengths_follow_changes() {
+        let mut path = l_shape();
+        assert!(float_eq(path.segment_lengths()[0], 3.0));
+        assert!(float_eq(path.segment_lengths()[1], 1.0));
+
+        // Stretching the second segment moves the split between them
+        *path.segments[1].control_points_mut()[1] = Vector3d::new(3.0, 3.0, 0.0);
+        assert!(float_eq(path.segment_lengths()[1], 3.0));
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.5)),
+            Vector3d::new(3.0, 0.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn test_no_gaps() {
+        assert!(l_shape().gaps().is_empty());
+    }
+
+    #[test]
+    fn test_gaps() {
+        let mut path = l_shape();
+        path.segments.push(PathEnum::Line(Line::new(
+            Vector3d {
+                x: 5.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 6.0,
+                y: 1.0,
+                z: 0.0,
+            },
+        )));
+
+        let gaps = path.gaps();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].after_segment, 1);
+        assert!(float_eq(gaps[0].distance, 2.0));
+    }
+}
@@ -1,8 +1,17 @@
+//! Cubic Bezier curves
+
 use isx::prelude::IsDefault;
 use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
 use vector3d::Vector3d;
 
-use crate::path::{bezier::Bezier, line::Line, Path};
+use crate::path::{
+    arc_length::ArcLengthTable,
+    bezier::Bezier,
+    line::Line,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
 
 impl IsDefault for CubicBezier {
     fn is_default(&self) -> bool {
@@ -10,34 +19,63 @@ impl IsDefault for CubicBezier {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+/// A cubic Bezier curve, leaving `start` towards `handle_1` and arriving at `end` from `handle_2`
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CubicBezier {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
     pub name: String,
     pub start: Vector3d<f64>,
     pub end: Vector3d<f64>,
     pub handle_1: Vector3d<f64>,
     pub handle_2: Vector3d<f64>,
+    /// Curves saved before cubic Beziers followed the standard formula were a blend of two quadratic Beziers.
+    /// This keeps them the same shape they were when they were made
+    pub legacy_blend: bool,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
 }
 
 impl Path for CubicBezier {
     fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
-        // See https://www.desmos.com/calculator/083535c5a3 for an easier to follow version of this,
-        // The short of it is, you find the "index" point between the two end points (treating them as lines) and find the point at "index" along that line
-        let bezier_1 = Bezier::new(self.start, self.handle_1, self.end).point_at(index);
+        if self.legacy_blend {
+            return self.legacy_point_at(index);
+        }
 
-        let bezier_2 = Bezier::new(self.start, self.handle_2, self.end).point_at(index);
+        // The standard cubic Bernstein form, the same as Blender, Vectorworks and SVG use.
+        // handle_1 pulls the curve away from the start and handle_2 pulls it in to the end
+        let t = index.value();
+        let u = 1.0 - t;
 
-        Line::new(bezier_2, bezier_1).point_at(index)
+        self.start * u.powi(3)
+            + self.handle_1 * (3.0 * u.powi(2) * t)
+            + self.handle_2 * (3.0 * u * t.powi(2))
+            + self.end * t.powi(3)
     }
 
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(
+            &[self.start, self.end, self.handle_1, self.handle_2],
+            &[self.legacy_blend as u8 as f64],
+        )
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
 }
 
 impl CubicBezier {
     /// Make a new CubicBezier where you do not care about it's name
-    #[allow(dead_code)]
     pub fn new(
         start: Vector3d<f64>,
         end: Vector3d<f64>,
@@ -45,37 +83,38 @@ impl CubicBezier {
         handle_2: Vector3d<f64>,
     ) -> Self {
         Self {
+            id: 0,
             name: String::default(),
             start,
             end,
             handle_1,
             handle_2,
+            legacy_blend: false,
+            arc_length: ShapeCache::default(),
         }
     }
+
+    /// The shape cubic Beziers used to have: a blend between two quadratic Beziers that share a start and end
+    fn legacy_point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        // See https://www.desmos.com/calculator/083535c5a3 for an easier to follow version of this,
+        // The short of it is, you find the "index" point between the two end points (treating them as lines) and find the point at "index" along that line
+        let bezier_1 = Bezier::new(self.start, self.handle_1, self.end).point_at(index);
+
+        let bezier_2 = Bezier::new(self.start, self.handle_2, self.end).point_at(index);
+
+        Line::new(bezier_2, bezier_1).point_at(index)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use percentage::Percentage;
 
+    use crate::test_util::eq_vector3d;
+
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
 
-    /// Checks if two floating point values are equal, leaving some wiggle room for floating point error
-    fn float_eq(value_1: f64, value_2: f64) -> bool {
-        let diff = value_1 - value_2;
-        diff < 0.000001 && diff > -0.000001
-    }
-
-    /// Checks if two vector3Ds are equal, leaving some wiggle room for floating point error
-    fn eq_vector3d(vector_1: Vector3d<f64>, vector_2: Vector3d<f64>) -> bool {
-        let x_eq = float_eq(vector_1.x, vector_2.x);
-        let y_eq = float_eq(vector_1.y, vector_2.y);
-        let z_eq = float_eq(vector_1.z, vector_2.z);
-
-        x_eq && y_eq && z_eq
-    }
-
     #[test]
     fn test_point_begin() {
         let start = Vector3d {
@@ -155,7 +194,10 @@ mod tests {
             z: 0.0,
         };
 
-        let path = CubicBezier::new(start, end, handle_1, handle_2);
+        let path = CubicBezier {
+            legacy_blend: true,
+            ..CubicBezier::new(start, end, handle_1, handle_2)
+        };
 
         assert!(eq_vector3d(
             path.point_at(&Percentage::from_decimal(0.2)),
@@ -166,4 +208,111 @@ mod tests {
             },
         ))
     }
+
+    #[test]
+    fn test_point_reference_middle() {
+        // Reference value from the SVG path "M 0 0 C 0 1 1 1 1 0"
+        let start = Vector3d {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let end = Vector3d {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let handle_1 = Vector3d {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        };
+        let handle_2 = Vector3d {
+            x: 1.0,
+            y: 1.0,
+            z: 0.0,
+        };
+
+        let path = CubicBezier::new(start, end, handle_1, handle_2);
+
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.5)),
+            Vector3d {
+                x: 0.5,
+                y: 0.75,
+                z: 0.0,
+            },
+        ))
+    }
+
+    #[test]
+    fn test_point_reference_fifth() {
+        let start = Vector3d {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let end = Vector3d {
+            x: 4.0,
+            y: 4.0,
+            z: 0.0,
+        };
+        let handle_1 = Vector3d {
+            x: 0.0,
+            y: 4.0,
+            z: 0.0,
+        };
+        let handle_2 = Vector3d {
+            x: 4.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        let path = CubicBezier::new(start, end, handle_1, handle_2);
+
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.2)),
+            Vector3d {
+                x: 0.416,
+                y: 1.568,
+                z: 0.0,
+            },
+        ))
+    }
+
+    #[test]
+    fn test_point_reference_3d() {
+        let start = Vector3d {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        };
+        let end = Vector3d {
+            x: 4.0,
+            y: -2.0,
+            z: 0.0,
+        };
+        let handle_1 = Vector3d {
+            x: 2.0,
+            y: 5.0,
+            z: 6.0,
+        };
+        let handle_2 = Vector3d {
+            x: -1.0,
+            y: 0.0,
+            z: 2.0,
+        };
+
+        let path = CubicBezier::new(start, end, handle_1, handle_2);
+
+        // 0.343 * start + 0.441 * handle_1 + 0.189 * handle_2 + 0.027 * end
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.3)),
+            Vector3d {
+                x: 1.144,
+                y: 2.837,
+                z: 4.053,
+            },
+        ))
+    }
 }
@@ -0,0 +1,239 @@
+//! Ellipses, and parts of them
+
+use isx::prelude::IsDefault;
+use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
+use trig::Trig;
+use vector3d::Vector3d;
+
+use crate::path::{
+    arc_length::ArcLengthTable,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
+
+/// An ellipse (or part of one) lying flat in the plane facing `normal`.
+/// radius_1 lies along the plane's first axis, which is where angle 0 is, and radius_2 along the second axis at 90°
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Ellipse {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
+    pub name: String,
+    pub centre: Vector3d<f64>,
+    pub radius_1: f64,
+    pub radius_2: f64,
+    pub normal: Vector3d<f64>,
+    /// Angle (in degrees) around the ellipse that the path starts at
+    pub start_angle: f64,
+    /// How far round the ellipse (in degrees) the path goes, negative values go round the other way
+    pub sweep: f64,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
+}
+
+impl IsDefault for Ellipse {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Path for Ellipse {
+    fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        point_on_ellipse(
+            self.centre,
+            self.radius_1,
+            self.radius_2,
+            self.normal,
+            self.start_angle + self.sweep * index.value(),
+        )
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(
+            &[self.centre, self.normal],
+            &[self.radius_1, self.radius_2, self.start_angle, self.sweep],
+        )
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
+}
+
+impl Ellipse {
+    /// Make a new Ellipse where you do not care about it's name
+    pub fn new(
+        centre: Vector3d<f64>,
+        radius_1: f64,
+        radius_2: f64,
+        normal: Vector3d<f64>,
+        start_angle: f64,
+        sweep: f64,
+    ) -> Self {
+        Self {
+            id: 0,
+            name: String::default(),
+            centre,
+            radius_1,
+            radius_2,
+            normal,
+            start_angle,
+            sweep,
+            arc_length: ShapeCache::default(),
+        }
+    }
+}
+
+/// Finds the point `angle` degrees round an ellipse lying flat in the plane facing `normal`
+pub(crate) fn point_on_ellipse(
+    centre: Vector3d<f64>,
+    radius_1: f64,
+    radius_2: f64,
+    normal: Vector3d<f64>,
+    angle: f64,
+) -> Vector3d<f64> {
+    let (axis_1, axis_2) = plane_axes(normal);
+
+    centre + axis_1 * (radius_1 * angle.cosd()) + axis_2 * (radius_2 * angle.sind())
+}
+
+/// Works out two perpendicular directions that lie flat in the plane facing `normal`.
+/// The first axis is kept level where possible: on the floor (normal up) the axes are +x and +y, on a wall facing the audience (normal -y) they are +x and up.
+/// A zero normal is treated as the floor
+pub(crate) fn plane_axes(normal: Vector3d<f64>) -> (Vector3d<f64>, Vector3d<f64>) {
+    let up = Vector3d::new(0.0, 0.0, 1.0);
+
+    let length = normal.norm2().sqrt();
+    let normal = if length < 1e-9 { up } else { normal / length };
+
+    // Level axis in the plane. If the plane is level itself then any level direction works, so use +x
+    let level = up.cross(normal);
+    let level_length = level.norm2().sqrt();
+    let axis_1 = if level_length < 1e-9 {
+        Vector3d::new(1.0, 0.0, 0.0)
+    } else {
+        level / level_length
+    };
+    let axis_2 = normal.cross(axis_1);
+
+    (axis_1, axis_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use percentage::Percentage;
+
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn floor_ellipse() -> Ellipse {
+        Ellipse::new(
+            Vector3d {
+                x: 1.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            2.0,
+            1.0,
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            0.0,
+            360.0,
+        )
+    }
+
+    #[test]
+    fn test_point_begin() {
+        assert!(eq_vector3d(
+            floor_ellipse().point_at(&Percentage::from_decimal(0.0)),
+            Vector3d {
+                x: 3.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_quarter() {
+        assert!(eq_vector3d(
+            floor_ellipse().point_at(&Percentage::from_decimal(0.25)),
+            Vector3d {
+                x: 1.0,
+                y: 2.0,
+                z: 0.0,
+            }
+        ))
+    }
+
+    #[test]
+    fn test_point_end() {
+        // A full sweep ends back where it started
+        assert!(eq_vector3d(
+            floor_ellipse().point_at(&Percentage::from_decimal(1.0)),
+            floor_ellipse().point_at(&Percentage::from_decimal(0.0)),
+        ))
+    }
+
+    #[test]
+    fn test_plane_axes_wall() {
+        let (axis_1, axis_2) = plane_axes(Vector3d {
+            x: 0.0,
+            y: -1.0,
+            z: 0.0,
+        });
+
+        assert!(eq_vector3d(
+            axis_1,
+            Vector3d {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            }
+        ));
+        assert!(eq_vector3d(
+            axis_2,
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_plane_axes_zero_normal() {
+        let (axis_1, axis_2) = plane_axes(Vector3d::default());
+
+        assert!(eq_vector3d(
+            axis_1,
+            Vector3d {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            }
+        ));
+        assert!(eq_vector3d(
+            axis_2,
+            Vector3d {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        ));
+    }
+}
@@ -1,13 +1,26 @@
-use crate::path::Path;
+//! Straight lines
+
+use crate::path::{
+    arc_length::ArcLengthTable,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
 use isx::prelude::IsDefault;
 use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
 use vector3d::Vector3d;
 
-#[derive(Debug, Default, PartialEq, Clone)]
+/// A straight line from `start` to `end`
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Line {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
     pub name: String,
     pub start: Vector3d<f64>,
     pub end: Vector3d<f64>,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
 }
 
 impl IsDefault for Line {
@@ -25,16 +38,29 @@ impl Path for Line {
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(&[self.start, self.end], &[])
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
 }
 
 impl Line {
-    // Make a new Line where you do not care about it's name
-    #[allow(dead_code)]
+    /// Make a new Line where you do not care about it's name
     pub fn new(start: Vector3d<f64>, end: Vector3d<f64>) -> Self {
         Self {
+            id: 0,
             name: String::default(),
             start,
             end,
+            arc_length: ShapeCache::default(),
         }
     }
 }
@@ -1,24 +1,265 @@
+//! The shapes lights can follow. Each kind is in it's own module and they're all gathered up in `PathEnum`
+
+pub mod arc;
+pub mod arc_length;
 pub mod bezier;
+pub mod circle;
+pub mod composite;
 pub mod cubic_bezier;
+pub mod ellipse;
 pub mod line;
+pub mod shape_cache;
+pub mod spline;
 
 use enum_dispatch::enum_dispatch;
-use percentage::PercentageDecimal;
+use percentage::{Percentage, PercentageDecimal};
+use serde::{Deserialize, Serialize};
 use vector3d::Vector3d;
 
-use crate::path::{bezier::Bezier, cubic_bezier::CubicBezier, line::Line};
+use crate::path::{
+    arc::Arc, arc_length::ArcLengthTable, bezier::Bezier, circle::Circle, composite::CompositePath,
+    cubic_bezier::CubicBezier, ellipse::Ellipse, line::Line, shape_cache::ShapeCache,
+    spline::Spline,
+};
 
 #[enum_dispatch]
-#[derive(Debug, Clone, PartialEq)]
+/// Any kind of path. Serialises as the path's fields with a `kind` field added, see `PathEnum::kind`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum PathEnum {
     Line,
     Bezier,
     CubicBezier,
+    Spline,
+    Circle,
+    Arc,
+    Ellipse,
+    CompositePath,
+}
+
+impl PathEnum {
+    /// The name of this kind of path, used to refer to it in the database. It's the same as the variant's name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PathEnum::Line(_) => "Line",
+            PathEnum::Bezier(_) => "Bezier",
+            PathEnum::CubicBezier(_) => "CubicBezier",
+            PathEnum::Spline(_) => "Spline",
+            PathEnum::Circle(_) => "Circle",
+            PathEnum::Arc(_) => "Arc",
+            PathEnum::Ellipse(_) => "Ellipse",
+            PathEnum::CompositePath(_) => "CompositePath",
+        }
+    }
+
+    /// The points that set where this path goes, that can be moved to change it's shape.
+    /// Circles, arcs and ellipses just have their centre and composite paths have none, their segments are edited instead
+    pub fn control_points(&self) -> Vec<Vector3d<f64>> {
+        match self {
+            PathEnum::Line(line) => vec![line.start, line.end],
+            PathEnum::Bezier(bezier) => vec![bezier.start, bezier.midpoint, bezier.end],
+            PathEnum::CubicBezier(cubic_bezier) => vec![
+                cubic_bezier.start,
+                cubic_bezier.handle_1,
+                cubic_bezier.handle_2,
+                cubic_bezier.end,
+            ],
+            PathEnum::Spline(spline) => spline.waypoints.clone(),
+            PathEnum::Circle(circle) => vec![circle.centre],
+            PathEnum::Arc(arc) => vec![arc.centre],
+            PathEnum::Ellipse(ellipse) => vec![ellipse.centre],
+            PathEnum::CompositePath(_) => vec![],
+        }
+    }
+
+    /// The same points as `control_points`, in the same order, so they can be moved
+    pub fn control_points_mut(&mut self) -> Vec<&mut Vector3d<f64>> {
+        match self {
+            PathEnum::Line(line) => vec![&mut line.start, &mut line.end],
+            PathEnum::Bezier(bezier) => {
+                vec![&mut bezier.start, &mut bezier.midpoint, &mut bezier.end]
+            }
+            PathEnum::CubicBezier(cubic_bezier) => vec![
+                &mut cubic_bezier.start,
+                &mut cubic_bezier.handle_1,
+                &mut cubic_bezier.handle_2,
+                &mut cubic_bezier.end,
+            ],
+            PathEnum::Spline(spline) => spline.waypoints.iter_mut().collect(),
+            PathEnum::Circle(circle) => vec![&mut circle.centre],
+            PathEnum::Arc(arc) => vec![&mut arc.centre],
+            PathEnum::Ellipse(ellipse) => vec![&mut ellipse.centre],
+            PathEnum::CompositePath(_) => vec![],
+        }
+    }
+
+    /// Gives this path a new name
+    pub fn with_name(self, name: String) -> Self {
+        match self {
+            PathEnum::Line(line) => PathEnum::Line(Line { name, ..line }),
+            PathEnum::Bezier(bezier) => PathEnum::Bezier(Bezier { name, ..bezier }),
+            PathEnum::CubicBezier(cubic_bezier) => PathEnum::CubicBezier(CubicBezier {
+                name,
+                ..cubic_bezier
+            }),
+            PathEnum::Spline(spline) => PathEnum::Spline(Spline { name, ..spline }),
+            PathEnum::Circle(circle) => PathEnum::Circle(Circle { name, ..circle }),
+            PathEnum::Arc(arc) => PathEnum::Arc(Arc { name, ..arc }),
+            PathEnum::Ellipse(ellipse) => PathEnum::Ellipse(Ellipse { name, ..ellipse }),
+            PathEnum::CompositePath(composite_path) => PathEnum::CompositePath(CompositePath {
+                name,
+                ..composite_path
+            }),
+        }
+    }
+
+    /// Gives this path a new database id, used once it has been saved
+    pub fn with_id(self, id: i64) -> Self {
+        match self {
+            PathEnum::Line(line) => PathEnum::Line(Line { id, ..line }),
+            PathEnum::Bezier(bezier) => PathEnum::Bezier(Bezier { id, ..bezier }),
+            PathEnum::CubicBezier(cubic_bezier) => {
+                PathEnum::CubicBezier(CubicBezier { id, ..cubic_bezier })
+            }
+            PathEnum::Spline(spline) => PathEnum::Spline(Spline { id, ..spline }),
+            PathEnum::Circle(circle) => PathEnum::Circle(Circle { id, ..circle }),
+            PathEnum::Arc(arc) => PathEnum::Arc(Arc { id, ..arc }),
+            PathEnum::Ellipse(ellipse) => PathEnum::Ellipse(Ellipse { id, ..ellipse }),
+            PathEnum::CompositePath(composite_path) => PathEnum::CompositePath(CompositePath {
+                id,
+                ..composite_path
+            }),
+        }
+    }
 }
 
+/// Something a light can follow, measured in metres
 #[enum_dispatch(PathEnum)]
 pub trait Path: Clone + PartialEq {
     /// Calculate the 3D coordinate at index% in to this path
     fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64>;
+    /// What the user called this path
     fn name(&self) -> String;
+    /// The database id of this path, 0 if it has not been saved yet
+    fn id(&self) -> i64;
+
+    /// A number that changes whenever this path changes shape, so things worked out from it's shape can be kept until then.
+    /// See `shape_cache::shape_key`
+    fn shape_key(&self) -> u64;
+    /// Where this path keeps it's `ArcLengthTable`
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable>;
+
+    /// How far along this path each percentage is. Measured the first time it's needed, then kept until the path changes shape
+    fn arc_length_table(&self) -> std::sync::Arc<ArcLengthTable> {
+        self.arc_length_cache()
+            .get(self.shape_key(), || ArcLengthTable::new(self))
+    }
+
+    /// The length of this path
+    fn length(&self) -> f64 {
+        self.arc_length_table().length()
+    }
+
+    /// True if this path ends where it starts, like a full circle, so something going past the end carries on from the start
+    fn is_closed(&self) -> bool {
+        let start = self.point_at(&Percentage::from_decimal(0.0));
+        let end = self.point_at(&Percentage::from_decimal(1.0));
+        // Within a millimetre
+        (end - start).norm2() < 0.001_f64.powi(2)
+    }
+
+    /// `count` + 1 points spread along this path by percentage, including both ends. Good enough for drawing it with straight lines
+    fn points(&self, count: usize) -> Vec<Vector3d<f64>> {
+        (0..=count)
+            .map(|i| self.point_at(&Percentage::from_decimal(i as f64 / count.max(1) as f64)))
+            .collect()
+    }
+
+    /// Calculate the 3D coordinate that is `distance` along this path, measured along the curve
+    fn point_at_distance(&self, distance: f64) -> Vector3d<f64> {
+        self.point_at(&self.arc_length_table().percentage_at_distance(distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_control_points_move_path() {
+        let mut path = PathEnum::CubicBezier(CubicBezier {
+            start: Vector3d::new(0.0, 0.0, 0.0),
+            handle_1: Vector3d::new(1.0, 1.0, 0.0),
+            handle_2: Vector3d::new(2.0, 1.0, 0.0),
+            end: Vector3d::new(3.0, 0.0, 0.0),
+            ..Default::default()
+        });
+        assert_eq!(path.control_points()[3], Vector3d::new(3.0, 0.0, 0.0));
+
+        *path.control_points_mut()[3] = Vector3d::new(3.0, 0.0, 2.0);
+        assert_eq!(path.control_points()[3], Vector3d::new(3.0, 0.0, 2.0));
+        assert_eq!(
+            path.point_at(&Percentage::from_decimal(1.0)),
+            Vector3d::new(3.0, 0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_length_follows_changes() {
+        let mut path = PathEnum::Line(Line::new(
+            Vector3d::new(0.0, 0.0, 0.0),
+            Vector3d::new(1.0, 0.0, 0.0),
+        ));
+        assert!((path.length() - 1.0).abs() < 1e-9);
+
+        // Moved without going through anything that could tell the kept table it's out of date
+        *path.control_points_mut()[1] = Vector3d::new(3.0, 0.0, 0.0);
+        assert!((path.length() - 3.0).abs() < 1e-9);
+        assert_eq!(path.point_at_distance(2.0), Vector3d::new(2.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_control_points_match() {
+        let mut paths = [
+            PathEnum::Line(Line::default()),
+            PathEnum::Bezier(Bezier::default()),
+            PathEnum::Spline(Spline {
+                waypoints: vec![Vector3d::new(0.0, 0.0, 0.0); 5],
+                ..Default::default()
+            }),
+            PathEnum::Ellipse(Ellipse::default()),
+            PathEnum::CompositePath(CompositePath::default()),
+        ];
+
+        for path in &mut paths {
+            assert_eq!(path.control_points().len(), path.control_points_mut().len());
+        }
+    }
+
+    #[test]
+    fn test_is_closed() {
+        let circle = Circle::new(Vector3d::default(), 2.0, Vector3d::new(0.0, 0.0, 1.0), 0.0);
+        assert!(circle.is_closed());
+        let half_circle = Arc::new(
+            Vector3d::default(),
+            2.0,
+            Vector3d::new(0.0, 0.0, 1.0),
+            0.0,
+            180.0,
+        );
+        assert!(!half_circle.is_closed());
+        assert!(!Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(1.0, 0.0, 0.0)).is_closed());
+    }
+
+    #[test]
+    fn test_points() {
+        let line = Line::new(Vector3d::new(0.0, 0.0, 0.0), Vector3d::new(4.0, 0.0, 0.0));
+        assert_eq!(
+            line.points(4),
+            (0..=4)
+                .map(|x| Vector3d::new(x as f64, 0.0, 0.0))
+                .collect::<Vec<_>>()
+        );
+    }
 }
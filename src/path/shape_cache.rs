@@ -0,0 +1,102 @@
+//! Keeping things worked out from a path's shape with the path, so they're only worked out again when it changes
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use vector3d::Vector3d;
+
+/// Something worked out from a path's shape, kept with the path.
+/// The fields of a path can be changed directly, so it's worked out again whenever the path's `shape_key` changes.
+/// It's ignored when comparing paths and isn't saved with them
+pub struct ShapeCache<T>(Mutex<Option<(u64, Arc<T>)>>);
+
+impl<T> ShapeCache<T> {
+    /// The value for the shape with `key`, using `work_out` if it hasn't been worked out for that shape yet
+    pub fn get(&self, key: u64, work_out: impl FnOnce() -> T) -> Arc<T> {
+        if let Some((cached_key, value)) = &*self.lock() {
+            if *cached_key == key {
+                return value.clone();
+            }
+        }
+
+        // Worked out without holding the lock, as it can need the caches of other paths
+        let value = Arc::new(work_out());
+        *self.lock() = Some((key, value.clone()));
+        value
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(u64, Arc<T>)>> {
+        // Nothing is left half written if a thread panics while holding the lock
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for ShapeCache<T> {
+    fn default() -> Self {
+        Self(Mutex::new(None))
+    }
+}
+
+impl<T> Clone for ShapeCache<T> {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.lock().clone()))
+    }
+}
+
+impl<T> PartialEq for ShapeCache<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> fmt::Debug for ShapeCache<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ShapeCache")
+    }
+}
+
+/// Makes a number that changes whenever any of the numbers that set a path's shape do
+pub fn shape_key(points: &[Vector3d<f64>], values: &[f64]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for point in points {
+        for value in [point.x, point.y, point.z] {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    for value in values {
+        value.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_worked_out_once_per_shape() {
+        let cache: ShapeCache<f64> = ShapeCache::default();
+        let worked_out = Cell::new(0);
+        let work_out = |value: f64| {
+            worked_out.set(worked_out.get() + 1);
+            value
+        };
+
+        let key = shape_key(&[Vector3d::new(1.0, 2.0, 3.0)], &[4.0]);
+        assert_eq!(*cache.get(key, || work_out(1.0)), 1.0);
+        assert_eq!(*cache.get(key, || work_out(2.0)), 1.0);
+        assert_eq!(worked_out.get(), 1);
+
+        let moved = shape_key(&[Vector3d::new(1.0, 2.0, 3.5)], &[4.0]);
+        assert_ne!(key, moved);
+        assert_eq!(*cache.get(moved, || work_out(2.0)), 2.0);
+        assert_eq!(worked_out.get(), 2);
+    }
+}
@@ -0,0 +1,247 @@
+//! Smooth curves through a list of waypoints
+
+use isx::prelude::IsDefault;
+use percentage::PercentageDecimal;
+use serde::{Deserialize, Serialize};
+use vector3d::Vector3d;
+
+use crate::path::{
+    arc_length::ArcLengthTable,
+    shape_cache::{shape_key, ShapeCache},
+    Path,
+};
+
+/// A smooth curve that passes through every one of it's waypoints, in order.
+/// Uses a centripetal Catmull-Rom spline so it doesn't loop or overshoot when waypoints are unevenly spaced
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Spline {
+    /// The row this path is stored in, 0 if it has not been saved yet
+    pub id: i64,
+    pub name: String,
+    pub waypoints: Vec<Vector3d<f64>>,
+    /// Kept so the path is only measured once, see `Path::arc_length_table`
+    #[serde(skip)]
+    pub arc_length: ShapeCache<ArcLengthTable>,
+}
+
+impl IsDefault for Spline {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Path for Spline {
+    fn point_at(&self, index: &PercentageDecimal) -> Vector3d<f64> {
+        match self.waypoints.len() {
+            0 => return Vector3d::default(),
+            1 => return self.waypoints[0],
+            _ => {}
+        }
+
+        // Each pair of waypoints gets an equal share of the path
+        let segments = self.waypoints.len() - 1;
+        let position = index.value() * segments as f64;
+        let segment = (position.floor() as usize).min(segments - 1);
+        let segment_index = position - segment as f64;
+
+        let (p0, p1, p2, p3) = self.segment_points(segment);
+        catmull_rom(p0, p1, p2, p3, segment_index)
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn shape_key(&self) -> u64 {
+        shape_key(&self.waypoints, &[])
+    }
+
+    fn arc_length_cache(&self) -> &ShapeCache<ArcLengthTable> {
+        &self.arc_length
+    }
+}
+
+impl Spline {
+    /// Make a new Spline where you do not care about it's name
+    pub fn new(waypoints: Vec<Vector3d<f64>>) -> Self {
+        Self {
+            id: 0,
+            name: String::default(),
+            waypoints,
+            arc_length: ShapeCache::default(),
+        }
+    }
+
+    /// Gets the four points that control the curve between waypoint `segment` and the one after it.
+    /// The first and last segments don't have a waypoint on one side so we mirror the neighbouring one
+    fn segment_points(
+        &self,
+        segment: usize,
+    ) -> (Vector3d<f64>, Vector3d<f64>, Vector3d<f64>, Vector3d<f64>) {
+        let p1 = self.waypoints[segment];
+        let p2 = self.waypoints[segment + 1];
+
+        let p0 = match segment.checked_sub(1) {
+            Some(previous) => self.waypoints[previous],
+            None => p1 * 2.0 - p2,
+        };
+        let p3 = match self.waypoints.get(segment + 2) {
+            Some(next) => *next,
+            None => p2 * 2.0 - p1,
+        };
+
+        (p0, p1, p2, p3)
+    }
+}
+
+/// Finds the point `index` (0 to 1) of the way between p1 and p2 on a centripetal Catmull-Rom spline.
+/// See https://en.wikipedia.org/wiki/Centripetal_Catmull%E2%80%93Rom_spline for how this works
+fn catmull_rom(
+    p0: Vector3d<f64>,
+    p1: Vector3d<f64>,
+    p2: Vector3d<f64>,
+    p3: Vector3d<f64>,
+    index: f64,
+) -> Vector3d<f64> {
+    // The "knots", spaced by the square root of the distance between points which is what makes it centripetal
+    let t0 = 0.0;
+    let t1 = t0 + knot_interval(p0, p1);
+    let t2 = t1 + knot_interval(p1, p2);
+    let t3 = t2 + knot_interval(p2, p3);
+    let t = t1 + (t2 - t1) * index;
+
+    let a1 = p0 * ((t1 - t) / (t1 - t0)) + p1 * ((t - t0) / (t1 - t0));
+    let a2 = p1 * ((t2 - t) / (t2 - t1)) + p2 * ((t - t1) / (t2 - t1));
+    let a3 = p2 * ((t3 - t) / (t3 - t2)) + p3 * ((t - t2) / (t3 - t2));
+
+    let b1 = a1 * ((t2 - t) / (t2 - t0)) + a2 * ((t - t0) / (t2 - t0));
+    let b2 = a2 * ((t3 - t) / (t3 - t1)) + a3 * ((t - t1) / (t3 - t1));
+
+    b1 * ((t2 - t) / (t2 - t1)) + b2 * ((t - t1) / (t2 - t1))
+}
+
+/// The gap between two knots. Waypoints in the same place would give a gap of 0 and divide by zero, so they get a gap of 1 instead
+fn knot_interval(start: Vector3d<f64>, end: Vector3d<f64>) -> f64 {
+    let interval = (end - start).norm2().sqrt().sqrt();
+
+    if interval < 1e-9 {
+        1.0
+    } else {
+        interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use percentage::Percentage;
+
+    use crate::test_util::eq_vector3d;
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn waypoints() -> Vec<Vector3d<f64>> {
+        vec![
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 1.0,
+                y: 2.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 4.0,
+                y: 2.0,
+                z: 1.0,
+            },
+            Vector3d {
+                x: 5.0,
+                y: 0.0,
+                z: 1.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_point_begin() {
+        let path = Spline::new(waypoints());
+
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.0)),
+            waypoints()[0]
+        ))
+    }
+
+    #[test]
+    fn test_point_end() {
+        let path = Spline::new(waypoints());
+
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(1.0)),
+            waypoints()[3]
+        ))
+    }
+
+    #[test]
+    fn test_passes_through_waypoints() {
+        let path = Spline::new(waypoints());
+
+        // With 4 waypoints there are 3 segments, so the middle waypoints are a third of the way along each
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(1.0 / 3.0)),
+            waypoints()[1]
+        ));
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(2.0 / 3.0)),
+            waypoints()[2]
+        ));
+    }
+
+    #[test]
+    fn test_straight_waypoints() {
+        // Evenly spaced waypoints in a line should give a straight line
+        let path = Spline::new(vec![
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector3d {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+            Vector3d {
+                x: 2.0,
+                y: 2.0,
+                z: 2.0,
+            },
+        ]);
+
+        assert!(eq_vector3d(
+            path.point_at(&Percentage::from_decimal(0.25)),
+            Vector3d {
+                x: 0.5,
+                y: 0.5,
+                z: 0.5,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_repeated_waypoint() {
+        let mut repeated = waypoints();
+        repeated.insert(1, repeated[1]);
+        let path = Spline::new(repeated);
+
+        let point = path.point_at(&Percentage::from_decimal(0.3));
+        assert!(point.x.is_finite() && point.y.is_finite() && point.z.is_finite());
+    }
+}
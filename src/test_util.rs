@@ -0,0 +1,18 @@
+//! Helpers shared by the tests of different modules
+
+use vector3d::Vector3d;
+
+/// Checks if two floating point values are equal, leaving some wiggle room for floating point error
+pub(crate) fn float_eq(value_1: f64, value_2: f64) -> bool {
+    let diff = value_1 - value_2;
+    diff < 0.000001 && diff > -0.000001
+}
+
+/// Checks if two vector3Ds are equal, leaving some wiggle room for floating point error
+pub(crate) fn eq_vector3d(vector_1: Vector3d<f64>, vector_2: Vector3d<f64>) -> bool {
+    let x_eq = float_eq(vector_1.x, vector_2.x);
+    let y_eq = float_eq(vector_1.y, vector_2.y);
+    let z_eq = float_eq(vector_1.z, vector_2.z);
+
+    x_eq && y_eq && z_eq
+}